@@ -0,0 +1,16 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // The toolchain is part of `BUILD_ID`, which libraries loaded with `--load-lints` are checked
+    // against.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("-V")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=RUSTC_VERSION={}", version.trim());
+    println!("cargo:rerun-if-changed=build.rs");
+}
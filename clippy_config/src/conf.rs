@@ -1,7 +1,7 @@
 use crate::ClippyConfiguration;
 use crate::types::{
-    DisallowedPath, DisallowedPathWithoutReplacement, InherentImplLintScope, MacroMatcher, MatchLintBehaviour,
-    PubUnderscoreFieldsBehaviour, Rename, SourceItemOrdering, SourceItemOrderingCategory,
+    CustomLint, DisallowedPath, DisallowedPathWithoutReplacement, InherentImplLintScope, MacroMatcher,
+    MatchLintBehaviour, PubUnderscoreFieldsBehaviour, Rename, SourceItemOrdering, SourceItemOrderingCategory,
     SourceItemOrderingModuleItemGroupings, SourceItemOrderingModuleItemKind, SourceItemOrderingTraitAssocItemKind,
     SourceItemOrderingTraitAssocItemKinds, SourceItemOrderingWithinModuleItemGroupings,
 };
@@ -55,6 +55,52 @@ const DEFAULT_ALLOWED_IDENTS_BELOW_MIN_CHARS: &[&str] = &["i", "j", "x", "y", "z
 const DEFAULT_ALLOWED_PREFIXES: &[&str] = &["to", "as", "into", "from", "try_into", "try_from"];
 const DEFAULT_ALLOWED_TRAITS_WITH_RENAMED_PARAMS: &[&str] =
     &["core::convert::From", "core::convert::TryFrom", "core::str::FromStr"];
+const DEFAULT_SECRET_NAMES: &[&str] = &[
+    "access_key",
+    "api_key",
+    "apikey",
+    "auth_token",
+    "client_secret",
+    "passwd",
+    "password",
+    "private_key",
+    "secret",
+    "secret_key",
+    "token",
+];
+const DEFAULT_TAINT_SOURCES: &[&str] = &[
+    "std::env::args",
+    "std::env::args_os",
+    "std::env::var",
+    "std::env::var_os",
+    "std::io::Stdin::lines",
+    "std::io::Stdin::read_line",
+];
+const DEFAULT_SQL_INJECTION_SINKS: &[&str] = &[
+    "diesel::sql_query",
+    "postgres::Client::batch_execute",
+    "postgres::Client::execute",
+    "postgres::Client::query",
+    "rusqlite::Connection::execute",
+    "rusqlite::Connection::execute_batch",
+    "rusqlite::Connection::prepare",
+    "rusqlite::Connection::query_row",
+    "sqlx::query",
+    "sqlx::query_as",
+    "sqlx::query_scalar",
+    "tokio_postgres::Client::batch_execute",
+    "tokio_postgres::Client::execute",
+    "tokio_postgres::Client::query",
+];
+const DEFAULT_JOIN_HANDLE_TYPES: &[&str] = &["std::thread::JoinHandle"];
+const DEFAULT_SPAWN_BLOCKING_FUNCTIONS: &[&str] = &[
+    "async_std::task::spawn_blocking",
+    "blocking::unblock",
+    "smol::unblock",
+    "std::thread::spawn",
+    "tokio::task::block_in_place",
+    "tokio::task::spawn_blocking",
+];
 const DEFAULT_MODULE_ITEM_ORDERING_GROUPS: &[(&str, &[SourceItemOrderingModuleItemKind])] = {
     #[allow(clippy::enum_glob_use)] // Very local glob use for legibility.
     use SourceItemOrderingModuleItemKind::*;
@@ -450,6 +496,10 @@ define_Conf! {
     /// The list of unicode scripts allowed to be used in the scope.
     #[lints(disallowed_script_idents)]
     allowed_scripts: Vec<String> = vec!["Latin".to_string()],
+    /// Literal values which are not considered to be secrets, e.g. placeholders. A `*` matches
+    /// any sequence of characters, so `"<*>"` allows any value enclosed in angle brackets.
+    #[lints(hardcoded_secret)]
+    allowed_secrets: Vec<String> = Vec::new(),
     /// List of path segments allowed to have wildcard imports.
     ///
     /// #### Example
@@ -541,6 +591,11 @@ define_Conf! {
     /// Use the Disallowed Names lint instead
     #[conf_deprecated("Please use `disallowed-names` instead", disallowed_names)]
     blacklisted_names: Vec<String> = Vec::new(),
+    /// Additional blocking functions which shouldn't be called in an async context. These are
+    /// checked in addition to the built-in list of blocking functions from the standard library.
+    #[disallowed_paths_allow_replacements = true]
+    #[lints(blocking_in_async)]
+    blocking_functions: Vec<DisallowedPath> = Vec::new(),
     /// For internal testing only, ignores the current `publish` settings in the Cargo manifest.
     #[lints(cargo_common_metadata)]
     cargo_ignore_publish: bool = false,
@@ -568,7 +623,13 @@ define_Conf! {
     #[lints(inconsistent_struct_constructor)]
     check_inconsistent_struct_field_initializers: bool = false,
     /// Whether to also run the listed lints on private items.
-    #[lints(missing_errors_doc, missing_panics_doc, missing_safety_doc, unnecessary_safety_doc)]
+    #[lints(
+        missing_errors_doc,
+        missing_panics_doc,
+        missing_safety_doc,
+        unnecessary_panics_doc,
+        unnecessary_safety_doc,
+    )]
     check_private_items: bool = false,
     /// The maximum cognitive complexity a function can have
     #[lints(cognitive_complexity)]
@@ -576,6 +637,34 @@ define_Conf! {
     /// The minimum digits a const float literal must have to supress the `excessive_precicion` lint
     #[lints(excessive_precision)]
     const_literal_digits_threshold: usize = 30,
+    /// Lints defined in the configuration, written as `[[custom-lint]]` tables. Each lint reports the
+    /// expressions matching a pattern written like a Rust expression, in which `$name` is a metavariable
+    /// matching any expression and `$_` matches any expression without naming it. A metavariable used
+    /// more than once has to match equal expressions.
+    ///
+    /// **Fields:**
+    /// - `name` (required): the name of the lint, used as `clippy::name` in lint attributes
+    /// - `message` (required): the message of the lint
+    /// - `pattern` (required): the pattern to lint, e.g. `"$x.lock().unwrap()"`
+    /// - `level` (optional, `"warn"` by default): the default level of the lint, one of `"allow"`,
+    ///   `"warn"` or `"deny"`
+    /// - `where` (optional): the types the expressions matched by metavariables must have, ignoring
+    ///   references, e.g. `{ x = "crate::SharedState" }`. Types can be paths to structs, enums or
+    ///   unions, primitive types, slices, tuples or references to these types, without generic arguments
+    /// - `replacement` (optional): the suggested replacement, in which the metavariables are replaced by
+    ///   the expressions they matched
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// [[custom-lint]]
+    /// name = "shared_state_lock_unwrap"
+    /// message = "locking `SharedState` can't fail"
+    /// pattern = "$x.lock().unwrap()"
+    /// where = { x = "crate::SharedState" }
+    /// replacement = "$x.lock_infallible()"
+    /// ```
+    custom_lint: Vec<CustomLint> = Vec::new(),
     /// DEPRECATED LINT: CYCLOMATIC_COMPLEXITY.
     ///
     /// Use the Cognitive Complexity lint instead.
@@ -669,6 +758,11 @@ define_Conf! {
     /// Sets the scope ("crate", "file", or "module") in which duplicate inherent `impl` blocks for the same type are linted.
     #[lints(multiple_inherent_impl)]
     inherent_impl_lint_scope: InherentImplLintScope = InherentImplLintScope::Crate,
+    /// The paths of handle types which should be joined instead of being dropped, e.g. the handles
+    /// of async tasks. The value `".."` can be used as part of the list to append to the default
+    /// list instead of replacing it.
+    #[lints(detached_join_handles)]
+    join_handle_types: Vec<String> = DEFAULT_JOIN_HANDLE_TYPES.iter().map(ToString::to_string).collect(),
     /// A list of paths to types that should be ignored as overly large `Err`-variants in a
     /// `Result` returned from a function
     #[lints(result_large_err)]
@@ -689,6 +783,11 @@ define_Conf! {
     /// The lower bound for linting decimal literals
     #[lints(decimal_literal_representation)]
     literal_representation_threshold: u64 = 16384,
+    /// Paths of dynamic libraries to load additional lints from, relative to the directory of the
+    /// configuration file. Each library has to export a `register_lints(&mut LintStore, &'static Conf)`
+    /// function and its build ID with `clippy_config::export_build_id!()`, and has to be built against
+    /// the same Clippy commit and toolchain.
+    load_lints: Vec<String> = Vec::new(),
     /// Whether the matches should be considered by the lint, and whether there should
     /// be filtering for common types.
     #[lints(manual_let_else)]
@@ -720,6 +819,10 @@ define_Conf! {
     /// crate. For example, `pub(crate)` items.
     #[lints(missing_docs_in_private_items)]
     missing_docs_in_crate_items: bool = false,
+    /// Whether to also lint functions which don't panic themselves, but call a function of the same
+    /// crate which may panic.
+    #[lints(missing_panics_doc)]
+    missing_panics_doc_transitive: bool = false,
     /// The named groupings of different source item kinds within modules.
     #[lints(arbitrary_source_item_ordering)]
     module_item_order_groupings: SourceItemOrderingModuleItemGroupings = DEFAULT_MODULE_ITEM_ORDERING_GROUPS.into(),
@@ -823,6 +926,12 @@ define_Conf! {
     /// Whether the type itself in a struct or enum should be replaced with `Self` when encountering recursive types.
     #[lints(use_self)]
     recursive_self_in_type_definitions: bool = true,
+    /// The names of constants, variables and fields which hold secrets. A name matches an
+    /// identifier if its words appear consecutively in the identifier, ignoring case, so
+    /// `api_key` matches `GITHUB_API_KEY` and `apiKey`. The value `".."` can be used as part of
+    /// the list to append to the default list instead of replacing it.
+    #[lints(hardcoded_secret)]
+    secret_names: Vec<String> = DEFAULT_SECRET_NAMES.iter().map(ToString::to_string).collect(),
     /// Whether to lint only if it's multiline.
     #[lints(semicolon_inside_block)]
     semicolon_inside_block_ignore_singleline: bool = false,
@@ -835,6 +944,16 @@ define_Conf! {
     /// Which kind of elements should be ordered internally, possible values being `enum`, `impl`, `module`, `struct`, `trait`.
     #[lints(arbitrary_source_item_ordering)]
     source_item_ordering: SourceItemOrdering = DEFAULT_SOURCE_ITEM_ORDERING.into(),
+    /// The paths of functions which run a closure on a thread where blocking is allowed, e.g.
+    /// `tokio::task::spawn_blocking`. The value `".."` can be used as part of the list to append to
+    /// the default list instead of replacing it.
+    #[lints(blocking_in_async)]
+    spawn_blocking_functions: Vec<String> = DEFAULT_SPAWN_BLOCKING_FUNCTIONS.iter().map(ToString::to_string).collect(),
+    /// The paths of functions which execute an SQL query passed as their first argument after the
+    /// receiver. The value `".."` can be used as part of the list to append to the default list
+    /// instead of replacing it.
+    #[lints(sql_injection)]
+    sql_injection_sinks: Vec<String> = DEFAULT_SQL_INJECTION_SINKS.iter().map(ToString::to_string).collect(),
     /// The maximum allowed stack size for functions in bytes
     #[lints(large_stack_frames)]
     stack_size_threshold: u64 = 512_000,
@@ -855,6 +974,15 @@ define_Conf! {
     /// if no suggestion can be made.
     #[lints(indexing_slicing)]
     suppress_restriction_lint_in_const: bool = false,
+    /// The paths of functions which turn an untrusted value into one which is safe to use, e.g. by
+    /// escaping or validating it.
+    #[lints(sql_injection)]
+    taint_sanitizers: Vec<String> = Vec::new(),
+    /// The paths of functions returning untrusted values, e.g. the data of a network request. The
+    /// value `".."` can be used as part of the list to append to the default list instead of
+    /// replacing it.
+    #[lints(sql_injection)]
+    taint_sources: Vec<String> = DEFAULT_TAINT_SOURCES.iter().map(ToString::to_string).collect(),
     /// The maximum size of objects (in bytes) that will be linted. Larger objects are ok on the heap
     #[lints(boxed_local, useless_vec)]
     too_large_for_stack: u64 = 200,
@@ -961,6 +1089,14 @@ fn deserialize(file: &SourceFile) -> TryConf {
                 &mut conf.conf.allow_renamed_params_for,
                 DEFAULT_ALLOWED_TRAITS_WITH_RENAMED_PARAMS,
             );
+            extend_vec_if_indicator_present(&mut conf.conf.secret_names, DEFAULT_SECRET_NAMES);
+            extend_vec_if_indicator_present(&mut conf.conf.taint_sources, DEFAULT_TAINT_SOURCES);
+            extend_vec_if_indicator_present(&mut conf.conf.sql_injection_sinks, DEFAULT_SQL_INJECTION_SINKS);
+            extend_vec_if_indicator_present(&mut conf.conf.join_handle_types, DEFAULT_JOIN_HANDLE_TYPES);
+            extend_vec_if_indicator_present(
+                &mut conf.conf.spawn_blocking_functions,
+                DEFAULT_SPAWN_BLOCKING_FUNCTIONS,
+            );
 
             // Confirms that the user has not accidentally configured ordering requirements for groups that
             // aren't configured.
@@ -27,3 +27,27 @@ pub mod types;
 
 pub use conf::{Conf, get_configuration_metadata, lookup_conf_file, sanitize_explanation};
 pub use metadata::ClippyConfiguration;
+
+use std::ffi::CStr;
+
+/// Identifies the Clippy version and toolchain this crate was built with. Libraries loaded with
+/// `--load-lints` share `Conf` and the lint store with `clippy-driver`, so they're only loaded when
+/// their build ID, exported with [`export_build_id!`], is the same as the driver's.
+pub const BUILD_ID: &CStr = match CStr::from_bytes_with_nul(
+    concat!("clippy ", env!("CARGO_PKG_VERSION"), " (", env!("RUSTC_VERSION"), ")\0").as_bytes(),
+) {
+    Ok(id) => id,
+    Err(_) => panic!("the build ID contains a nul byte"),
+};
+
+/// Exports the [`BUILD_ID`] of the `clippy_config` crate a library loaded with `--load-lints` is
+/// built with, as the `clippy_build_id` function.
+#[macro_export]
+macro_rules! export_build_id {
+    () => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn clippy_build_id() -> *const ::std::ffi::c_char {
+            $crate::BUILD_ID.as_ptr()
+        }
+    };
+}
@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use std::fmt;
+use std::fmt::{self, Write as _};
 
 #[derive(Debug, Clone, Default)]
 pub struct ClippyConfiguration {
@@ -22,15 +22,23 @@ impl fmt::Display for ClippyConfiguration {
 
 impl ClippyConfiguration {
     pub fn to_markdown_paragraph(&self) -> String {
-        format!(
-            "## `{}`\n{}\n\n**Default Value:** `{}`\n\n---\n**Affected lints:**\n{}\n\n",
+        let mut paragraph = format!(
+            "## `{}`\n{}\n\n**Default Value:** `{}`\n\n",
             self.name,
             self.doc.lines().map(|x| x.strip_prefix(' ').unwrap_or(x)).join("\n"),
             self.default,
-            self.lints.iter().format_with("\n", |name, f| f(&format_args!(
-                "* [`{name}`](https://rust-lang.github.io/rust-clippy/master/index.html#{name})"
-            ))),
-        )
+        );
+        // Options such as `custom-lint` define lints instead of configuring existing ones.
+        if !self.lints.is_empty() {
+            let _ = write!(
+                paragraph,
+                "---\n**Affected lints:**\n{}\n\n",
+                self.lints.iter().format_with("\n", |name, f| f(&format_args!(
+                    "* [`{name}`](https://rust-lang.github.io/rust-clippy/master/index.html#{name})"
+                ))),
+            );
+        }
+        paragraph
     }
 
     pub fn to_markdown_link(&self) -> String {
@@ -1,3 +1,4 @@
+use clippy_utils::expr_pattern::{ExprPattern, fill_template};
 use clippy_utils::paths::{PathNS, find_crates, lookup_path};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::{Applicability, Diag};
@@ -8,7 +9,7 @@ use rustc_middle::ty::TyCtxt;
 use rustc_span::{Span, Symbol};
 use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize, ser};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 #[derive(Debug, Deserialize)]
@@ -671,6 +672,96 @@ impl Serialize for SourceItemOrderingWithinModuleItemGroupings {
     }
 }
 
+/// A lint defined in the configuration with the `custom-lint` configuration.
+#[derive(Debug)]
+pub struct CustomLint {
+    pub name: String,
+    pub level: CustomLintLevel,
+    pub message: String,
+    pub pattern: ExprPattern,
+    /// The metavariables which have to match an expression of the given type.
+    pub types: Vec<(String, String)>,
+    pub replacement: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomLintLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+/// Helper struct for deserializing a [`CustomLint`].
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCustomLint {
+    name: String,
+    #[serde(default)]
+    level: CustomLintLevel,
+    message: String,
+    pattern: String,
+    #[serde(default)]
+    r#where: BTreeMap<String, String>,
+    replacement: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for CustomLint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RawCustomLint::deserialize(deserializer)?;
+        let name = raw.name;
+        if !name.starts_with(|c: char| c.is_ascii_lowercase())
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(de::Error::custom(format!(
+                "invalid custom lint name `{name}`, expected a snake case identifier"
+            )));
+        }
+        let pattern = ExprPattern::parse(&raw.pattern)
+            .map_err(|e| de::Error::custom(format!("invalid pattern for custom lint `{name}`: {e}")))?;
+        let unknown_metavar =
+            |var: &str| de::Error::custom(format!("custom lint `{name}` uses `${var}` which isn't in its pattern"));
+
+        let mut types = Vec::with_capacity(raw.r#where.len());
+        for (var, ty) in raw.r#where {
+            let var = var.strip_prefix('$').unwrap_or(&var);
+            if !pattern.metavars().any(|v| v == var) {
+                return Err(unknown_metavar(var));
+            }
+            types.push((var.to_owned(), ty));
+        }
+        if let Some(replacement) = &raw.replacement
+            && let Some(var) = replacement_metavars(replacement).find(|&var| !pattern.metavars().any(|v| v == var))
+        {
+            return Err(unknown_metavar(var));
+        }
+
+        Ok(Self {
+            name,
+            level: raw.level,
+            message: raw.message,
+            pattern,
+            types,
+            replacement: raw.replacement,
+        })
+    }
+}
+
+fn replacement_metavars(replacement: &str) -> impl Iterator<Item = &str> {
+    let mut vars = Vec::new();
+    fill_template(replacement, |var| {
+        vars.push(var);
+        Some(String::new())
+    });
+    vars.into_iter()
+}
+
 // these impls are never actually called but are used by the various config options that default to
 // empty lists
 macro_rules! unimplemented_serialize {
@@ -691,6 +782,7 @@ macro_rules! unimplemented_serialize {
 unimplemented_serialize! {
     Rename,
     MacroMatcher,
+    CustomLint,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -0,0 +1,346 @@
+use crate::new_lint::{Context, LintData, Pass, add_lint, get_lint_declaration, to_camel_case, write_file};
+use crate::utils::{Version, cargo_cmd};
+use itertools::Itertools;
+use std::fmt::{self, Write as _};
+use std::{fs, io};
+
+/// The nodes `#[clippy::author]` can be put on, the name of their binding in the generated code,
+/// the `LateLintPass` method checking them and the type of the node.
+const ROOTS: &[(&str, &str, &str)] = &[
+    ("expr", "check_expr", "Expr"),
+    ("stmt", "check_stmt", "Stmt"),
+    ("arm", "check_arm", "Arm"),
+    ("pat", "check_pat", "Pat"),
+    ("item", "check_item", "Item"),
+    ("impl_item", "check_impl_item", "ImplItem"),
+    ("trait_item", "check_trait_item", "TraitItem"),
+];
+
+/// Imports for the paths used by the code generated by `#[clippy::author]` which aren't in
+/// `rustc_hir`.
+const IMPORTS: &[(&str, &str)] = &[
+    ("FloatTy::", "rustc_ast::FloatTy"),
+    ("IntTy::", "rustc_ast::IntTy"),
+    ("LitFloatType::", "rustc_ast::LitFloatType"),
+    ("LitIntType::", "rustc_ast::LitIntType"),
+    ("LitKind::", "rustc_ast::LitKind"),
+    ("Pu128(", "rustc_data_structures::packed::Pu128"),
+    ("UintTy::", "rustc_ast::UintTy"),
+    ("higher::", "clippy_utils::higher"),
+    ("paths::", "clippy_utils::paths"),
+    ("sym::", "clippy_utils::sym"),
+    (".is_diag_item(", "clippy_utils::res::MaybeDef"),
+    (".is_lang_item(", "clippy_utils::res::MaybeDef"),
+    (".res(", "clippy_utils::res::MaybeQPath"),
+];
+
+/// A chain of conditions printed by `#[clippy::author]`.
+struct Chain<'a> {
+    /// The index of the root in `ROOTS`
+    root: usize,
+    /// The conditions, starting with `if` and ending before the body of the `if`
+    conditions: Vec<&'a str>,
+}
+
+/// Creates a new lint from the output of `#[clippy::author]` on the nodes of the file at `path`
+/// and uses the file as its test.
+///
+/// # Errors
+///
+/// This function errors out if Clippy couldn't be run on the file, or if the files couldn't be
+/// created or written to.
+pub fn create(clippy_version: Version, path: &str, name: &str, category: &str, edition: &str) -> io::Result<()> {
+    let source = fs::read_to_string(path).context(format!("reading `{path}`"))?;
+    if !source.contains("clippy::author") {
+        return Err(io::Error::other(format!(
+            "`{path}` doesn't contain a `#[clippy::author]` attribute"
+        )));
+    }
+
+    let output = cargo_cmd()
+        .args(["run", "--bin", "clippy-driver", "--"])
+        .args(["-L", "./target/debug"])
+        .args(["-Z", "no-codegen"])
+        .args(["--edition", edition])
+        .arg(path)
+        .env("RUSTC_ICE", "0")
+        .output()
+        .context("running `clippy-driver`")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        return Err(io::Error::other(format!("`clippy-driver` failed on `{path}`")));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let chains = parse_chains(&stdout)?;
+
+    let lint = LintData {
+        clippy_version,
+        pass: Pass::Late,
+        name,
+        category,
+        ty: None,
+    };
+
+    let lint_path = format!("clippy_lints/src/{name}.rs");
+    write_file(&lint_path, get_lint_file_contents(&lint, &chains)).context("Unable to create lint implementation")?;
+    println!("Generated lint file: `{lint_path}`");
+
+    let test_path = format!("tests/ui/{name}.rs");
+    write_file(&test_path, get_test_file_contents(&source, name))
+        .context("Unable to create a test for the new lint")?;
+    println!("Generated test file: `{test_path}`");
+
+    add_lint(&lint, false).context("Unable to add lint to clippy_lints/src/lib.rs")?;
+
+    println!(
+        "\n\
+        NOTE: Fill in the lint description and message, and run `TESTNAME={name} cargo uibless`\n\
+        to generate the expected output of the test"
+    );
+
+    Ok(())
+}
+
+fn parse_chains(output: &str) -> io::Result<Vec<Chain<'_>>> {
+    let mut chains = Vec::new();
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if !line.starts_with("if ") {
+            continue;
+        }
+        let root = find_root(line)
+            .ok_or_else(|| io::Error::other(format!("unexpected `#[clippy::author]` output: `{line}`")))?;
+        let mut conditions = vec![line];
+        conditions.extend(lines.by_ref().take_while(|&line| line != "{"));
+        chains.push(Chain { root, conditions });
+    }
+    if chains.is_empty() {
+        return Err(io::Error::other("`#[clippy::author]` didn't generate any code"));
+    }
+    Ok(chains)
+}
+
+/// Finds the node the first condition of a chain checks, e.g. `expr` in
+/// `if let ExprKind::Call(func, args) = expr.kind`.
+fn find_root(first_line: &str) -> Option<usize> {
+    let checked = first_line.rsplit_once(" = ").map_or(first_line, |(_, checked)| checked);
+    checked
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find_map(|ident| ROOTS.iter().position(|&(root, ..)| root == ident))
+}
+
+fn get_lint_file_contents(lint: &LintData<'_>, chains: &[Chain<'_>]) -> String {
+    let name_upper = lint.name.to_uppercase();
+    let name_camel = to_camel_case(lint.name);
+
+    let mut hir_items: Vec<&str> = Vec::new();
+    let mut imports = vec![
+        "clippy_utils::diagnostics::span_lint",
+        "rustc_lint::{LateContext, LateLintPass}",
+        "rustc_session::declare_lint_pass",
+    ];
+    for chain in chains {
+        hir_items.push(ROOTS[chain.root].2);
+        for condition in &chain.conditions {
+            for (prefix, import) in IMPORTS {
+                if condition.contains(prefix) {
+                    imports.push(import);
+                }
+            }
+            for (i, _) in condition.match_indices("::") {
+                let path = &condition[..i];
+                let start = path
+                    .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+                    .map_or(0, |start| start + 1);
+                let ident = &path[start..];
+                if ident.starts_with(char::is_uppercase)
+                    && !IMPORTS
+                        .iter()
+                        .any(|(prefix, _)| prefix.strip_suffix("::") == Some(ident))
+                    && !path[..start].ends_with("::")
+                {
+                    hir_items.push(ident);
+                }
+            }
+        }
+    }
+    hir_items.sort_unstable();
+    hir_items.dedup();
+    let hir_import = match &*hir_items {
+        [item] => format!("rustc_hir::{item}"),
+        items => format!("rustc_hir::{{{}}}", items.join(", ")),
+    };
+    imports.push(&hir_import);
+    imports.sort_unstable();
+    imports.dedup();
+
+    let mut result = String::new();
+    for group in imports.chunk_by(|a, b| import_module(a) == import_module(b)) {
+        if let [import] = group {
+            let _: fmt::Result = writeln!(result, "use {import};");
+        } else {
+            let items = group.iter().map(|import| &import[import_module(import).len() + 2..]);
+            let _: fmt::Result = writeln!(result, "use {}::{{{}}};", import_module(group[0]), items.format(", "));
+        }
+    }
+    let _: fmt::Result = write!(
+        result,
+        "\n{}\n\ndeclare_lint_pass!({name_camel} => [{name_upper}]);\n\nimpl<'tcx> LateLintPass<'tcx> for {name_camel} {{",
+        get_lint_declaration(lint.clippy_version, &name_upper, lint.category),
+    );
+
+    for (i, &(root, method, ty)) in ROOTS.iter().enumerate() {
+        let mut chains = chains.iter().filter(|chain| chain.root == i).peekable();
+        if chains.peek().is_none() {
+            continue;
+        }
+        let _: fmt::Result = write!(
+            result,
+            "\n    fn {method}(&mut self, cx: &LateContext<'tcx>, {root}: &'tcx {ty}<'_>) {{"
+        );
+        let mut first = true;
+        for chain in chains {
+            if !first {
+                result.push('\n');
+            }
+            first = false;
+            for condition in &chain.conditions {
+                let _: fmt::Result = write!(result, "\n        {}", shorten_field_patterns(condition));
+            }
+            let _: fmt::Result = write!(
+                result,
+                "\n        {{\n            span_lint(cx, {name_upper}, {root}.span, \"default lint description\");\n        }}"
+            );
+        }
+        result.push_str("\n    }\n");
+    }
+    result.push_str("}\n");
+    result
+}
+
+fn import_module(import: &str) -> &str {
+    import.rsplit_once("::").map_or(import, |(module, _)| module)
+}
+
+/// Replaces field patterns like `ident: ident` with their shorthand `ident`.
+fn shorten_field_patterns(condition: &str) -> String {
+    let mut result = String::with_capacity(condition.len());
+    let mut rest = condition;
+    while let Some(i) = rest.find(": ") {
+        let (start, end) = rest.split_at(i);
+        let field = &start[start
+            .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+            .map_or(0, |i| i + 1)..];
+        let end = &end[2..];
+        result.push_str(start);
+        if !field.is_empty()
+            && let Some(after) = end.strip_prefix(field)
+            && !after.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        {
+            rest = after;
+        } else {
+            result.push_str(": ");
+            rest = end;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Turns the file `#[clippy::author]` was run on into a test for the new lint.
+fn get_test_file_contents(source: &str, lint_name: &str) -> String {
+    let mut result = String::new();
+    let mut lines = source.lines().peekable();
+    while let Some(line) = lines.next_if(|line| line.starts_with("//@")) {
+        if line.trim_start_matches("//@").trim() != "check-pass" {
+            result.push_str(line);
+            result.push('\n');
+        }
+    }
+    let _: fmt::Result = writeln!(result, "#![warn(clippy::{lint_name})]");
+    if lines.peek().is_some_and(|line| !line.is_empty()) {
+        result.push('\n');
+    }
+
+    let mut marked = false;
+    for line in lines {
+        if line.trim_start().starts_with("#![clippy::author") {
+            continue;
+        }
+        if line.trim() == "#[clippy::author]" {
+            marked = true;
+            continue;
+        }
+        let (line, inline) = match line.split_once("#[clippy::author] ") {
+            Some((start, end)) => (format!("{start}{end}"), true),
+            None => (line.to_owned(), false),
+        };
+        result.push_str(&line);
+        result.push('\n');
+        if marked || inline {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let _: fmt::Result = writeln!(result, "{indent}//~^ {lint_name}");
+            marked = false;
+        }
+    }
+    result
+}
+
+#[test]
+fn test_find_root() {
+    let root = |line| find_root(line).map(|i| ROOTS[i].0);
+    assert_eq!(root("if let ExprKind::Call(func, args) = expr.kind"), Some("expr"));
+    assert_eq!(
+        root("if let Some(higher::If { cond, then, r#else }) = higher::If::hir(expr)"),
+        Some("expr")
+    );
+    assert_eq!(
+        root("if let ImplItemKind::Fn(_, body) = impl_item.kind"),
+        Some("impl_item")
+    );
+    assert_eq!(root("if let PatKind::Wild = pat.kind"), Some("pat"));
+    assert_eq!(root("if let Some(guard) = arm.guard"), Some("arm"));
+    assert_eq!(root("if let ExprKind::Call(func, args) = e.kind"), None);
+}
+
+#[test]
+fn test_shorten_field_patterns() {
+    assert_eq!(
+        shorten_field_patterns("if let ItemKind::Fn { ident: ident, body: body1, .. } = item.kind"),
+        "if let ItemKind::Fn { ident, body: body1, .. } = item.kind"
+    );
+    assert_eq!(
+        shorten_field_patterns("    && let ExprKind::Closure { fn_decl: fn_decl_x, .. } = e.kind"),
+        "    && let ExprKind::Closure { fn_decl: fn_decl_x, .. } = e.kind"
+    );
+}
+
+#[test]
+fn test_test_file_contents() {
+    let source = "\
+//@ check-pass
+//@edition:2018
+
+#![allow(clippy::no_effect)]
+
+fn main() {
+    #[clippy::author]
+    let x = 1;
+    let _ = |#[clippy::author] y: u8| y;
+}
+";
+    let expected = "\
+//@edition:2018
+#![warn(clippy::foo)]
+
+#![allow(clippy::no_effect)]
+
+fn main() {
+    let x = 1;
+    //~^ foo
+    let _ = |y: u8| y;
+    //~^ foo
+}
+";
+    assert_eq!(get_test_file_contents(source, "foo"), expected);
+}
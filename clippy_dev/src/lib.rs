@@ -24,10 +24,12 @@ extern crate rustc_driver;
 extern crate rustc_lexer;
 extern crate rustc_literal_escaper;
 
+pub mod author;
 pub mod deprecate_lint;
 pub mod dogfood;
 pub mod fmt;
 pub mod lint;
+pub mod move_lint;
 pub mod new_lint;
 pub mod release;
 pub mod rename_lint;
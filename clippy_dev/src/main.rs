@@ -4,8 +4,8 @@
 
 use clap::{Args, Parser, Subcommand};
 use clippy_dev::{
-    ClippyInfo, UpdateMode, deprecate_lint, dogfood, fmt, lint, new_lint, new_parse_cx, release, rename_lint, serve,
-    setup, sync, update_lints,
+    ClippyInfo, UpdateMode, author, deprecate_lint, dogfood, fmt, lint, move_lint, new_lint, new_parse_cx, release,
+    rename_lint, serve, setup, sync, update_lints,
 };
 use std::env;
 
@@ -34,7 +34,8 @@ fn main() {
             category,
             r#type,
             msrv,
-        } => match new_lint::create(clippy.version, pass, &name, &category, r#type.as_deref(), msrv) {
+            config,
+        } => match new_lint::create(clippy.version, pass, &name, &category, r#type.as_deref(), msrv, &config) {
             Ok(()) => new_parse_cx(|cx| update_lints::update(cx, UpdateMode::Change)),
             Err(e) => eprintln!("Unable to create lint: {e}"),
         },
@@ -73,6 +74,15 @@ fn main() {
             RemoveSubcommand::VscodeTasks => setup::vscode::remove_tasks(),
         },
         DevCommand::Serve { port, lint } => serve::run(port, lint),
+        DevCommand::Author {
+            path,
+            name,
+            category,
+            edition,
+        } => match author::create(clippy.version, &path, &name, &category, &edition) {
+            Ok(()) => new_parse_cx(|cx| update_lints::update(cx, UpdateMode::Change)),
+            Err(e) => eprintln!("Unable to create lint: {e}"),
+        },
         DevCommand::Lint { path, edition, args } => lint::run(&path, &edition, args.iter()),
         DevCommand::RenameLint {
             old_name,
@@ -87,6 +97,9 @@ fn main() {
                 uplift,
             );
         }),
+        DevCommand::MoveLint { name, category, module } => new_parse_cx(|cx| {
+            move_lint::move_lint(cx, &name, category.as_deref(), module.as_deref());
+        }),
         DevCommand::Deprecate { name, reason } => {
             new_parse_cx(|cx| deprecate_lint::deprecate(cx, clippy.version, &name, &reason));
         },
@@ -196,12 +209,50 @@ enum DevCommand {
         #[arg(long)]
         /// Add MSRV config code to the lint
         msrv: bool,
+        #[arg(long, value_name = "NAME[:TYPE:DEFAULT]", value_parser = new_lint::ConfigOption::parse)]
+        /// Add a configuration option read by the lint, ex: `max_depth:u64:5`. An existing option
+        /// can be reused by passing only its name. Can be passed multiple times
+        config: Vec<new_lint::ConfigOption>,
+    },
+    /// Create a new lint from the code `#[clippy::author]` generates for a file and run
+    /// `cargo dev update_lints`
+    ///
+    /// The file is used as the test of the lint, and the nodes marked with `#[clippy::author]`
+    /// are the ones the lint is expected to trigger on.
+    Author {
+        /// The path to the file containing `#[clippy::author]` attributes
+        path: String,
+        #[arg(short, long, value_parser = lint_name)]
+        /// Name of the new lint in snake case, ex: `fn_too_long`
+        name: String,
+        #[arg(
+            short,
+            long,
+            value_parser = [
+                "style",
+                "correctness",
+                "suspicious",
+                "complexity",
+                "perf",
+                "pedantic",
+                "restriction",
+                "nursery",
+            ],
+            default_value = "nursery",
+        )]
+        /// What category the lint belongs to
+        category: String,
+        /// The Rust edition to use
+        #[arg(long, default_value = "2024")]
+        edition: String,
     },
     /// Support for setting up your personal development environment
     Setup(SetupCommand),
     /// Support for removing changes done by the setup command
     Remove(RemoveCommand),
     /// Launch a local 'ALL the Clippy Lints' website in a browser
+    ///
+    /// The website is regenerated and reloaded when the lint sources change
     Serve {
         #[arg(short, long, default_value = "8000")]
         /// Local port for the http server
@@ -253,6 +304,35 @@ enum DevCommand {
         /// This lint will be uplifted into rustc
         uplift: bool,
     },
+    #[command(name = "move_lint")]
+    /// Move a lint to a different category or module
+    MoveLint {
+        /// The name of the lint to move
+        #[arg(value_parser = lint_name)]
+        name: String,
+        #[arg(
+            short,
+            long,
+            required_unless_present = "module",
+            value_parser = [
+                "style",
+                "correctness",
+                "suspicious",
+                "complexity",
+                "perf",
+                "pedantic",
+                "restriction",
+                "cargo",
+                "nursery",
+            ],
+        )]
+        /// The category to move the lint to
+        category: Option<String>,
+        #[arg(short, long)]
+        /// The module to move the lint's code to, ex: `casts`. Use `crate` to move it to its own
+        /// top-level module
+        module: Option<String>,
+    },
     /// Deprecate the given lint
     Deprecate {
         /// The name of the lint to deprecate
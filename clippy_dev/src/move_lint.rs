@@ -0,0 +1,602 @@
+use crate::parse::cursor::{self, Capture, Cursor};
+use crate::parse::{Lint, ParseCx};
+use crate::update_lints::generate_lint_files;
+use crate::utils::{
+    ErrAction, FileUpdater, UpdateMode, UpdateStatus, expect_action, try_rename_file, walk_dir_no_dot_or_target,
+};
+use core::range::Range;
+use rustc_lexer::TokenKind;
+use std::path::{Path, PathBuf};
+
+/// Runs the `move_lint` command.
+///
+/// This does the following:
+/// * Changes the lint's category in its declaration and adds an entry to the changelog.
+/// * Moves the lint's pass into a different module. The declaration is placed in the target
+///   module's `mod.rs` with the lint pass in a `pub(crate)` submodule, the same way as
+///   `operators::arithmetic_side_effects`. Moving to `crate` turns the lint back into a top-level
+///   module.
+/// * Updates all paths to the lint and its pass in the lint crate.
+///
+/// # Panics
+/// Panics for the following conditions:
+/// * If a file path could not read from or then written to
+/// * If `name` doesn't name an existing lint.
+/// * If the lint's pass can't be moved on its own.
+pub fn move_lint<'cx>(cx: ParseCx<'cx>, name: &'cx str, category: Option<&str>, module: Option<&str>) {
+    let mut updater = FileUpdater::default();
+    let lints = cx.find_lint_decls();
+    let (deprecated_lints, renamed_lints) = cx.read_deprecated_lints();
+
+    let Ok(lint_idx) = lints.binary_search_by(|x| x.name.cmp(name)) else {
+        panic!("could not find lint `{name}`");
+    };
+    let lint = &lints[lint_idx];
+
+    let category = category.filter(|&category| {
+        if category == lint.group {
+            println!("`{name}` is already in `{category}`");
+        }
+        category != lint.group
+    });
+    let module = module.map(|m| m.trim_start_matches("crate::")).filter(|&module| {
+        // The module containing the lint pass's module.
+        let parent = if lint.path.file_name().is_some_and(|x| x == "mod.rs") {
+            lint.module
+        } else {
+            lint.module.rsplit_once("::").map_or("", |x| x.0)
+        };
+        if parent == module || (parent.is_empty() && module == "crate") {
+            println!("`{name}` is already in the `{module}` module");
+        }
+        parent != module && !(parent.is_empty() && module == "crate")
+    });
+    if category.is_none() && module.is_none() {
+        return;
+    }
+
+    if let Some(category) = category {
+        updater.update_file(&lint.path, &mut |_, src, dst| {
+            dst.push_str(&src[..lint.declaration_range.start]);
+            dst.push_str(&set_category(&src[lint.declaration_range], category));
+            dst.push_str(&src[lint.declaration_range.end..]);
+            UpdateStatus::Changed
+        });
+        updater.update_file("CHANGELOG.md", &mut |_, src, dst| {
+            add_changelog_entry(src, dst, name, lint.group, category)
+        });
+    }
+
+    let mut lints = if let Some(module) = module {
+        // The declaration range may have been changed by the category edit.
+        let lints = cx.find_lint_decls();
+        let lint = &lints[lint_idx];
+        move_pass(&mut updater, &lints, lint, module);
+        cx.find_lint_decls()
+    } else {
+        lints
+    };
+    lints.sort_by(|lhs, rhs| lhs.name.cmp(rhs.name));
+    generate_lint_files(UpdateMode::Change, &lints, &deprecated_lints, &renamed_lints);
+
+    if let Some(category) = category {
+        println!("Moved `clippy::{name}` to `{category}`");
+    }
+    if let Some(module) = module {
+        println!("Moved the code of `clippy::{name}` to `{module}`");
+        println!("All code referencing the old path has been updated");
+        println!("Make sure to inspect the results as some things may have been missed");
+        println!("note: `cargo dev fmt` should be run to reorder the module declarations");
+    }
+    println!("note: `cargo test` and `cargo uibless` should be run to check the results");
+}
+
+/// Replaces the group of the lint declaration in `decl`.
+fn set_category(decl: &str, category: &str) -> String {
+    #[allow(clippy::enum_glob_use)]
+    use cursor::Pat::*;
+
+    let mut cursor = Cursor::new(decl);
+    let mut captures = [Capture::EMPTY; 2];
+    assert!(
+        cursor.find_pat(CloseBracket)
+            && cursor.match_all(
+                &[Ident("pub"), CaptureIdent, Comma, AnyComment, CaptureIdent],
+                &mut captures
+            ),
+        "malformed lint declaration"
+    );
+    let group = captures[1];
+    let mut res = String::with_capacity(decl.len());
+    res.push_str(&decl[..group.pos as usize]);
+    res.push_str(category);
+    res.push_str(&decl[(group.pos + group.len) as usize..]);
+    res
+}
+
+/// Adds an entry to the "Moves and Deprecations" section of the unreleased changes.
+fn add_changelog_entry(src: &str, dst: &mut String, name: &str, from: &str, to: &str) -> UpdateStatus {
+    let Some(section_start) = src.find("\n## Unreleased") else {
+        println!(
+            "warning: couldn't find the unreleased section of `CHANGELOG.md`, it will need to be updated manually"
+        );
+        return UpdateStatus::Unchanged;
+    };
+    let section_start = section_start + 1;
+    let section_end = src[section_start + 1..]
+        .find("\n## ")
+        .map_or(src.len(), |i| section_start + i + 2);
+    let section = &src[section_start..section_end];
+    let entry = format!("* Moved [`{name}`] from `{from}` to `{to}`\n");
+
+    if let Some(sub_start) = section.find("### Moves and Deprecations\n") {
+        let sub_start = section_start + sub_start;
+        let sub_end = src[sub_start..section_end]
+            .find("\n### ")
+            .map_or(section_end, |i| sub_start + i + 1);
+        let insert_at = sub_start + src[sub_start..sub_end].trim_end().len() + 1;
+        dst.push_str(&src[..insert_at]);
+        dst.push_str(&entry);
+        dst.push_str(&src[insert_at..]);
+    } else {
+        dst.push_str(&src[..section_end]);
+        if !dst.ends_with("\n\n") {
+            dst.push('\n');
+        }
+        dst.push_str("### Moves and Deprecations\n\n");
+        dst.push_str(&entry);
+        dst.push('\n');
+        dst.push_str(&src[section_end..]);
+    }
+    UpdateStatus::Changed
+}
+
+/// Moves the lint's declaration and pass to the given module.
+#[expect(clippy::too_many_lines)]
+fn move_pass(updater: &mut FileUpdater, lints: &[Lint<'_>], lint: &Lint<'_>, module: &str) {
+    let name_upper = lint.name.to_ascii_uppercase();
+    let use_decl = format!("use super::{name_upper};\n");
+    let crate_src = lint.path.components().take(2).collect::<PathBuf>();
+
+    // Find the file containing the lint pass. This is either the file containing the declaration
+    // or, for lints declared in a `mod.rs`, a submodule named after the lint which imports it.
+    let (pass_path, old_pass_mod) = if lint.path.file_name().is_some_and(|x| x == "mod.rs") {
+        let path = lint.path.with_file_name(format!("{}.rs", lint.name));
+        let contents = expect_action(std::fs::read_to_string(&path), ErrAction::Read, &path);
+        assert!(
+            contents.contains(&use_decl),
+            "`{}` doesn't import `{name_upper}` from its parent, only lints with their own submodule can be moved between modules",
+            path.display(),
+        );
+        (path, format!("{}::{}", lint.module, lint.name))
+    } else {
+        assert!(
+            lint.path.file_name().is_some_and(|x| x != "lib.rs")
+                && lints.iter().filter(|l| l.path == lint.path).count() == 1,
+            "`{}` declares multiple lints, only lints with their own lint pass can be moved between modules",
+            lint.path.display(),
+        );
+        (lint.path.clone(), lint.module.to_owned())
+    };
+    let pass_name = pass_path.file_stem().unwrap().to_str().unwrap().to_owned();
+
+    let (new_pass_path, new_mod_file, new_pass_mod, new_decl_mod) = if module == "crate" {
+        (
+            crate_src.join(format!("{pass_name}.rs")),
+            None,
+            pass_name.clone(),
+            pass_name.clone(),
+        )
+    } else {
+        let dir = module.split("::").fold(crate_src.clone(), |path, x| path.join(x));
+        let mod_file = dir.join("mod.rs");
+        assert!(
+            mod_file.exists(),
+            "`{}` doesn't exist, lints can only be moved into existing directory modules",
+            mod_file.display()
+        );
+        (
+            dir.join(format!("{pass_name}.rs")),
+            Some(mod_file),
+            format!("{module}::{pass_name}"),
+            module.to_owned(),
+        )
+    };
+    assert!(
+        !new_pass_path.exists(),
+        "`{}` already exists, the lint can't be moved there",
+        new_pass_path.display()
+    );
+
+    // Cut out the declaration.
+    let mut decl = String::new();
+    updater.update_file(&lint.path, &mut |_, src, dst| {
+        decl.push_str(&src[lint.declaration_range]);
+        remove_range(src, dst, lint.declaration_range);
+        UpdateStatus::Changed
+    });
+
+    // Place the declaration in its new home and update the pass to refer to it.
+    if let Some(mod_file) = &new_mod_file {
+        updater.update_file(&pass_path, &mut |_, src, dst| {
+            if !src.contains(&use_decl) {
+                dst.push_str(&use_decl);
+            }
+            dst.push_str(src);
+            UpdateStatus::Changed
+        });
+        updater.update_file(mod_file, &mut |_, src, dst| {
+            let pos = last_decl_end(src).unwrap_or_else(|| use_block_end(src));
+            let (head, tail) = src.split_at(pos);
+            dst.push_str(head.trim_end());
+            dst.push_str("\n\n");
+            dst.push_str(&decl);
+            dst.push('\n');
+            if !tail.trim_start().is_empty() {
+                dst.push('\n');
+                dst.push_str(tail.trim_start());
+            }
+            add_mod_decl(dst, &pass_name);
+            UpdateStatus::Changed
+        });
+    } else {
+        updater.update_file(&pass_path, &mut |_, src, dst| {
+            let src = src.replacen(&use_decl, "", 1);
+            let pos = use_block_end(&src);
+            let (head, tail) = src.split_at(pos);
+            if !head.trim_end().is_empty() {
+                dst.push_str(head.trim_end());
+                dst.push_str("\n\n");
+            }
+            dst.push_str(&decl);
+            dst.push_str("\n\n");
+            dst.push_str(tail.trim_start());
+            UpdateStatus::Changed
+        });
+    }
+    if pass_path != lint.path {
+        // The pass was a submodule of the module containing the declaration.
+        updater.update_file(&lint.path, &mut |_, src, dst| remove_mod_decl(src, dst, &pass_name));
+    }
+    assert!(
+        try_rename_file(&pass_path, &new_pass_path),
+        "error moving `{}` to `{}`",
+        pass_path.display(),
+        new_pass_path.display()
+    );
+
+    let root = crate_src.join("lib.rs");
+    let mut update_fn = path_update_fn(
+        &old_pass_mod,
+        &new_pass_mod,
+        lint.module,
+        &new_decl_mod,
+        &name_upper,
+        &root,
+    );
+    for e in walk_dir_no_dot_or_target(&crate_src) {
+        let e = expect_action(e, ErrAction::Read, &crate_src);
+        if e.path().as_os_str().as_encoded_bytes().ends_with(b".rs") {
+            updater.update_file(e.path(), &mut update_fn);
+        }
+    }
+}
+
+/// Copies `src` to `dst` leaving out the given range and one of the blank lines surrounding it.
+fn remove_range(src: &str, dst: &mut String, range: Range<usize>) {
+    let mut start = &src[..range.start];
+    if start.ends_with("\n\n") {
+        start = &start[..start.len() - 1];
+    }
+    let mut end = &src[range.end..];
+    if end.starts_with("\n\n") {
+        end = &end[1..];
+    }
+    dst.push_str(start);
+    dst.push_str(end);
+}
+
+/// Finds the end of the `use` items at the start of a file.
+fn use_block_end(src: &str) -> usize {
+    let mut cursor = Cursor::new(src);
+    let mut end = 0;
+    while cursor.match_pat(cursor::Pat::AnyComment) && cursor.match_pat(cursor::Pat::Ident("use")) {
+        if !cursor.find_pat(cursor::Pat::Semi) {
+            break;
+        }
+        end = cursor.pos() as usize;
+    }
+    end
+}
+
+/// Finds the end of the last lint declaration in a file.
+fn last_decl_end(src: &str) -> Option<usize> {
+    let mut cursor = Cursor::new(src);
+    let mut end = None;
+    while cursor.find_ident("declare_clippy_lint").is_some() {
+        if cursor.match_all(&[cursor::Pat::Bang, cursor::Pat::OpenBrace], &mut [])
+            && cursor.find_pat(cursor::Pat::CloseBrace)
+        {
+            end = Some(cursor.pos() as usize);
+        }
+    }
+    end
+}
+
+/// Adds a `pub(crate) mod` declaration after the existing module declarations.
+fn add_mod_decl(dst: &mut String, name: &str) {
+    let mut cursor = Cursor::new(dst);
+    let mut end = None;
+    let mut is_pub = false;
+    loop {
+        let vis = cursor.match_all(
+            &[
+                cursor::Pat::AnyComment,
+                cursor::Pat::Ident("pub"),
+                cursor::Pat::OpenParen,
+                cursor::Pat::Ident("crate"),
+                cursor::Pat::CloseParen,
+            ],
+            &mut [],
+        );
+        if cursor.match_all(
+            &[cursor::Pat::Ident("mod"), cursor::Pat::CaptureIdent, cursor::Pat::Semi],
+            &mut [Capture::EMPTY],
+        ) {
+            end = Some(cursor.pos() as usize);
+            is_pub = vis;
+        } else {
+            break;
+        }
+    }
+    let decl = format!("pub(crate) mod {name};\n");
+    match end {
+        Some(end) if is_pub => dst.insert_str(end + 1, &decl),
+        Some(end) => dst.insert_str(end + 1, &format!("\n{decl}")),
+        None => dst.insert_str(0, &format!("{decl}\n")),
+    }
+}
+
+/// Removes the declaration of the module `name`.
+fn remove_mod_decl(src: &str, dst: &mut String, name: &str) -> UpdateStatus {
+    for (i, line) in src.split_inclusive('\n').enumerate() {
+        let decl = line.trim_end().strip_prefix("pub(crate) ").unwrap_or(line.trim_end());
+        if decl.strip_prefix("mod ").and_then(|x| x.strip_suffix(';')) == Some(name) {
+            let start = src.split_inclusive('\n').take(i).map(str::len).sum::<usize>();
+            remove_range(src, dst, start..start + line.len());
+            return UpdateStatus::Changed;
+        }
+    }
+    UpdateStatus::Unchanged
+}
+
+/// Creates a function which rewrites paths to the lint pass module and the lint declaration.
+///
+/// * `crate::{old_pass}::Item` becomes `crate::{new_pass}::Item`.
+/// * `crate::{old_decl}::LINT_NAME` becomes `crate::{new_decl}::LINT_NAME`.
+///
+/// Within the crate root the same is done for paths without the `crate::` prefix.
+fn path_update_fn<'a>(
+    old_pass: &'a str,
+    new_pass: &'a str,
+    old_decl: &'a str,
+    new_decl: &'a str,
+    name_upper: &'a str,
+    root: &'a Path,
+) -> impl use<'a> + FnMut(&Path, &str, &mut String) -> UpdateStatus {
+    let old_pass: Vec<_> = old_pass.split("::").collect();
+    let old_decl: Vec<_> = old_decl.split("::").collect();
+    move |path, src, dst| {
+        let at_root = path == root;
+        let mut copy_pos = 0;
+        let mut changed = false;
+        let mut cursor = Cursor::new(src);
+        let mut capture = [Capture::EMPTY];
+        loop {
+            match cursor.peek() {
+                TokenKind::Eof => break,
+                TokenKind::Ident => {
+                    let start = cursor.pos();
+                    let text = cursor.peek_text();
+                    let mut segments = Vec::new();
+                    if text != "crate" {
+                        if !at_root || src[..start as usize].trim_end().ends_with("::") {
+                            cursor.step();
+                            continue;
+                        }
+                        segments.push(Capture {
+                            pos: start,
+                            len: cursor.peek_len(),
+                        });
+                    }
+                    cursor.step();
+                    while cursor.match_all(&[cursor::Pat::DoubleColon, cursor::Pat::CaptureIdent], &mut capture) {
+                        segments.push(capture[0]);
+                    }
+                    let texts: Vec<_> = segments.iter().map(|&x| cursor.get_text(x)).collect();
+
+                    let replace = if let Some(rest) = texts.strip_prefix(&*old_decl)
+                        && rest.first() == Some(&name_upper)
+                    {
+                        Some((old_decl.len(), new_decl))
+                    } else if let Some(rest) = texts.strip_prefix(&*old_pass)
+                        && !rest.is_empty()
+                    {
+                        Some((old_pass.len(), new_pass))
+                    } else {
+                        None
+                    };
+                    if let Some((len, replacement)) = replace {
+                        let last = segments[len - 1];
+                        dst.push_str(&src[copy_pos..segments[0].pos as usize]);
+                        dst.push_str(replacement);
+                        copy_pos = (last.pos + last.len) as usize;
+                        changed = true;
+                    }
+                },
+                _ => cursor.step(),
+            }
+        }
+        dst.push_str(&src[copy_pos..]);
+        UpdateStatus::from_changed(changed)
+    }
+}
+
+#[test]
+fn test_set_category() {
+    let decl = "declare_clippy_lint! {
+    /// ### What it does
+    #[clippy::version = \"1.90.0\"]
+    pub SOME_LINT,
+    // Pending more testing
+    nursery,
+    \"default lint description\"
+}";
+    assert_eq!(
+        set_category(decl, "pedantic"),
+        decl.replace("    nursery,", "    pedantic,")
+    );
+}
+
+#[test]
+fn test_add_changelog_entry() {
+    let changelog = "# Changelog
+
+## Unreleased / Beta / In Rust Nightly
+
+### New Lints
+
+* Added [`new_lint`]
+
+## Rust 1.90
+
+### Moves and Deprecations
+
+* Moved [`old_lint`] from `style` to `complexity`
+";
+    let mut dst = String::new();
+    assert!(add_changelog_entry(changelog, &mut dst, "some_lint", "nursery", "pedantic").is_changed());
+    assert_eq!(
+        dst,
+        changelog.replace(
+            "* Added [`new_lint`]\n\n",
+            "* Added [`new_lint`]\n\n### Moves and Deprecations\n\n* Moved [`some_lint`] from `nursery` to `pedantic`\n\n"
+        )
+    );
+
+    // Appends to an existing subsection
+    let changelog = dst;
+    let mut dst = String::new();
+    assert!(add_changelog_entry(&changelog, &mut dst, "other_lint", "style", "restriction").is_changed());
+    assert_eq!(
+        dst,
+        changelog.replace(
+            "from `nursery` to `pedantic`\n",
+            "from `nursery` to `pedantic`\n* Moved [`other_lint`] from `style` to `restriction`\n"
+        )
+    );
+
+    // The last section of the file
+    let mut dst = String::new();
+    assert!(add_changelog_entry("# Changelog\n\n## Unreleased\n", &mut dst, "a", "b", "c").is_changed());
+    assert_eq!(
+        dst,
+        "# Changelog\n\n## Unreleased\n\n### Moves and Deprecations\n\n* Moved [`a`] from `b` to `c`\n\n"
+    );
+
+    let mut dst = String::new();
+    assert!(!add_changelog_entry("# Changelog\n\n## Rust 1.90\n", &mut dst, "a", "b", "c").is_changed());
+    assert!(dst.is_empty());
+}
+
+#[test]
+fn test_remove_range() {
+    let src = "use a;\n\nstruct A;\n\nstruct B;\n";
+    let mut dst = String::new();
+    let start = src.find("struct A").unwrap();
+    remove_range(src, &mut dst, start..start + "struct A;".len());
+    assert_eq!(dst, "use a;\n\nstruct B;\n");
+}
+
+#[test]
+fn test_use_block_end() {
+    let src = "// comment\nuse a::b;\nuse c::{d, e};\n\nfn f() {}\nuse g;\n";
+    assert_eq!(&src[..use_block_end(src)], "// comment\nuse a::b;\nuse c::{d, e};");
+    assert_eq!(use_block_end("fn f() {}\n"), 0);
+}
+
+#[test]
+fn test_last_decl_end() {
+    let src = "use a;\n\ndeclare_clippy_lint! {\n    pub A,\n}\n\ndeclare_clippy_lint! {\n    pub B,\n}\n\nfn f() {}\n";
+    assert_eq!(&src[last_decl_end(src).unwrap()..], "\n\nfn f() {}\n");
+    assert_eq!(last_decl_end("fn f() {}"), None);
+}
+
+#[test]
+fn test_add_mod_decl() {
+    let mut dst = "mod a;\npub(crate) mod b;\n\nuse c;\n".to_owned();
+    add_mod_decl(&mut dst, "new");
+    assert_eq!(dst, "mod a;\npub(crate) mod b;\npub(crate) mod new;\n\nuse c;\n");
+
+    let mut dst = "mod a;\nmod b;\n\nuse c;\n".to_owned();
+    add_mod_decl(&mut dst, "new");
+    assert_eq!(dst, "mod a;\nmod b;\n\npub(crate) mod new;\n\nuse c;\n");
+
+    let mut dst = "use c;\n".to_owned();
+    add_mod_decl(&mut dst, "new");
+    assert_eq!(dst, "pub(crate) mod new;\n\nuse c;\n");
+}
+
+#[test]
+fn test_remove_mod_decl() {
+    let src = "mod a;\npub(crate) mod some_lint;\nmod some_lint_helpers;\n\nuse c;\n";
+    let mut dst = String::new();
+    assert!(remove_mod_decl(src, &mut dst, "some_lint").is_changed());
+    assert_eq!(dst, "mod a;\nmod some_lint_helpers;\n\nuse c;\n");
+
+    let mut dst = String::new();
+    assert!(!remove_mod_decl(src, &mut dst, "other").is_changed());
+    assert!(dst.is_empty());
+}
+
+#[test]
+fn test_path_update_fn() {
+    let root = Path::new("clippy_lints/src/lib.rs");
+    let mut update = path_update_fn(
+        "methods::some_lint",
+        "loops::some_lint",
+        "methods",
+        "loops",
+        "SOME_LINT",
+        root,
+    );
+
+    let src = "use crate::methods::some_lint::check;
+use crate::methods::{OTHER_LINT, SOME_LINT};
+use crate::methods::SOME_LINT;
+fn f() { crate::methods::some_lint::Pass::new(); crate::methods::some_lint_helpers::f(); }
+";
+    let mut dst = String::new();
+    assert!(update(Path::new("clippy_lints/src/methods/mod.rs"), src, &mut dst).is_changed());
+    assert_eq!(
+        dst,
+        "use crate::loops::some_lint::check;
+use crate::methods::{OTHER_LINT, SOME_LINT};
+use crate::loops::SOME_LINT;
+fn f() { crate::loops::some_lint::Pass::new(); crate::methods::some_lint_helpers::f(); }
+"
+    );
+
+    // Paths without `crate::` are only updated in the crate root
+    let src = "store.register_late_pass(|_| Box::new(methods::some_lint::Pass));\nother::methods::some_lint::f();\n";
+    let mut dst = String::new();
+    assert!(update(root, src, &mut dst).is_changed());
+    assert_eq!(
+        dst,
+        "store.register_late_pass(|_| Box::new(loops::some_lint::Pass));\nother::methods::some_lint::f();\n"
+    );
+
+    let mut dst = String::new();
+    assert!(!update(Path::new("clippy_lints/src/loops/mod.rs"), src, &mut dst).is_changed());
+    assert_eq!(dst, src);
+}
@@ -22,15 +22,120 @@ impl fmt::Display for Pass {
     }
 }
 
-struct LintData<'a> {
-    clippy_version: Version,
-    pass: Pass,
-    name: &'a str,
-    category: &'a str,
-    ty: Option<&'a str>,
+pub(crate) struct LintData<'a> {
+    pub clippy_version: Version,
+    pub pass: Pass,
+    pub name: &'a str,
+    pub category: &'a str,
+    pub ty: Option<&'a str>,
+}
+
+/// A configuration option the new lint reads, passed as `name:type:default` to add a new option or
+/// as `name` to reuse an option that already exists in `clippy_config/src/conf.rs`
+#[derive(Clone)]
+pub struct ConfigOption {
+    /// The name of the option in snake case
+    pub name: String,
+    /// The type and default value of a new option
+    pub new: Option<(String, String)>,
+}
+
+impl ConfigOption {
+    /// Parses `name` or `name:type:default`. The type and default may contain paths, only colons
+    /// that aren't part of a `::` separate the parts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the option is malformed.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let bytes = s.as_bytes();
+        let separators: Vec<usize> = bytes
+            .iter()
+            .enumerate()
+            .filter(|&(i, &b)| b == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':'))
+            .map(|(i, _)| i)
+            .collect();
+
+        let (name, new) = match *separators {
+            [] => (s, None),
+            [ty_start, default_start] => (
+                &s[..ty_start],
+                Some((
+                    s[ty_start + 1..default_start].trim().to_owned(),
+                    s[default_start + 1..].trim().to_owned(),
+                )),
+            ),
+            _ => return Err("expected `name` or `name:type:default`".to_owned()),
+        };
+
+        let name = name.trim().replace('-', "_");
+        if name.is_empty()
+            || !name
+                .bytes()
+                .all(|b| b == b'_' || b.is_ascii_lowercase() || b.is_ascii_digit())
+        {
+            return Err(format!("invalid configuration name `{name}`"));
+        }
+        if new
+            .as_ref()
+            .is_some_and(|(ty, default)| ty.is_empty() || default.is_empty())
+        {
+            return Err("the type and default value must not be empty".to_owned());
+        }
+
+        Ok(Self { name, new })
+    }
+
+    fn kebab_name(&self) -> String {
+        self.name.replace('_', "-")
+    }
+}
+
+/// A [`ConfigOption`] whose type is known, either from the command line or from `conf.rs`
+struct ResolvedConfig<'a> {
+    option: &'a ConfigOption,
+    ty: String,
+    default: Option<String>,
 }
 
-trait Context {
+impl ResolvedConfig<'_> {
+    /// The type and initializer of the field storing the option in the lint pass struct. `Copy`
+    /// values are stored directly, others are borrowed from the `'static` configuration.
+    fn field(&self) -> (String, String) {
+        let name = &self.option.name;
+        let inner = self
+            .ty
+            .strip_prefix("Option<")
+            .and_then(|ty| ty.strip_suffix('>'))
+            .unwrap_or(&self.ty);
+        let is_copy = matches!(
+            inner,
+            "bool"
+                | "char"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+                | "f32"
+                | "f64"
+        );
+        if is_copy {
+            (self.ty.clone(), format!("conf.{name}"))
+        } else {
+            (format!("&'static {}", self.ty), format!("&conf.{name}"))
+        }
+    }
+}
+
+pub(crate) trait Context {
     fn context<C: AsRef<str>>(self, text: C) -> Self;
 }
 
@@ -58,6 +163,7 @@ pub fn create(
     category: &str,
     mut ty: Option<&str>,
     msrv: bool,
+    config: &[ConfigOption],
 ) -> io::Result<()> {
     if category == "cargo" && ty.is_none() {
         // `cargo` is a special category, these lints should always be in `clippy_lints/src/cargo`
@@ -72,11 +178,21 @@ pub fn create(
         ty,
     };
 
-    create_lint(&lint, msrv).context("Unable to create lint implementation")?;
+    let conf_rs = fs::read_to_string(CONF_PATH).context(format!("reading `{CONF_PATH}`"))?;
+    let config = resolve_config(&conf_rs, config)?;
+
+    create_lint(&lint, msrv, &config).context("Unable to create lint implementation")?;
     create_test(&lint, msrv).context("Unable to create a test for the new lint")?;
 
     if lint.ty.is_none() {
-        add_lint(&lint, msrv).context("Unable to add lint to clippy_lints/src/lib.rs")?;
+        add_lint(&lint, msrv || !config.is_empty()).context("Unable to add lint to clippy_lints/src/lib.rs")?;
+    }
+
+    if msrv || !config.is_empty() {
+        add_config(&lint, msrv, &config, conf_rs).context("Unable to add the lint configuration")?;
+    }
+    if !config.is_empty() {
+        create_config_test(&lint, &config).context("Unable to create a ui-toml test for the new lint")?;
     }
 
     if pass == Pass::Early {
@@ -90,11 +206,18 @@ pub fn create(
     Ok(())
 }
 
-fn create_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
+fn create_lint(lint: &LintData<'_>, enable_msrv: bool, config: &[ResolvedConfig<'_>]) -> io::Result<()> {
     if let Some(ty) = lint.ty {
-        create_lint_for_ty(lint, enable_msrv, ty)
+        create_lint_for_ty(lint, enable_msrv, ty)?;
+        for conf in config {
+            println!(
+                "Be sure to pass `conf.{}` from the lint pass in `clippy_lints/src/{ty}/mod.rs` to `{}::check`!",
+                conf.option.name, lint.name
+            );
+        }
+        Ok(())
     } else {
-        let lint_contents = get_lint_file_contents(lint, enable_msrv);
+        let lint_contents = get_lint_file_contents(lint, enable_msrv, config);
         let lint_path = format!("clippy_lints/src/{}.rs", lint.name);
         write_file(&lint_path, lint_contents.as_bytes())?;
         println!("Generated lint file: `{lint_path}`");
@@ -153,7 +276,7 @@ fn create_test(lint: &LintData<'_>, msrv: bool) -> io::Result<()> {
     Ok(())
 }
 
-fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
+pub(crate) fn add_lint(lint: &LintData<'_>, uses_conf: bool) -> io::Result<()> {
     let path = "clippy_lints/src/lib.rs";
     let mut lib_rs = fs::read_to_string(path).context("reading")?;
 
@@ -166,7 +289,7 @@ fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
     let module_name = lint.name;
     let camel_name = to_camel_case(lint.name);
 
-    let new_lint = if enable_msrv {
+    let new_lint = if uses_conf {
         format!("Box::new(move |{ctor_arg}| Box::new({module_name}::{camel_name}::new(conf))),\n        ",)
     } else {
         format!("Box::new(|{ctor_arg}| Box::new({module_name}::{camel_name})),\n        ",)
@@ -177,7 +300,301 @@ fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
     fs::write(path, lib_rs).context("writing")
 }
 
-fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+const CONF_PATH: &str = "clippy_config/src/conf.rs";
+const BOOK_CONF_PATH: &str = "book/src/lint_configuration.md";
+const CHANGELOG_PATH: &str = "CHANGELOG.md";
+
+/// An entry of `define_Conf!` in `clippy_config/src/conf.rs`
+struct ConfEntry<'a> {
+    name: &'a str,
+    ty: &'a str,
+    /// The default value, if it's on the same line as the name
+    default: Option<&'a str>,
+    /// Byte offset of the start of the entry's doc comment
+    start: usize,
+    /// Byte offset of the start of the `name: type = default,` line
+    decl_start: usize,
+}
+
+/// Returns the entries of `define_Conf!` and the byte offset of its closing brace
+fn parse_conf_entries(conf_rs: &str) -> (Vec<ConfEntry<'_>>, usize) {
+    const MACRO_START: &str = "\ndefine_Conf! {\n";
+    let mut pos = conf_rs.find(MACRO_START).expect("couldn't find `define_Conf!`") + MACRO_START.len();
+    let mut entries = Vec::new();
+    let mut doc_start = None;
+
+    for line in conf_rs[pos..].split_inclusive('\n') {
+        if line == "}\n" || line == "}" {
+            return (entries, pos);
+        }
+        let indented = line.strip_prefix("    ").unwrap_or_default();
+        if indented.starts_with("///") {
+            doc_start.get_or_insert(pos);
+        } else if let Some((name, rest)) = indented.split_once(": ")
+            && !name.is_empty()
+            && name.bytes().all(|b| b == b'_' || b.is_ascii_alphanumeric())
+            && let Some((ty, default)) = rest.split_once(" =")
+        {
+            entries.push(ConfEntry {
+                name,
+                ty,
+                default: default.trim().strip_suffix(',').map(str::trim),
+                start: doc_start.take().unwrap_or(pos),
+                decl_start: pos,
+            });
+        }
+        pos += line.len();
+    }
+
+    panic!("couldn't find the end of `define_Conf!`");
+}
+
+fn resolve_config<'a>(conf_rs: &str, config: &'a [ConfigOption]) -> io::Result<Vec<ResolvedConfig<'a>>> {
+    let (entries, _) = parse_conf_entries(conf_rs);
+    config
+        .iter()
+        .map(|option| {
+            let existing = entries.iter().find(|entry| entry.name == option.name);
+            let (ty, default) = match (existing, &option.new) {
+                (Some(entry), None) => (entry.ty.to_owned(), entry.default.map(str::to_owned)),
+                (None, Some((ty, default))) => (ty.clone(), Some(default.clone())),
+                (Some(_), Some(_)) => {
+                    return Err(io::Error::other(format!(
+                        "configuration `{}` already exists, pass only its name to reuse it",
+                        option.name
+                    )));
+                },
+                (None, None) => {
+                    return Err(io::Error::other(format!(
+                        "configuration `{}` doesn't exist, pass it as `name:type:default` to add it",
+                        option.name
+                    )));
+                },
+            };
+            Ok(ResolvedConfig { option, ty, default })
+        })
+        .collect()
+}
+
+/// Adds the new configuration options to `define_Conf!`, the book and the changelog, and adds the
+/// lint to the `#[lints(...)]` list of the options it reuses
+fn add_config(lint: &LintData<'_>, msrv: bool, config: &[ResolvedConfig<'_>], mut conf_rs: String) -> io::Result<()> {
+    let mut book = fs::read_to_string(BOOK_CONF_PATH).context(format!("reading `{BOOK_CONF_PATH}`"))?;
+    let mut changelog = fs::read_to_string(CHANGELOG_PATH).context(format!("reading `{CHANGELOG_PATH}`"))?;
+    let mut bless_needed = false;
+
+    let msrv_option = ConfigOption {
+        name: "msrv".to_owned(),
+        new: None,
+    };
+    let reused = config
+        .iter()
+        .map(|conf| conf.option)
+        .filter(|option| option.new.is_none())
+        .chain(msrv.then_some(&msrv_option));
+    for option in reused {
+        add_lint_to_conf_entry(&mut conf_rs, &option.name, lint.name);
+        bless_needed |= !add_lint_to_book_paragraph(&mut book, &option.kebab_name(), lint.name);
+    }
+
+    for conf in config {
+        let Some((ty, default)) = &conf.option.new else {
+            continue;
+        };
+        let name = &conf.option.name;
+        let (entries, macro_end) = parse_conf_entries(&conf_rs);
+        let insert_at = entries
+            .iter()
+            .find(|entry| entry.name > name.as_str())
+            .map_or(macro_end, |entry| entry.start);
+        conf_rs.insert_str(
+            insert_at,
+            &format!(
+                "    /// TODO: document this configuration option\n    #[lints({})]\n    {name}: {ty} = {default},\n",
+                lint.name
+            ),
+        );
+
+        if let Some(default_text) = toml_default(default) {
+            insert_book_paragraph(&mut book, &conf.option.kebab_name(), &default_text, lint.name);
+            insert_changelog_link(&mut changelog, &conf.option.kebab_name());
+        } else {
+            bless_needed = true;
+        }
+    }
+
+    fs::write(CONF_PATH, conf_rs).context(format!("writing `{CONF_PATH}`"))?;
+    fs::write(BOOK_CONF_PATH, book).context(format!("writing `{BOOK_CONF_PATH}`"))?;
+    fs::write(CHANGELOG_PATH, changelog).context(format!("writing `{CHANGELOG_PATH}`"))?;
+    println!("Added the lint configuration to `{CONF_PATH}`");
+    if bless_needed {
+        println!("Run `cargo bless --test config-metadata` to update the configuration documentation");
+    }
+
+    Ok(())
+}
+
+/// Adds `lint` to the `#[lints(...)]` attribute of the configuration option `name`, keeping the
+/// list sorted and wrapping it over multiple lines if it gets too long
+fn add_lint_to_conf_entry(conf_rs: &mut String, name: &str, lint: &str) {
+    let (entries, _) = parse_conf_entries(conf_rs);
+    let entry = entries
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("configuration `{name}` not found"));
+
+    let attrs = &conf_rs[entry.start..entry.decl_start];
+    let (range, mut lints) = if let Some(start) = attrs.find("    #[lints(") {
+        let end = start + attrs[start..].find(")]\n").expect("unterminated `#[lints(...)]`") + ")]\n".len();
+        let lints: Vec<String> = attrs[start + "    #[lints(".len()..end - ")]\n".len()]
+            .split(',')
+            .map(str::trim)
+            .filter(|lint| !lint.is_empty())
+            .map(str::to_owned)
+            .collect();
+        (entry.start + start..entry.start + end, lints)
+    } else {
+        (entry.decl_start..entry.decl_start, Vec::new())
+    };
+
+    if !lints.iter().any(|l| l == lint) {
+        lints.push(lint.to_owned());
+    }
+    lints.sort();
+
+    let single_line = format!("    #[lints({})]\n", lints.join(", "));
+    let attr = if single_line.len() <= 120 {
+        single_line
+    } else {
+        let mut attr = String::from("    #[lints(\n");
+        for lint in &lints {
+            let _: fmt::Result = writeln!(attr, "        {lint},");
+        }
+        attr.push_str("    )]\n");
+        attr
+    };
+    conf_rs.replace_range(range, &attr);
+}
+
+/// Converts the default value expression of a new configuration option to how it is displayed in
+/// the book, if it's simple enough to do so without evaluating it
+fn toml_default(default: &str) -> Option<String> {
+    if default.parse::<i128>().is_ok()
+        || default.parse::<f64>().is_ok()
+        || matches!(default, "true" | "false")
+        || (default.len() >= 2 && default.starts_with('"') && default.ends_with('"'))
+    {
+        Some(default.to_owned())
+    } else if matches!(default, "Vec::new()" | "vec![]" | "Vec::default()") {
+        Some("[]".to_owned())
+    } else if matches!(default, "String::new()" | "String::default()") {
+        Some("\"\"".to_owned())
+    } else {
+        None
+    }
+}
+
+fn book_paragraph_start(book: &str, kebab_name: &str) -> Option<usize> {
+    book.find(&format!("\n## `{kebab_name}`\n")).map(|pos| pos + 1)
+}
+
+/// Adds `lint` to the affected lints of the book paragraph of an option, returns `false` if the
+/// option isn't documented in the book
+fn add_lint_to_book_paragraph(book: &mut String, kebab_name: &str, lint: &str) -> bool {
+    let Some(start) = book_paragraph_start(book, kebab_name) else {
+        return false;
+    };
+    let lints_start = start
+        + book[start..]
+            .find("**Affected lints:**\n")
+            .expect("malformed book paragraph")
+        + "**Affected lints:**\n".len();
+    let bullet = format!("* [`{lint}`](https://rust-lang.github.io/rust-clippy/master/index.html#{lint})\n");
+
+    let mut insert_at = lints_start;
+    for line in book[lints_start..].split_inclusive('\n') {
+        if !line.starts_with("* ") || line.as_bytes() > bullet.as_bytes() {
+            break;
+        }
+        if line == bullet {
+            return true;
+        }
+        insert_at += line.len();
+    }
+    book.insert_str(insert_at, &bullet);
+    true
+}
+
+/// Inserts the paragraph documenting a new option into the book, keeping the options sorted
+fn insert_book_paragraph(book: &mut String, kebab_name: &str, default_text: &str, lint: &str) {
+    let paragraph = format!(
+        "## `{kebab_name}`\nTODO: document this configuration option\n\n**Default Value:** `{default_text}`\n\n---\n\
+         **Affected lints:**\n* [`{lint}`](https://rust-lang.github.io/rust-clippy/master/index.html#{lint})\n"
+    );
+
+    let next = book.match_indices("\n## `").map(|(pos, _)| pos + 1).find(|&pos| {
+        book[pos + "## `".len()..]
+            .split('`')
+            .next()
+            .is_some_and(|name| name > kebab_name)
+    });
+    if let Some(pos) = next {
+        book.insert_str(pos, &format!("{paragraph}\n\n"));
+    } else {
+        let _: fmt::Result = write!(book, "\n\n{paragraph}");
+    }
+}
+
+/// Inserts the link to the book for a new option into the changelog, keeping the links sorted
+fn insert_changelog_link(changelog: &mut String, kebab_name: &str) {
+    const BEGIN: &str = "<!-- begin autogenerated links to configuration documentation -->\n";
+    const END: &str = "<!-- end autogenerated links to configuration documentation -->";
+
+    let link = format!("[`{kebab_name}`]: https://doc.rust-lang.org/clippy/lint_configuration.html#{kebab_name}\n");
+    let start = changelog
+        .find(BEGIN)
+        .expect("couldn't find the configuration links in the changelog")
+        + BEGIN.len();
+    let end = start + changelog[start..].find(END).expect("unterminated configuration links");
+
+    let mut insert_at = start;
+    for line in changelog[start..end].split_inclusive('\n') {
+        if line.as_bytes() > link.as_bytes() {
+            break;
+        }
+        insert_at += line.len();
+    }
+    changelog.insert_str(insert_at, &link);
+}
+
+fn create_config_test(lint: &LintData<'_>, config: &[ResolvedConfig<'_>]) -> io::Result<()> {
+    let test_dir = PathBuf::from(format!("tests/ui-toml/{}", lint.name));
+    fs::create_dir(&test_dir)?;
+
+    let mut clippy_toml = String::from("# TODO: change the values to test the lint with a non-default configuration\n");
+    for conf in config {
+        let kebab_name = conf.option.kebab_name();
+        match conf.default.as_deref().and_then(toml_default) {
+            Some(value) => {
+                let _: fmt::Result = writeln!(clippy_toml, "{kebab_name} = {value}");
+            },
+            None => {
+                let _: fmt::Result = writeln!(clippy_toml, "# {kebab_name} = ");
+            },
+        }
+    }
+    write_file(test_dir.join("clippy.toml"), clippy_toml)?;
+    write_file(
+        test_dir.join(format!("{}.rs", lint.name)),
+        get_test_file_contents(lint.name, false),
+    )?;
+
+    println!("Generated configuration test directory: `{}`", test_dir.display());
+
+    Ok(())
+}
+
+pub(crate) fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
     fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
         OpenOptions::new()
             .write(true)
@@ -189,7 +606,7 @@ fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Resul
     inner(path.as_ref(), contents.as_ref()).context(format!("writing to file: {}", path.as_ref().display()))
 }
 
-fn to_camel_case(name: &str) -> String {
+pub(crate) fn to_camel_case(name: &str) -> String {
     name.split('_')
         .map(|s| {
             if s.is_empty() {
@@ -250,7 +667,7 @@ fn get_manifest_contents(lint_name: &str, hint: &str) -> String {
     )
 }
 
-fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
+fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool, config: &[ResolvedConfig<'_>]) -> String {
     let mut result = String::new();
 
     let (pass_type, pass_lifetimes, pass_import, context_import) = match lint.pass {
@@ -271,12 +688,16 @@ fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
     let name_camel = to_camel_case(lint.name);
     let name_upper = lint_name.to_uppercase();
 
-    if enable_msrv {
+    if enable_msrv || !config.is_empty() {
+        let msrv_import = if enable_msrv {
+            format!("use clippy_utils::msrvs::{{self, {msrv_ty}}};\n")
+        } else {
+            String::new()
+        };
         let _: fmt::Result = writedoc!(
             result,
             r"
-            use clippy_utils::msrvs::{{self, {msrv_ty}}};
-            use clippy_config::Conf;
+            {msrv_import}use clippy_config::Conf;
             {pass_import}
             use rustc_lint::{{{context_import}, {pass_type}}};
             use rustc_session::impl_lint_pass;
@@ -301,27 +722,49 @@ fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
         get_lint_declaration(lint.clippy_version, &name_upper, category)
     );
 
-    if enable_msrv {
+    if enable_msrv || !config.is_empty() {
+        let mut fields: Vec<(&str, String, String)> = config
+            .iter()
+            .map(|conf| {
+                let (ty, init) = conf.field();
+                (conf.option.name.as_str(), ty, init)
+            })
+            .collect();
+        if enable_msrv {
+            fields.push(("msrv", msrv_ty.to_owned(), msrv_ctor.to_owned()));
+        }
+        let mut field_decls = String::new();
+        let mut field_inits = String::new();
+        for (name, ty, init) in &fields {
+            let _: fmt::Result = write!(field_decls, "\n    {name}: {ty},");
+            let _: fmt::Result = write!(field_inits, "\n            {name}: {init},");
+        }
+        let (extract_msrv, msrv_todo) = if enable_msrv {
+            (
+                extract_msrv,
+                "\n// TODO: Add MSRV level to `clippy_config/src/msrvs.rs` if needed.\n",
+            )
+        } else {
+            ("", "")
+        };
+
         let _: fmt::Result = writedoc!(
             result,
             r"
-            pub struct {name_camel} {{
-                msrv: {msrv_ty},
+            pub struct {name_camel} {{{field_decls}
             }}
 
             impl {name_camel} {{
                 pub fn new(conf: &'static Conf) -> Self {{
-                    Self {{ msrv: {msrv_ctor} }}
+                    Self {{{field_inits}
+                    }}
                 }}
             }}
 
             impl_lint_pass!({name_camel} => [{name_upper}]);
 
             impl {pass_type}{pass_lifetimes} for {name_camel} {{{extract_msrv}}}
-
-            // TODO: Add MSRV level to `clippy_config/src/msrvs.rs` if needed.
-            // TODO: Update msrv config comment in `clippy_config/src/conf.rs`
-        "
+            {msrv_todo}"
         );
     } else {
         let _: fmt::Result = writedoc!(
@@ -337,7 +780,7 @@ fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
     result
 }
 
-fn get_lint_declaration(version: Version, name_upper: &str, category: &str) -> String {
+pub(crate) fn get_lint_declaration(version: Version, name_upper: &str, category: &str) -> String {
     let justification_heading = if category == "restriction" {
         "Why restrict this?"
     } else {
@@ -564,3 +1007,27 @@ fn test_camel_case() {
     let name4 = to_camel_case(name3);
     assert_eq!(name4, "LintName");
 }
+
+#[test]
+fn test_parse_config_option() {
+    let option = ConfigOption::parse("max-depth").unwrap();
+    assert_eq!(option.name, "max_depth");
+    assert!(option.new.is_none());
+
+    let option = ConfigOption::parse("allowed_paths:Vec<String>:Vec::new()").unwrap();
+    assert_eq!(option.name, "allowed_paths");
+    assert_eq!(option.new, Some(("Vec<String>".to_owned(), "Vec::new()".to_owned())));
+
+    let option = ConfigOption::parse("ordering:crate::types::Ordering:crate::types::Ordering::Default").unwrap();
+    assert_eq!(
+        option.new,
+        Some((
+            "crate::types::Ordering".to_owned(),
+            "crate::types::Ordering::Default".to_owned()
+        ))
+    );
+
+    assert!(ConfigOption::parse("max_depth:u64").is_err());
+    assert!(ConfigOption::parse("max_depth:u64:").is_err());
+    assert!(ConfigOption::parse("Max Depth:u64:5").is_err());
+}
@@ -1,104 +1,293 @@
+use crate::new_parse_cx;
 use crate::utils::{ErrAction, cargo_cmd, expect_action};
 use core::fmt::Display;
-use core::mem;
-use std::path::Path;
-use std::process::Command;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::{Duration, SystemTime};
 use std::{fs, thread};
 use walkdir::WalkDir;
 
-#[cfg(windows)]
-const PYTHON: &str = "python";
+const ROOT: &str = "util/gh-pages";
 
-#[cfg(not(windows))]
-const PYTHON: &str = "python3";
+/// The endpoint the browser listens to for reload events.
+const RELOAD_PATH: &str = "/__reload";
+
+/// Injected into `index.html` to reload the page when the metadata has been regenerated.
+const RELOAD_SCRIPT: &str =
+    "<script>new EventSource(\"/__reload\").onmessage = () => window.location.reload();</script>\n";
+
+/// Files which require regenerating the metadata for every lint when they change.
+const GLOBAL_FILES: &[&str] = &["util/gh-pages/index_template.html", "tests/compile-test.rs"];
 
 /// # Panics
 ///
-/// Panics if the python commands could not be spawned
+/// Panics if the server could not be started or the metadata could not be generated.
 pub fn run(port: u16, lint: Option<String>) -> ! {
-    let mut url = Some(match lint {
+    let url = match lint {
         None => format!("http://localhost:{port}"),
         Some(lint) => format!("http://localhost:{port}/#{lint}"),
-    });
+    };
 
-    let mut last_update = mtime("util/gh-pages/index.html");
-    loop {
-        if is_metadata_outdated(mem::replace(&mut last_update, SystemTime::now())) {
-            // Ignore the command result; we'll fall back to displaying the old metadata.
-            let _ = expect_action(
-                cargo_cmd().arg("collect-metadata").status(),
-                ErrAction::Run,
-                "cargo collect-metadata",
-            );
-            last_update = SystemTime::now();
-        }
+    let mut sources = scan_sources();
+    let index = Path::new(ROOT).join("index.html");
+    let index_time = mtime(&index);
+    if GLOBAL_FILES
+        .iter()
+        .map(Path::new)
+        .chain(sources.keys().map(PathBuf::as_path))
+        .any(|path| index_time < mtime(path))
+    {
+        // Ignore the command result; we'll fall back to displaying the old metadata.
+        collect_metadata(None);
+    }
 
-        // Only start the web server the first time around.
-        if let Some(url) = url.take() {
+    let reloads = Arc::new(Reloads::default());
+    let listener = expect_action(TcpListener::bind(("localhost", port)), ErrAction::Run, "http server");
+    let server_reloads = reloads.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Some(stream) = log_err_and_continue(stream, "http server") else {
+                continue;
+            };
+            let reloads = server_reloads.clone();
             thread::spawn(move || {
-                let mut child = expect_action(
-                    Command::new(PYTHON)
-                        .args(["-m", "http.server", port.to_string().as_str()])
-                        .current_dir("util/gh-pages")
-                        .spawn(),
-                    ErrAction::Run,
-                    "python -m http.server",
-                );
-                // Give some time for python to start
-                thread::sleep(Duration::from_millis(500));
-                // Launch browser after first export.py has completed and http.server is up
-                let _result = opener::open(url);
-                expect_action(child.wait(), ErrAction::Run, "python -m http.server");
+                if let Err(e) = handle_connection(stream, &reloads) {
+                    eprintln!("error handling request: {e}");
+                }
             });
         }
+    });
+    println!("Serving `{ROOT}` at {url}");
+    let _result = opener::open(url);
 
+    let mut global_times: Vec<_> = GLOBAL_FILES.iter().map(|path| mtime(Path::new(path))).collect();
+    loop {
         // Delay to avoid updating the metadata too aggressively.
         thread::sleep(Duration::from_millis(1000));
+
+        let new_global_times: Vec<_> = GLOBAL_FILES.iter().map(|path| mtime(Path::new(path))).collect();
+        let new_sources = scan_sources();
+        let mut changed: Vec<_> = new_sources
+            .iter()
+            .filter(|&(path, time)| sources.get(path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        let removed = sources.keys().any(|path| !new_sources.contains_key(path));
+        if changed.is_empty() && !removed && new_global_times == global_times {
+            continue;
+        }
+
+        let lints = if removed || new_global_times != global_times {
+            None
+        } else {
+            changed.sort();
+            changed_lints(&changed)
+        };
+        match &lints {
+            Some(lints) => println!("Regenerating the metadata for {}", lints.join(", ")),
+            None => println!("Regenerating the metadata for all lints"),
+        }
+        if collect_metadata(lints.as_deref()) {
+            reloads.notify();
+        }
+        sources = new_sources;
+        global_times = new_global_times;
     }
 }
 
-fn log_err_and_continue<T>(res: Result<T, impl Display>, path: &Path) -> Option<T> {
-    match res {
-        Ok(x) => Some(x),
-        Err(ref e) => {
-            eprintln!("error reading `{}`: {e}", path.display());
-            None
-        },
+/// Runs `cargo collect-metadata`, restricted to the tests of the given lints if any. Returns
+/// whether the metadata was successfully regenerated.
+fn collect_metadata(lints: Option<&[String]>) -> bool {
+    let mut cmd = cargo_cmd();
+    cmd.arg("collect-metadata");
+    if let Some(lints) = lints {
+        cmd.env("TESTNAME", lints.join(","));
     }
+    expect_action(cmd.status(), ErrAction::Run, "cargo collect-metadata").success()
 }
 
-fn mtime(path: &str) -> SystemTime {
-    log_err_and_continue(fs::metadata(path), path.as_ref())
-        .and_then(|metadata| log_err_and_continue(metadata.modified(), path.as_ref()))
-        .unwrap_or(SystemTime::UNIX_EPOCH)
+/// Finds the lints whose tests need to be run for the metadata to reflect the changed files.
+/// Returns `None` if every lint may be affected.
+fn changed_lints(changed: &[PathBuf]) -> Option<Vec<String>> {
+    new_parse_cx(|cx| {
+        let lints = cx.find_lint_decls();
+        let mut names = Vec::new();
+        for path in changed {
+            // Lints split over multiple files are either declared in the changed file or in the
+            // `mod.rs` of the same directory with the implementation in a file named after the lint.
+            let declared: Vec<_> = lints.iter().filter(|lint| lint.path == *path).collect();
+            let declared = if declared.is_empty() {
+                let mod_file = path.with_file_name("mod.rs");
+                let siblings: Vec<_> = lints.iter().filter(|lint| lint.path == mod_file).collect();
+                match siblings
+                    .iter()
+                    .find(|lint| path.file_stem().is_some_and(|stem| stem == lint.name))
+                {
+                    Some(&lint) => vec![lint],
+                    None => siblings,
+                }
+            } else {
+                declared
+            };
+            if declared.is_empty() {
+                return None;
+            }
+            names.extend(declared.iter().map(|lint| lint.name.to_owned()));
+        }
+        names.sort_unstable();
+        names.dedup();
+        Some(names)
+    })
 }
 
-fn is_metadata_outdated(time: SystemTime) -> bool {
+/// Gets the modification times of all the files the metadata is generated from.
+fn scan_sources() -> HashMap<PathBuf, SystemTime> {
+    let mut sources = HashMap::new();
     // Ignore all IO errors here. We don't want to stop them from hosting the server.
-    if time < mtime("util/gh-pages/index_template.html") || time < mtime("tests/compile-test.rs") {
-        return true;
-    }
-    let Some(dir) = log_err_and_continue(fs::read_dir("."), ".".as_ref()) else {
-        return false;
+    let Some(dir) = log_err_and_continue(fs::read_dir("."), ".") else {
+        return sources;
     };
-    dir.map_while(|e| log_err_and_continue(e, ".".as_ref())).any(|e| {
+    for e in dir.map_while(|e| log_err_and_continue(e, ".")) {
         let name = e.file_name();
         let name_bytes = name.as_encoded_bytes();
         if (name_bytes.starts_with(b"clippy_lints") && name_bytes != b"clippy_lints_internal")
             || name_bytes == b"clippy_config"
         {
-            WalkDir::new(&name)
-                .into_iter()
-                .map_while(|e| log_err_and_continue(e, name.as_ref()))
-                .filter(|e| e.file_type().is_file())
-                .filter_map(|e| {
-                    log_err_and_continue(e.metadata(), e.path())
-                        .and_then(|m| log_err_and_continue(m.modified(), e.path()))
-                })
-                .any(|ftime| time < ftime)
-        } else {
-            false
+            sources.extend(
+                WalkDir::new(Path::new(&name).join("src"))
+                    .into_iter()
+                    .map_while(|e| log_err_and_continue(e, Path::new(&name)))
+                    .filter(|e| e.file_type().is_file())
+                    .filter_map(|e| {
+                        let time = log_err_and_continue(e.metadata(), e.path())
+                            .and_then(|m| log_err_and_continue(m.modified(), e.path()))?;
+                        Some((e.into_path(), time))
+                    }),
+            );
         }
-    })
+    }
+    sources
+}
+
+/// Tracks the number of times the metadata has been regenerated.
+#[derive(Default)]
+struct Reloads {
+    count: Mutex<u64>,
+    condvar: Condvar,
+}
+impl Reloads {
+    fn notify(&self) {
+        *self.count.lock().unwrap() += 1;
+        self.condvar.notify_all();
+    }
+
+    /// Waits until the metadata has been regenerated after the given count, returning the new
+    /// count. Returns `None` if this doesn't happen before the timeout.
+    fn wait(&self, count: u64, timeout: Duration) -> Option<u64> {
+        let guard = self.count.lock().unwrap();
+        let (guard, _) = self
+            .condvar
+            .wait_timeout_while(guard, timeout, |c| *c == count)
+            .unwrap();
+        (*guard != count).then_some(*guard)
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, reloads: &Reloads) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Skip the headers, none of them are needed.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_ascii_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return respond(&mut stream, "400 Bad Request", "text/plain", b"bad request");
+    };
+    if method != "GET" {
+        return respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"method not allowed",
+        );
+    }
+    let target = target.split(['?', '#']).next().unwrap_or(target);
+
+    if target == RELOAD_PATH {
+        stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n")?;
+        stream.flush()?;
+        let mut count = *reloads.count.lock().unwrap();
+        loop {
+            // Periodically send a comment so closed connections are noticed.
+            if let Some(new_count) = reloads.wait(count, Duration::from_secs(15)) {
+                count = new_count;
+                stream.write_all(b"data: reload\n\n")?;
+            } else {
+                stream.write_all(b": keep-alive\n\n")?;
+            }
+            stream.flush()?;
+        }
+    }
+
+    let relative = Path::new(target.trim_start_matches('/'));
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return respond(&mut stream, "404 Not Found", "text/plain", b"not found");
+    }
+    let mut path = Path::new(ROOT).join(relative);
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    let Ok(mut contents) = fs::read(&path) else {
+        return respond(&mut stream, "404 Not Found", "text/plain", b"not found");
+    };
+    let content_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => {
+            let pos = contents
+                .windows(b"</body>".len())
+                .rposition(|x| x == b"</body>")
+                .unwrap_or(contents.len());
+            contents.splice(pos..pos, RELOAD_SCRIPT.bytes());
+            "text/html; charset=utf-8"
+        },
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    };
+    respond(&mut stream, "200 OK", content_type, &contents)
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn log_err_and_continue<T>(res: Result<T, impl Display>, path: impl AsRef<Path>) -> Option<T> {
+    match res {
+        Ok(x) => Some(x),
+        Err(ref e) => {
+            eprintln!("error reading `{}`: {e}", path.as_ref().display());
+            None
+        },
+    }
+}
+
+fn mtime(path: &Path) -> SystemTime {
+    log_err_and_continue(fs::metadata(path), path)
+        .and_then(|metadata| log_err_and_continue(metadata.modified(), path))
+        .unwrap_or(SystemTime::UNIX_EPOCH)
 }
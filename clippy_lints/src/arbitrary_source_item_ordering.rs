@@ -4,7 +4,7 @@ use clippy_config::types::{
     SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
     SourceItemOrderingWithinModuleItemGroupings,
 };
-use clippy_utils::diagnostics::span_lint_and_note;
+use clippy_utils::diagnostics::{ExpectAttrContext, span_lint_and_note};
 use clippy_utils::is_cfg_test;
 use rustc_hir::attrs::AttributeKind;
 use rustc_hir::{
@@ -211,7 +211,7 @@ impl ArbitrarySourceItemOrdering {
     }
 
     /// Produces a linting warning for incorrectly ordered item members.
-    fn lint_member_name<T: LintContext>(cx: &T, ident: Ident, before_ident: Ident) {
+    fn lint_member_name<T: ExpectAttrContext>(cx: &T, ident: Ident, before_ident: Ident) {
         span_lint_and_note(
             cx,
             ARBITRARY_SOURCE_ITEM_ORDERING,
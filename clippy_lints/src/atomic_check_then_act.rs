@@ -0,0 +1,247 @@
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::visitors::{Descend, for_each_expr, for_each_expr_without_closures};
+use clippy_utils::{eq_expr_value, expr_or_init, paths, sym};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{Expr, ExprKind, HirId, MatchSource, Node, PatKind, StmtKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::impl_lint_pass;
+use rustc_span::{Span, Symbol};
+use std::ops::ControlFlow;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for atomics which are modified with `store` or one of the `fetch_*` methods inside
+    /// an `if` or `match` whose condition depends on a separate `load` of the same atomic.
+    ///
+    /// ### Why is this bad?
+    /// Another thread can modify the atomic between the `load` and the `store`, so the condition
+    /// may no longer hold when the value is modified. E.g. two threads can both see a flag as set
+    /// and both act on it, even though only one of them was meant to. `compare_exchange`, `swap`
+    /// and `fetch_update` check the value and modify it in a single atomic operation.
+    ///
+    /// Modifications made while a `Mutex` or `RwLock` guard is held, and modifications inside a
+    /// nested condition which loads the same atomic again (e.g. double-checked locking) are not
+    /// linted.
+    ///
+    /// ### Known problems
+    /// Atomics which are only modified from a single thread, or while holding a lock acquired
+    /// elsewhere, don't have this problem.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # fn run_pending_job() {}
+    /// static PENDING: AtomicBool = AtomicBool::new(false);
+    ///
+    /// if PENDING.load(Ordering::Acquire) {
+    ///     PENDING.store(false, Ordering::Release);
+    ///     run_pending_job();
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # fn run_pending_job() {}
+    /// static PENDING: AtomicBool = AtomicBool::new(false);
+    ///
+    /// if PENDING.swap(false, Ordering::AcqRel) {
+    ///     run_pending_job();
+    /// }
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub ATOMIC_CHECK_THEN_ACT,
+    nursery,
+    "modifying an atomic based on a separate load of its value"
+}
+
+#[derive(Default)]
+pub struct AtomicCheckThenAct {
+    /// The modifications which were already linted.
+    linted: FxHashSet<HirId>,
+}
+
+impl_lint_pass!(AtomicCheckThenAct => [ATOMIC_CHECK_THEN_ACT]);
+
+impl<'tcx> LateLintPass<'tcx> for AtomicCheckThenAct {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        if expr.span.from_expansion() {
+            return;
+        }
+        match expr.kind {
+            ExprKind::If(cond, then, els) => {
+                let loads = find_loads(cx, cond);
+                self.check_branch(cx, &loads, then);
+                if let Some(els) = els {
+                    self.check_branch(cx, &loads, els);
+                }
+            },
+            ExprKind::Match(scrutinee, arms, MatchSource::Normal) => {
+                let loads = find_loads(cx, scrutinee);
+                for arm in arms {
+                    self.check_branch(cx, &loads, arm.body);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+impl AtomicCheckThenAct {
+    /// Lints modifications of any of the loaded atomics inside the branch.
+    fn check_branch<'tcx>(&mut self, cx: &LateContext<'tcx>, loads: &[Load<'tcx>], branch: &'tcx Expr<'tcx>) {
+        if loads.is_empty() {
+            return;
+        }
+        for_each_expr(cx, branch, |e| {
+            // The nested condition checks the value again, its branches are checked on their own.
+            let nested_cond = match e.kind {
+                ExprKind::If(cond, ..) => Some(cond),
+                ExprKind::Match(scrutinee, _, MatchSource::Normal) => Some(scrutinee),
+                _ => None,
+            };
+            if let Some(cond) = nested_cond
+                && find_loads(cx, cond)
+                    .iter()
+                    .any(|nested| loads.iter().any(|load| eq_expr_value(cx, load.atomic, nested.atomic)))
+            {
+                return ControlFlow::<(), _>::Continue(Descend::No);
+            }
+
+            if let Some((name, atomic)) = atomic_method_call(cx, e)
+                && (name == sym::store || name.as_str().starts_with("fetch_") && name != sym::fetch_update)
+                && let Some(load) = loads.iter().find(|load| eq_expr_value(cx, load.atomic, atomic))
+                && !e.span.from_expansion()
+                && !holds_lock_guard(cx, e)
+                && self.linted.insert(e.hir_id)
+            {
+                span_lint_and_then(
+                    cx,
+                    ATOMIC_CHECK_THEN_ACT,
+                    e.span,
+                    "atomic is modified based on a separate load of its value",
+                    |diag| {
+                        diag.span_note(
+                            load.span,
+                            "the value is loaded here, it can be changed by another thread before it is modified",
+                        );
+                        diag.help(
+                            "use `compare_exchange`, `swap` or `fetch_update` to check and modify the value atomically",
+                        );
+                    },
+                );
+            }
+            ControlFlow::Continue(Descend::Yes)
+        });
+    }
+}
+
+/// Checks if a lock guard bound to a local in one of the blocks enclosing the expression is
+/// still held when the expression is evaluated.
+fn holds_lock_guard(cx: &LateContext<'_>, e: &Expr<'_>) -> bool {
+    let mut child = e.hir_id;
+    for (parent_id, node) in cx.tcx.hir_parent_iter(e.hir_id) {
+        match node {
+            Node::Block(block) => {
+                let end = block
+                    .stmts
+                    .iter()
+                    .position(|stmt| stmt.hir_id == child)
+                    .unwrap_or(block.stmts.len());
+                if block.stmts[..end].iter().any(|stmt| {
+                    if let StmtKind::Let(local) = stmt.kind
+                        && !matches!(local.pat.kind, PatKind::Wild)
+                    {
+                        is_lock_guard(cx, cx.typeck_results().pat_ty(local.pat))
+                    } else {
+                        false
+                    }
+                }) {
+                    return true;
+                }
+            },
+            Node::Expr(Expr {
+                kind: ExprKind::Closure(_),
+                ..
+            })
+            | Node::Item(_)
+            | Node::ImplItem(_)
+            | Node::TraitItem(_) => return false,
+            _ => {},
+        }
+        child = parent_id;
+    }
+    false
+}
+
+/// Checks if the type is a `Mutex` or `RwLock` guard.
+fn is_lock_guard<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    let ty::Adt(adt, _) = ty.kind() else {
+        return false;
+    };
+    match cx.tcx.get_diagnostic_name(adt.did()) {
+        Some(name) => matches!(name, sym::MutexGuard | sym::RwLockReadGuard | sym::RwLockWriteGuard),
+        None => paths::PARKING_LOT_GUARDS
+            .iter()
+            .any(|guard| guard.matches(cx, adt.did())),
+    }
+}
+
+/// A call to `load` on an atomic.
+struct Load<'tcx> {
+    /// The atomic the value is loaded from.
+    atomic: &'tcx Expr<'tcx>,
+    span: Span,
+}
+
+/// Finds the loads the value of the condition depends on. This includes loads in the
+/// initializers of the local variables used in the condition.
+fn find_loads<'tcx>(cx: &LateContext<'tcx>, cond: &'tcx Expr<'tcx>) -> Vec<Load<'tcx>> {
+    let mut loads = Vec::new();
+    for_each_expr_without_closures(cond, |e| {
+        if let Some((sym::load, atomic)) = atomic_method_call(cx, e) {
+            loads.push(Load { atomic, span: e.span });
+        } else if let ExprKind::Path(_) = e.kind
+            && let init = expr_or_init(cx, e)
+            && init.hir_id != e.hir_id
+        {
+            loads.extend(find_loads(cx, init));
+        }
+        ControlFlow::<()>::Continue(())
+    });
+    loads
+}
+
+/// If the expression is a method call on one of the atomic types, gets the name of the method and
+/// the atomic it is called on.
+fn atomic_method_call<'tcx>(cx: &LateContext<'tcx>, e: &'tcx Expr<'tcx>) -> Option<(Symbol, &'tcx Expr<'tcx>)> {
+    if let ExprKind::MethodCall(path, recv, ..) = e.kind
+        && let Some(method) = cx.typeck_results().type_dependent_def_id(e.hir_id)
+        && let Some(impl_id) = cx.tcx.impl_of_assoc(method)
+        && let ty::Adt(adt, _) = cx.tcx.type_of(impl_id).instantiate_identity().kind()
+        && let Some(
+            sym::AtomicBool
+            | sym::AtomicI8
+            | sym::AtomicI16
+            | sym::AtomicI32
+            | sym::AtomicI64
+            | sym::AtomicI128
+            | sym::AtomicIsize
+            | sym::AtomicPtr
+            | sym::AtomicU8
+            | sym::AtomicU16
+            | sym::AtomicU32
+            | sym::AtomicU64
+            | sym::AtomicU128
+            | sym::AtomicUsize,
+        ) = cx.tcx.get_diagnostic_name(adt.did())
+    {
+        let mut recv = recv;
+        while let ExprKind::AddrOf(_, _, inner) = recv.kind {
+            recv = inner;
+        }
+        Some((path.ident.name, recv))
+    } else {
+        None
+    }
+}
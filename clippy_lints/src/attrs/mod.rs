@@ -10,6 +10,7 @@ mod non_minimal_cfg;
 mod repr_attributes;
 mod should_panic_without_expect;
 mod unnecessary_clippy_cfg;
+mod unused_clippy_allow;
 mod useless_attribute;
 mod utils;
 
@@ -17,7 +18,8 @@ use clippy_config::Conf;
 use clippy_utils::diagnostics::span_lint_and_help;
 use clippy_utils::msrvs::{self, Msrv, MsrvStack};
 use rustc_ast::{self as ast, AttrArgs, AttrKind, Attribute, MetaItemInner, MetaItemKind};
-use rustc_hir::{ImplItem, Item, ItemKind, TraitItem};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{self as hir, ImplItem, Item, ItemKind, TraitItem};
 use rustc_lint::{EarlyContext, EarlyLintPass, LateContext, LateLintPass};
 use rustc_session::impl_lint_pass;
 use rustc_span::sym;
@@ -474,6 +476,44 @@ declare_clippy_lint! {
     "ignored tests without messages"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `#[allow]` attributes for Clippy lints which don't suppress any lint.
+    ///
+    /// This lint only runs with `cargo clippy --unused-allows`, which tracks the attributes
+    /// suppressing each lint. With `--unused-allows=expect` the suggestion converts the attribute
+    /// to `#[expect]` instead of removing it, so rustc keeps pointing it out through
+    /// `unfulfilled_lint_expectations` until it has been looked at.
+    ///
+    /// Only Clippy's own lints and the lints configured with `custom-lint` are checked. Lint
+    /// groups and lints loaded from other libraries with `load-lints` are ignored.
+    ///
+    /// ### Why is this bad?
+    /// The attribute is no longer needed, e.g. because the code or the lint changed. It hides
+    /// lints added to the code later on.
+    ///
+    /// ### Known problems
+    /// Attributes only needed with other features or on other targets are reported as well.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// #[allow(clippy::needless_return)]
+    /// fn f() -> u32 {
+    ///     1
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// fn f() -> u32 {
+    ///     1
+    /// }
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub UNUSED_CLIPPY_ALLOW,
+    restriction,
+    "`#[allow]` attributes for Clippy lints which don't suppress any lint"
+}
+
 pub struct Attributes {
     msrv: Msrv,
 }
@@ -513,6 +553,45 @@ impl<'tcx> LateLintPass<'tcx> for Attributes {
     }
 }
 
+/// Reports the `#[allow]` attributes for Clippy lints which didn't suppress a lint. This must be
+/// registered after all other lint passes.
+pub struct UnusedClippyAllow {
+    to_expect: bool,
+    /// The names of the lints whose `#[allow]` attributes are checked, without the `clippy::`
+    /// prefix. Lints loaded from other libraries with `load-lints` are emitted through their own
+    /// copy of `clippy_utils`, so the attributes allowing them can't be tracked.
+    checked_lints: FxHashSet<String>,
+    allows: Vec<unused_clippy_allow::AllowAttr>,
+}
+
+impl_lint_pass!(UnusedClippyAllow => [UNUSED_CLIPPY_ALLOW]);
+
+impl UnusedClippyAllow {
+    pub fn new(conf: &'static Conf, to_expect: bool) -> Self {
+        Self {
+            to_expect,
+            checked_lints: crate::declared_lints::LINTS
+                .iter()
+                .filter(|info| info.lint.name != UNUSED_CLIPPY_ALLOW.name)
+                .map(|info| info.name_lower())
+                .chain(conf.custom_lint.iter().map(|lint| lint.name.clone()))
+                .collect(),
+            allows: Vec::new(),
+        }
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnusedClippyAllow {
+    fn check_attribute(&mut self, cx: &LateContext<'tcx>, attr: &'tcx hir::Attribute) {
+        self.allows
+            .extend(unused_clippy_allow::collect(cx, attr, &self.checked_lints));
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        unused_clippy_allow::check(cx, &self.allows, self.to_expect);
+    }
+}
+
 pub struct EarlyAttributes {
     msrv: MsrvStack,
 }
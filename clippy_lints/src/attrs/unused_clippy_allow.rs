@@ -0,0 +1,175 @@
+use super::UNUSED_CLIPPY_ALLOW;
+use clippy_utils::diagnostics::{span_lint_hir_and_then, used_allow_attributes};
+use clippy_utils::source::{IntoSpan, SpanRangeExt, snippet, snippet_indent};
+use rustc_ast::MetaItemInner;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::Applicability;
+use rustc_hir::{Attribute, HirId};
+use rustc_lint::LateContext;
+use rustc_span::{Span, sym};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    /// A Clippy lint which is checked for being unused.
+    ClippyLint,
+    /// Any other lint, or a Clippy lint which isn't checked such as this lint itself, lint groups
+    /// and lints loaded from other libraries.
+    OtherLint,
+    Reason,
+}
+
+pub(super) struct AllowAttr {
+    hir_id: HirId,
+    span: Span,
+    /// The span of `allow` in the attribute.
+    ident_span: Span,
+    items: Vec<(Span, ItemKind)>,
+}
+
+pub(super) fn collect(cx: &LateContext<'_>, attr: &Attribute, checked_lints: &FxHashSet<String>) -> Option<AllowAttr> {
+    if !attr.has_name(sym::allow) || attr.span().from_expansion() {
+        return None;
+    }
+    let ident = attr.ident()?;
+    let items: Vec<_> = attr
+        .meta_item_list()?
+        .iter()
+        .map(|item| (item.span(), item_kind(item, checked_lints)))
+        .collect();
+    items
+        .iter()
+        .any(|&(_, kind)| kind == ItemKind::ClippyLint)
+        .then(|| AllowAttr {
+            hir_id: cx.last_node_with_lint_attrs,
+            span: attr.span(),
+            ident_span: ident.span,
+            items,
+        })
+}
+
+fn item_kind(item: &MetaItemInner, checked_lints: &FxHashSet<String>) -> ItemKind {
+    let Some(meta_item) = item.meta_item() else {
+        return ItemKind::OtherLint;
+    };
+    if meta_item.has_name(sym::reason) {
+        return ItemKind::Reason;
+    }
+    match &*meta_item.path.segments {
+        [tool, lint] if tool.ident.name == sym::clippy && checked_lints.contains(lint.ident.as_str()) => {
+            ItemKind::ClippyLint
+        },
+        _ => ItemKind::OtherLint,
+    }
+}
+
+pub(super) fn check(cx: &LateContext<'_>, allows: &[AllowAttr], to_expect: bool) {
+    let used = used_allow_attributes();
+    // The attributes of `let` statements are visited for both the statement and the local.
+    let mut seen = FxHashSet::default();
+    for allow in allows {
+        if !seen.insert(allow.span) {
+            continue;
+        }
+        let (unused, kept): (Vec<_>, Vec<_>) = allow
+            .items
+            .iter()
+            .partition(|&&(span, kind)| kind == ItemKind::ClippyLint && !used.contains(&span));
+        if unused.is_empty() {
+            continue;
+        }
+
+        let names = unused
+            .iter()
+            .map(|&&(span, _)| format!("`{}`", snippet(cx, span, "..")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let spans: Vec<_> = unused.iter().map(|&&(span, _)| span).collect();
+        span_lint_hir_and_then(
+            cx,
+            UNUSED_CLIPPY_ALLOW,
+            allow.hir_id,
+            spans,
+            format!("allowing {names} doesn't suppress any lint"),
+            |diag| {
+                // Attributes from `cfg_attr` don't include the `#[`.
+                let Some(style) = allow
+                    .span
+                    .with_source_text(cx, |src| {
+                        if src.starts_with("#!") {
+                            Some("#!")
+                        } else {
+                            src.starts_with('#').then_some("#")
+                        }
+                    })
+                    .flatten()
+                else {
+                    return;
+                };
+
+                if kept.iter().all(|&&(_, kind)| kind == ItemKind::Reason) {
+                    if to_expect {
+                        diag.span_suggestion(
+                            allow.ident_span,
+                            "convert it to `expect`",
+                            "expect",
+                            Applicability::MachineApplicable,
+                        );
+                    } else {
+                        diag.span_suggestion(
+                            removal_span(cx, allow.span),
+                            "remove the attribute",
+                            "",
+                            Applicability::MachineApplicable,
+                        );
+                    }
+                    return;
+                }
+
+                let list = allow.items[0].0.to(allow.items[allow.items.len() - 1].0);
+                let kept = kept
+                    .iter()
+                    .map(|&&(span, _)| snippet(cx, span, "..").into_owned())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if to_expect {
+                    let unused = unused
+                        .iter()
+                        .map(|&&(span, _)| snippet(cx, span, ".."))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let indent = snippet_indent(cx, allow.span).unwrap_or_default();
+                    diag.multipart_suggestion(
+                        "move them to an `expect` attribute",
+                        vec![
+                            (
+                                allow.span.shrink_to_lo(),
+                                format!("{style}[expect({unused})]\n{indent}"),
+                            ),
+                            (list, kept),
+                        ],
+                        Applicability::MachineApplicable,
+                    );
+                } else {
+                    diag.span_suggestion(list, "remove them", kept, Applicability::MachineApplicable);
+                }
+            },
+        );
+    }
+}
+
+/// Extends the span of the attribute to its whole line if nothing else is on that line, otherwise
+/// to the whitespace following it.
+fn removal_span(cx: &LateContext<'_>, span: Span) -> Span {
+    span.map_range(cx, |_, src, range| {
+        let line_start = src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[range.end..].find('\n').map_or(src.len(), |i| range.end + i + 1);
+        Some(
+            if src[line_start..range.start].trim().is_empty() && src[range.end..line_end].trim().is_empty() {
+                line_start..line_end
+            } else {
+                range.start..src.len() - src[range.end..].trim_start().len()
+            },
+        )
+    })
+    .map_or(span, |range| range.with_ctxt(span.ctxt()))
+}
@@ -0,0 +1,216 @@
+use clippy_config::Conf;
+use clippy_config::types::{DisallowedPath, create_disallowed_map};
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::paths::{PathNS, lookup_path_str};
+use rustc_hir::def::{CtorKind, DefKind, Res};
+use rustc_hir::def_id::{DefIdMap, DefIdSet};
+use rustc_hir::{ClosureKind, CoroutineDesugaring, CoroutineKind, Expr, ExprKind, HirId, Node};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::impl_lint_pass;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for calls to blocking functions in async functions, async blocks and async
+    /// closures, including any closures defined inside them.
+    ///
+    /// Known blocking functions of the standard library, e.g. `std::fs::read`,
+    /// `std::thread::sleep` or `std::sync::Mutex::lock`, are checked by default. More can be
+    /// added with the `blocking-functions` configuration. Calls inside a closure passed to one of
+    /// the functions listed in `spawn-blocking-functions`, e.g. `tokio::task::spawn_blocking`,
+    /// are allowed.
+    ///
+    /// ### Why is this bad?
+    /// Async runtimes run many tasks on a small number of threads. A task which blocks its thread
+    /// stops every other task scheduled on that thread from making progress until the call
+    /// returns.
+    ///
+    /// ### Known problems
+    /// Only calls which are written in the async body are checked, blocking calls inside
+    /// functions called from the async body are not. Locking a `Mutex` which is only held for a
+    /// short time and never across an `.await` is usually fine.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// async fn load() -> std::io::Result<Vec<u8>> {
+    ///     std::fs::read("data.bin")
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```rust,ignore
+    /// async fn load() -> std::io::Result<Vec<u8>> {
+    ///     tokio::fs::read("data.bin").await
+    /// }
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub BLOCKING_IN_ASYNC,
+    pedantic,
+    "calls to blocking functions in an async context"
+}
+
+/// Functions from the standard library which block the current thread.
+const BLOCKING_FUNCTIONS: &[&str] = &[
+    "std::fs::canonicalize",
+    "std::fs::copy",
+    "std::fs::create_dir",
+    "std::fs::create_dir_all",
+    "std::fs::hard_link",
+    "std::fs::metadata",
+    "std::fs::read",
+    "std::fs::read_dir",
+    "std::fs::read_link",
+    "std::fs::read_to_string",
+    "std::fs::remove_dir",
+    "std::fs::remove_dir_all",
+    "std::fs::remove_file",
+    "std::fs::rename",
+    "std::fs::set_permissions",
+    "std::fs::symlink_metadata",
+    "std::fs::write",
+    "std::fs::File::create",
+    "std::fs::File::open",
+    "std::fs::OpenOptions::open",
+    "std::io::Stdin::read_line",
+    "std::net::TcpListener::accept",
+    "std::net::TcpStream::connect",
+    "std::net::UdpSocket::recv",
+    "std::net::UdpSocket::recv_from",
+    "std::process::Child::wait",
+    "std::process::Child::wait_with_output",
+    "std::process::Command::output",
+    "std::process::Command::status",
+    "std::sync::Barrier::wait",
+    "std::sync::Condvar::wait",
+    "std::sync::Mutex::lock",
+    "std::sync::RwLock::read",
+    "std::sync::RwLock::write",
+    "std::sync::mpsc::Receiver::recv",
+    "std::sync::mpsc::Receiver::recv_timeout",
+    "std::sync::mpsc::SyncSender::send",
+    "std::thread::JoinHandle::join",
+    "std::thread::park",
+    "std::thread::sleep",
+];
+
+pub struct BlockingInAsync {
+    /// The blocking functions from the standard library.
+    blocking: DefIdMap<&'static str>,
+    /// The blocking functions from the configuration.
+    configured: DefIdMap<(&'static str, &'static DisallowedPath)>,
+    spawn_blocking: DefIdSet,
+}
+
+impl BlockingInAsync {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        let (configured, _) = create_disallowed_map(
+            tcx,
+            &conf.blocking_functions,
+            PathNS::Value,
+            |def_kind| {
+                matches!(
+                    def_kind,
+                    DefKind::Fn | DefKind::Ctor(_, CtorKind::Fn) | DefKind::AssocFn
+                )
+            },
+            "function",
+            false,
+        );
+        Self {
+            blocking: BLOCKING_FUNCTIONS
+                .iter()
+                .flat_map(|&path| {
+                    lookup_path_str(tcx, PathNS::Value, path)
+                        .into_iter()
+                        .map(move |def_id| (def_id, path))
+                })
+                .collect(),
+            configured,
+            spawn_blocking: conf
+                .spawn_blocking_functions
+                .iter()
+                .flat_map(|path| lookup_path_str(tcx, PathNS::Value, path))
+                .collect(),
+        }
+    }
+
+    /// Checks if the expression is evaluated as part of an async body. This stops at closures
+    /// passed to one of the `spawn-blocking-functions`.
+    fn is_in_async_context(&self, cx: &LateContext<'_>, hir_id: HirId) -> bool {
+        for (parent_id, node) in cx.tcx.hir_parent_iter(hir_id) {
+            match node {
+                Node::Expr(e) if let ExprKind::Closure(closure) = e.kind => match closure.kind {
+                    ClosureKind::Coroutine(CoroutineKind::Desugared(CoroutineDesugaring::Async, _)) => return true,
+                    ClosureKind::Closure if self.is_spawn_blocking_arg(cx, parent_id) => return false,
+                    _ => {},
+                },
+                Node::Item(_) | Node::ImplItem(_) | Node::TraitItem(_) | Node::ForeignItem(_) => return false,
+                _ => {},
+            }
+        }
+        false
+    }
+
+    fn is_spawn_blocking_arg(&self, cx: &LateContext<'_>, closure: HirId) -> bool {
+        let Node::Expr(call) = cx.tcx.parent_hir_node(closure) else {
+            return false;
+        };
+        let callee = match call.kind {
+            ExprKind::Call(func, args) if args.iter().any(|arg| arg.hir_id == closure) => {
+                if let ExprKind::Path(qpath) = &func.kind
+                    && let Res::Def(_, def_id) = cx.qpath_res(qpath, func.hir_id)
+                {
+                    Some(def_id)
+                } else {
+                    None
+                }
+            },
+            ExprKind::MethodCall(_, _, args, _) if args.iter().any(|arg| arg.hir_id == closure) => {
+                cx.typeck_results().type_dependent_def_id(call.hir_id)
+            },
+            _ => None,
+        };
+        callee.is_some_and(|callee| self.spawn_blocking.contains(&callee))
+    }
+}
+
+impl_lint_pass!(BlockingInAsync => [BLOCKING_IN_ASYNC]);
+
+impl<'tcx> LateLintPass<'tcx> for BlockingInAsync {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
+        let (callee, callee_span) = match expr.kind {
+            ExprKind::Call(func, _)
+                if let ExprKind::Path(qpath) = &func.kind
+                    && let Res::Def(_, def_id) = cx.qpath_res(qpath, func.hir_id) =>
+            {
+                (def_id, func.span)
+            },
+            ExprKind::MethodCall(name, ..)
+                if let Some(def_id) = cx.typeck_results().type_dependent_def_id(expr.hir_id) =>
+            {
+                (def_id, name.ident.span)
+            },
+            _ => return,
+        };
+        if let Some((path, disallowed_path)) = self
+            .configured
+            .get(&callee)
+            .map(|&(path, disallowed_path)| (path, Some(disallowed_path)))
+            .or_else(|| self.blocking.get(&callee).map(|&path| (path, None)))
+            && !expr.span.from_expansion()
+            && self.is_in_async_context(cx, expr.hir_id)
+        {
+            span_lint_and_then(
+                cx,
+                BLOCKING_IN_ASYNC,
+                expr.span,
+                format!("call to the blocking function `{path}` in an async context"),
+                |diag| {
+                    if let Some(disallowed_path) = disallowed_path {
+                        disallowed_path.diag_amendment(callee_span)(diag);
+                    }
+                    diag.help("use an async alternative or move the call to a thread where blocking is allowed");
+                },
+            );
+        }
+    }
+}
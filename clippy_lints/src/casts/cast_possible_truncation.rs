@@ -3,6 +3,7 @@ use clippy_utils::diagnostics::{span_lint, span_lint_and_then};
 use clippy_utils::source::snippet;
 use clippy_utils::sugg::Sugg;
 use clippy_utils::ty::{get_discriminant_value, is_isize_or_usize};
+use clippy_utils::value_range::guarded_int_range;
 use clippy_utils::{expr_or_init, is_in_const_context, sym};
 use rustc_abi::IntegerType;
 use rustc_errors::{Applicability, Diag};
@@ -93,6 +94,10 @@ pub(super) fn check(
 ) {
     let msg = match (cast_from.kind(), utils::int_ty_to_nbits(cx.tcx, cast_to)) {
         (ty::Int(_) | ty::Uint(_), Some(to_nbits)) => {
+            if guarded_int_range(cx, cast_expr).is_some_and(|range| range.fits_in(cx, cast_to)) {
+                return;
+            }
+
             let from_nbits = apply_reductions(
                 cx,
                 utils::int_ty_to_nbits(cx.tcx, cast_from).unwrap(),
@@ -5,6 +5,7 @@ use clippy_utils::consts::{ConstEvalCtxt, Constant};
 use clippy_utils::diagnostics::span_lint_and_then;
 use clippy_utils::msrvs::{self, Msrv};
 use clippy_utils::sugg::Sugg;
+use clippy_utils::value_range::{IntRange, guarded_int_range};
 use clippy_utils::visitors::{Descend, for_each_expr_without_closures};
 use clippy_utils::{method_chain_args, sext, sym};
 use rustc_errors::Applicability;
@@ -96,6 +97,11 @@ fn should_lint<'cx>(cx: &LateContext<'cx>, cast_op: &Expr<'_>, cast_from: Ty<'cx
                 return false;
             }
 
+            // Don't lint if `cast_op` is bounded to be positive, e.g. by an enclosing `if`.
+            if guarded_int_range(cx, cast_op).is_some_and(IntRange::is_non_negative) {
+                return false;
+            }
+
             true
         },
 
@@ -0,0 +1,229 @@
+use crate::declared_lints::LINTS;
+use crate::deprecated_lints::{DEPRECATED, RENAMED};
+use clippy_config::Conf;
+use clippy_config::types::{CustomLint, CustomLintLevel};
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::expr_pattern::fill_template;
+use clippy_utils::is_lint_allowed;
+use clippy_utils::paths::{PathNS, lookup_path_str};
+use clippy_utils::sugg::Sugg;
+use rustc_errors::Applicability;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::{Expr, PrimTy};
+use rustc_lint::{LateContext, LateLintPass, Level, Lint, LintPass, LintStore, LintVec};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::Symbol;
+
+/// Registers the lints defined with the `custom-lint` configuration, and the pass checking them.
+pub fn register(store: &mut LintStore, conf: &'static Conf) {
+    let mut lints: Vec<(&'static Lint, &'static CustomLint)> = Vec::new();
+    let mut conflicts = Vec::new();
+    for custom in &conf.custom_lint {
+        let name = format!("clippy::{}", custom.name);
+        if LINTS.iter().any(|info| info.name_lower() == custom.name)
+            || RENAMED.iter().chain(DEPRECATED).any(|&(old_name, _)| old_name == name)
+            || lints.iter().any(|(_, other)| other.name == custom.name)
+            || store.find_lints(&name).is_some()
+        {
+            conflicts.push(&*custom.name);
+            continue;
+        }
+        // Lints have to be `'static`. They're only registered once, so leaking them is fine.
+        let lint: &'static Lint = Box::leak(Box::new(Lint {
+            name: Box::leak(format!("clippy::{}", custom.name.to_ascii_uppercase()).into_boxed_str()),
+            default_level: match custom.level {
+                CustomLintLevel::Allow => Level::Allow,
+                CustomLintLevel::Warn => Level::Warn,
+                CustomLintLevel::Deny => Level::Deny,
+            },
+            desc: &custom.message,
+            is_externally_loaded: true,
+            ..Lint::default_fields_for_macro()
+        }));
+        store.register_lints(&[lint]);
+        lints.push((lint, custom));
+    }
+
+    if !lints.is_empty() || !conflicts.is_empty() {
+        store.register_late_pass(move |tcx| Box::new(CustomLints::new(tcx, &lints, &conflicts)));
+    }
+}
+
+struct CustomLints {
+    lints: Vec<CheckedLint>,
+}
+
+struct CheckedLint {
+    lint: &'static Lint,
+    custom: &'static CustomLint,
+    /// The types of the metavariables, or `None` if one of them couldn't be resolved.
+    types: Option<Vec<(&'static str, TypeConstraint)>>,
+}
+
+enum TypeConstraint {
+    Adt(Vec<DefId>),
+    Prim(PrimTy),
+    Ref(Box<TypeConstraint>),
+    Slice(Box<TypeConstraint>),
+    Tuple(Vec<TypeConstraint>),
+}
+
+impl TypeConstraint {
+    /// Parses a type written in the configuration. Only paths to ADTs, primitive types,
+    /// references, slices and tuples are supported.
+    fn parse(tcx: TyCtxt<'_>, ty: &str) -> Option<Self> {
+        let ty = ty.trim();
+        if let Some(inner) = ty.strip_prefix('&') {
+            let inner = inner.trim_start();
+            let inner = inner.strip_prefix("mut ").unwrap_or(inner);
+            Some(Self::Ref(Box::new(Self::parse(tcx, inner)?)))
+        } else if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+            Some(Self::Slice(Box::new(Self::parse(tcx, inner)?)))
+        } else if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            split_tuple(inner)
+                .into_iter()
+                .map(|ty| Self::parse(tcx, ty))
+                .collect::<Option<_>>()
+                .map(Self::Tuple)
+        } else if let Some(prim) = PrimTy::from_name(Symbol::intern(ty)) {
+            Some(Self::Prim(prim))
+        } else if ty.contains("::")
+            && ty
+                .split("::")
+                .all(|segment| !segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        {
+            let path = match ty.strip_prefix("crate::") {
+                Some(rest) => format!("{}::{rest}", tcx.crate_name(LOCAL_CRATE)),
+                None => ty.to_owned(),
+            };
+            let def_ids = lookup_path_str(tcx, PathNS::Type, &path);
+            (!def_ids.is_empty()).then_some(Self::Adt(def_ids))
+        } else {
+            None
+        }
+    }
+
+    fn matches(&self, ty: Ty<'_>) -> bool {
+        match (self, ty.kind()) {
+            (Self::Adt(def_ids), ty::Adt(adt, _)) => def_ids.contains(&adt.did()),
+            (Self::Prim(PrimTy::Str), _) => ty.is_str(),
+            (Self::Prim(prim), _) => ty.primitive_symbol() == Some(prim.name()),
+            (Self::Ref(inner), ty::Ref(_, ty, _)) | (Self::Slice(inner), ty::Slice(ty)) => inner.matches(*ty),
+            (Self::Tuple(inner), ty::Tuple(tys)) => {
+                inner.len() == tys.len() && inner.iter().zip(*tys).all(|(inner, ty)| inner.matches(ty))
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Splits the fields of a tuple type on the commas which aren't nested in another type.
+fn split_tuple(fields: &str) -> Vec<&str> {
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut res = Vec::new();
+    for (i, c) in fields.char_indices() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                res.push(&fields[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    if !fields[start..].trim().is_empty() {
+        res.push(&fields[start..]);
+    }
+    res
+}
+
+impl CustomLints {
+    fn new(tcx: TyCtxt<'_>, lints: &[(&'static Lint, &'static CustomLint)], conflicts: &[&str]) -> Self {
+        for name in conflicts {
+            tcx.sess
+                .dcx()
+                .err(format!("custom lint `{name}` has the same name as another lint"));
+        }
+
+        let lints = lints
+            .iter()
+            .map(|&(lint, custom)| CheckedLint {
+                lint,
+                custom,
+                types: custom
+                    .types
+                    .iter()
+                    .map(|(var, ty)| Some((&**var, resolve_type(tcx, custom, ty)?)))
+                    .collect(),
+            })
+            .collect();
+        Self { lints }
+    }
+}
+
+fn resolve_type(tcx: TyCtxt<'_>, custom: &CustomLint, ty: &str) -> Option<TypeConstraint> {
+    let mut constraint = TypeConstraint::parse(tcx, ty);
+    // References to the matched expressions are ignored, see `check_expr`.
+    while let Some(TypeConstraint::Ref(inner)) = constraint {
+        constraint = Some(*inner);
+    }
+    if constraint.is_none() {
+        tcx.sess.dcx().warn(format!(
+            "`{ty}` used by custom lint `{}` does not refer to a reachable type",
+            custom.name
+        ));
+    }
+    constraint
+}
+
+#[expect(
+    rustc::lint_pass_impl_without_macro,
+    reason = "the lints are only known once the configuration is read"
+)]
+impl LintPass for CustomLints {
+    fn name(&self) -> &'static str {
+        "CustomLints"
+    }
+
+    fn get_lints(&self) -> LintVec {
+        self.lints.iter().map(|lint| lint.lint).collect()
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for CustomLints {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        if expr.span.from_expansion() {
+            return;
+        }
+        for CheckedLint { lint, custom, types } in &self.lints {
+            if let Some(types) = types
+                && !is_lint_allowed(cx, lint, expr.hir_id)
+                && let Some(bindings) = custom.pattern.matches(cx, expr)
+                && types.iter().all(|(var, ty)| {
+                    bindings
+                        .iter()
+                        .find(|(bound, _)| bound == var)
+                        .is_some_and(|(_, e)| ty.matches(cx.typeck_results().expr_ty(e).peel_refs()))
+                })
+            {
+                span_lint_and_then(cx, lint, expr.span, &*custom.message, |diag| {
+                    let mut app = Applicability::MachineApplicable;
+                    if let Some(replacement) = &custom.replacement
+                        && let Some(sugg) = fill_template(replacement, |var| {
+                            let (_, e) = bindings.iter().find(|(bound, _)| *bound == var)?;
+                            Some(
+                                Sugg::hir_with_context(cx, e, expr.span.ctxt(), "..", &mut app)
+                                    .maybe_paren()
+                                    .to_string(),
+                            )
+                        })
+                    {
+                        diag.span_suggestion(expr.span, "try", sugg, app);
+                    }
+                });
+            }
+        }
+    }
+}
@@ -1,13 +1,19 @@
-use super::{DocHeaders, MISSING_ERRORS_DOC, MISSING_PANICS_DOC, MISSING_SAFETY_DOC, UNNECESSARY_SAFETY_DOC};
+use super::{
+    DocHeaders, MISSING_ERRORS_DOC, MISSING_PANICS_DOC, MISSING_SAFETY_DOC, UNNECESSARY_PANICS_DOC,
+    UNNECESSARY_SAFETY_DOC,
+};
 use clippy_utils::diagnostics::{span_lint, span_lint_and_note};
 use clippy_utils::macros::{is_panic, root_macro_call_first_node};
 use clippy_utils::res::MaybeDef;
 use clippy_utils::ty::implements_trait_with_env;
 use clippy_utils::visitors::for_each_expr;
 use clippy_utils::{fulfill_or_allowed, is_doc_hidden, is_inside_always_const_context, method_chain_args, return_ty};
-use rustc_hir::{BodyId, FnSig, OwnerId, Safety};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::{BinOpKind, BodyId, Expr, ExprKind, FnSig, OwnerId, Pat, PatKind, Safety, StmtKind, UnOp};
 use rustc_lint::LateContext;
-use rustc_middle::ty;
+use rustc_middle::ty::{self, Instance, Ty, TypeckResults};
 use rustc_span::{Span, sym};
 use std::ops::ControlFlow;
 
@@ -18,6 +24,7 @@ pub fn check(
     headers: DocHeaders,
     body_id: Option<BodyId>,
     check_private_items: bool,
+    panics: &mut PanicAnalysis,
 ) {
     if !check_private_items && !cx.effective_visibilities.is_exported(owner_id.def_id) {
         return; // Private functions do not require doc comments
@@ -49,18 +56,40 @@ pub fn check(
         ),
         _ => (),
     }
-    if !headers.panics
-        && let Some(body_id) = body_id
-        && let Some(panic_span) = find_panic(cx, body_id)
-    {
-        span_lint_and_note(
-            cx,
-            MISSING_PANICS_DOC,
-            span,
-            "docs for function which may panic missing `# Panics` section",
-            Some(panic_span),
-            "first possible panic found here",
-        );
+    if let Some(body_id) = body_id {
+        if headers.panics {
+            if panics.summary(cx, owner_id.def_id) == PanicSummary::NoPanic {
+                span_lint(
+                    cx,
+                    UNNECESSARY_PANICS_DOC,
+                    span,
+                    "docs for function which can't panic have unnecessary `# Panics` section",
+                );
+            }
+        } else if let Some(panic_span) = find_panic(cx, body_id) {
+            span_lint_and_note(
+                cx,
+                MISSING_PANICS_DOC,
+                span,
+                "docs for function which may panic missing `# Panics` section",
+                Some(panic_span),
+                "first possible panic found here",
+            );
+        } else if panics.transitive
+            && let PanicSummary::MayPanic {
+                span: call_span,
+                callee: Some(callee),
+            } = panics.summary(cx, owner_id.def_id)
+        {
+            span_lint_and_note(
+                cx,
+                MISSING_PANICS_DOC,
+                span,
+                "docs for function which may panic missing `# Panics` section",
+                Some(call_span),
+                format!("`{}` may panic", cx.tcx.def_path_str(callee)),
+            );
+        }
     }
     if !headers.errors {
         if return_ty(cx, owner_id).is_diag_item(cx, sym::Result) {
@@ -104,27 +133,11 @@ fn find_panic(cx: &LateContext<'_>, body_id: BodyId) -> Option<Span> {
             return ControlFlow::<!>::Continue(());
         }
 
-        if let Some(macro_call) = root_macro_call_first_node(cx, expr)
-            && (is_panic(cx, macro_call.def_id)
-                || matches!(
-                    cx.tcx.get_diagnostic_name(macro_call.def_id),
-                    Some(sym::assert_macro | sym::assert_eq_macro | sym::assert_ne_macro)
-                ))
-            && !fulfill_or_allowed(cx, MISSING_PANICS_DOC, [expr.hir_id])
-            && panic_span.is_none()
-        {
-            panic_span = Some(macro_call.span);
-        }
-
-        // check for `unwrap` and `expect` for both `Option` and `Result`
-        if let Some(arglists) =
-            method_chain_args(expr, &[sym::unwrap]).or_else(|| method_chain_args(expr, &[sym::expect]))
-            && let receiver_ty = typeck.expr_ty(arglists[0].0).peel_refs()
-            && matches!(receiver_ty.opt_diag_name(cx), Some(sym::Option | sym::Result))
+        if let Some(span) = direct_panic(cx, typeck, expr)
             && !fulfill_or_allowed(cx, MISSING_PANICS_DOC, [expr.hir_id])
             && panic_span.is_none()
         {
-            panic_span = Some(expr.span);
+            panic_span = Some(span);
         }
 
         // Visit all nodes to fulfill any `#[expect]`s after the first linted panic
@@ -132,3 +145,292 @@ fn find_panic(cx: &LateContext<'_>, body_id: BodyId) -> Option<Span> {
     });
     panic_span
 }
+
+/// Checks if the expression is a panicking macro call, or a call to `unwrap` or `expect` on an
+/// `Option` or `Result`.
+fn direct_panic(cx: &LateContext<'_>, typeck: &TypeckResults<'_>, expr: &Expr<'_>) -> Option<Span> {
+    if let Some(macro_call) = root_macro_call_first_node(cx, expr)
+        && (is_panic(cx, macro_call.def_id)
+            || matches!(
+                cx.tcx.get_diagnostic_name(macro_call.def_id),
+                Some(sym::assert_macro | sym::assert_eq_macro | sym::assert_ne_macro)
+            ))
+    {
+        return Some(macro_call.span);
+    }
+
+    // check for `unwrap` and `expect` for both `Option` and `Result`
+    if let Some(arglists) = method_chain_args(expr, &[sym::unwrap]).or_else(|| method_chain_args(expr, &[sym::expect]))
+        && let receiver_ty = typeck.expr_ty(arglists[0].0).peel_refs()
+        && matches!(receiver_ty.opt_diag_name(cx), Some(sym::Option | sym::Result))
+    {
+        return Some(expr.span);
+    }
+    None
+}
+
+/// Whether a function may panic.
+#[derive(Clone, Copy, PartialEq)]
+enum PanicSummary {
+    /// The function contains a panic, or calls a function of the same crate which may panic.
+    MayPanic {
+        /// The first panic, or the call which may panic.
+        span: Span,
+        /// The function being called if the panic is in another function.
+        callee: Option<DefId>,
+    },
+    /// The function may panic in code which isn't analyzed, e.g. in a function of another crate.
+    Unknown,
+    /// The function can't panic.
+    NoPanic,
+}
+
+/// Computes whether the functions of the crate may panic, taking the functions they call into
+/// account.
+pub struct PanicAnalysis {
+    /// Whether `missing_panics_doc` uses the summaries.
+    transitive: bool,
+    summaries: FxHashMap<LocalDefId, PanicSummary>,
+    /// The functions whose summary isn't final yet, in the order they were reached. A call to one
+    /// of these is part of a cycle. A function stays on the stack until the summary of the first
+    /// function of its cycle is final, at which point the whole cycle is popped.
+    stack: Vec<LocalDefId>,
+    /// The position of each function on the `stack`.
+    on_stack: FxHashMap<LocalDefId, usize>,
+}
+
+impl PanicAnalysis {
+    pub fn new(transitive: bool) -> Self {
+        Self {
+            transitive,
+            summaries: FxHashMap::default(),
+            stack: Vec::new(),
+            on_stack: FxHashMap::default(),
+        }
+    }
+
+    fn summary(&mut self, cx: &LateContext<'_>, def_id: LocalDefId) -> PanicSummary {
+        self.summary_in_cycle(cx, def_id).0
+    }
+
+    /// Gets the summary of the function, along with the lowest position on the stack of the
+    /// functions it depends on. The summary of a function in a cycle of calls isn't final until
+    /// the first function of the cycle has been analyzed.
+    fn summary_in_cycle(&mut self, cx: &LateContext<'_>, def_id: LocalDefId) -> (PanicSummary, Option<usize>) {
+        if let Some(&summary) = self.summaries.get(&def_id) {
+            return (summary, None);
+        }
+        if let Some(&pos) = self.on_stack.get(&def_id) {
+            // Reaching the function again can't add any panics which aren't found by the
+            // outer call.
+            return (PanicSummary::NoPanic, Some(pos));
+        }
+        let pos = self.stack.len();
+        self.stack.push(def_id);
+        self.on_stack.insert(def_id, pos);
+        let (summary, cycle) = self.compute(cx, def_id);
+        let may_panic = matches!(summary, PanicSummary::MayPanic { .. });
+        // A panic is found no matter what the rest of the cycle does.
+        if may_panic {
+            self.summaries.insert(def_id, summary);
+        }
+        if cycle.is_some_and(|cycle| cycle < pos) {
+            // The summary of a panicking function is final, even though it stays on the stack.
+            return (summary, if may_panic { None } else { cycle });
+        }
+
+        // This is the first function of its cycle. Every function of the cycle calls every other
+        // one, so they all share its summary unless it may panic. The other functions then need
+        // their own panic span, so they are analyzed again once they are called.
+        for member in self.stack.drain(pos..) {
+            self.on_stack.remove(&member);
+            if !may_panic {
+                self.summaries.entry(member).or_insert(summary);
+            }
+        }
+        (summary, None)
+    }
+
+    fn compute(&mut self, cx: &LateContext<'_>, def_id: LocalDefId) -> (PanicSummary, Option<usize>) {
+        let Some(body) = cx.tcx.hir_maybe_body_owned_by(def_id) else {
+            return (PanicSummary::Unknown, None);
+        };
+        let typeck = cx.tcx.typeck(def_id);
+        let typing_env = ty::TypingEnv::post_analysis(cx.tcx, def_id);
+        let mut unknown = body
+            .params
+            .iter()
+            .any(|param| pat_has_unknown_drop(cx, typeck, typing_env, param.pat));
+        let mut cycle = None;
+        let may_panic = for_each_expr(cx, body.value, |expr| {
+            if is_inside_always_const_context(cx.tcx, expr.hir_id) {
+                return ControlFlow::Continue(());
+            }
+            // The `Drop` implementations of the dropped locals and temporaries aren't analyzed.
+            match expr.kind {
+                ExprKind::Block(block, _) => {
+                    unknown |= block.stmts.iter().any(|stmt| match stmt.kind {
+                        StmtKind::Let(local) => pat_has_unknown_drop(cx, typeck, typing_env, local.pat),
+                        StmtKind::Semi(e) => has_unknown_drop(cx, typing_env, typeck.expr_ty(e)),
+                        StmtKind::Expr(_) | StmtKind::Item(_) => false,
+                    });
+                },
+                ExprKind::Match(_, arms, _) => {
+                    unknown |= arms
+                        .iter()
+                        .any(|arm| pat_has_unknown_drop(cx, typeck, typing_env, arm.pat));
+                },
+                ExprKind::Let(let_expr) => unknown |= pat_has_unknown_drop(cx, typeck, typing_env, let_expr.pat),
+                _ => {},
+            }
+            if let Some(span) = direct_panic(cx, typeck, expr) {
+                if fulfill_or_allowed(cx, MISSING_PANICS_DOC, [expr.hir_id]) {
+                    // The panic is intentionally undocumented, but it can still happen.
+                    unknown = true;
+                    return ControlFlow::Continue(());
+                }
+                return ControlFlow::Break(PanicSummary::MayPanic { span, callee: None });
+            }
+            let callee = match expr.kind {
+                ExprKind::Call(func, _) => {
+                    if let ExprKind::Path(qpath) = &func.kind {
+                        match typeck.qpath_res(qpath, func.hir_id) {
+                            // Constructing a tuple struct or variant.
+                            Res::Def(DefKind::Ctor(..), _) | Res::SelfCtor(_) => return ControlFlow::Continue(()),
+                            Res::Def(DefKind::Fn | DefKind::AssocFn, callee) => {
+                                Some((callee, typeck.node_args(func.hir_id)))
+                            },
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    }
+                },
+                // Overloaded operators call the trait method.
+                ExprKind::MethodCall(..) | ExprKind::Binary(..) | ExprKind::AssignOp(..) | ExprKind::Unary(..)
+                    if let Some(callee) = typeck.type_dependent_def_id(expr.hir_id) =>
+                {
+                    Some((callee, typeck.node_args(expr.hir_id)))
+                },
+                ExprKind::MethodCall(..) => None,
+                // Indexing and integer arithmetic can panic, but these are rarely documented.
+                ExprKind::Index(..) => {
+                    unknown = true;
+                    return ControlFlow::Continue(());
+                },
+                ExprKind::Binary(op, lhs, _) => {
+                    unknown |= is_panicking_arith(typeck, op.node, lhs);
+                    return ControlFlow::Continue(());
+                },
+                ExprKind::AssignOp(op, lhs, _) => {
+                    unknown |= is_panicking_arith(typeck, op.node.into(), lhs);
+                    return ControlFlow::Continue(());
+                },
+                ExprKind::Unary(UnOp::Neg, e) => {
+                    unknown |= typeck.expr_ty(e).is_integral();
+                    return ControlFlow::Continue(());
+                },
+                _ => return ControlFlow::Continue(()),
+            };
+            let (summary, callee_cycle) = self.call_summary(cx, typing_env, callee);
+            cycle = cycle.into_iter().chain(callee_cycle).min();
+            match summary {
+                PanicSummary::MayPanic { callee, .. } => {
+                    return ControlFlow::Break(PanicSummary::MayPanic {
+                        span: expr.span,
+                        callee,
+                    });
+                },
+                PanicSummary::Unknown => unknown = true,
+                PanicSummary::NoPanic => {},
+            }
+            ControlFlow::Continue(())
+        });
+        match may_panic {
+            Some(summary) => (summary, cycle),
+            None if unknown => (PanicSummary::Unknown, cycle),
+            None => (PanicSummary::NoPanic, cycle),
+        }
+    }
+
+    /// Gets the summary of the called function. The `callee` of a returned
+    /// [`PanicSummary::MayPanic`] is the called function.
+    fn call_summary<'tcx>(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        typing_env: ty::TypingEnv<'tcx>,
+        callee: Option<(DefId, ty::GenericArgsRef<'tcx>)>,
+    ) -> (PanicSummary, Option<usize>) {
+        let Some((callee, args)) = callee else {
+            return (PanicSummary::Unknown, None);
+        };
+        // Desugared calls, e.g. the `into_iter` call of a `for` loop, don't record their generic args.
+        if args.len() != cx.tcx.generics_of(callee).count() {
+            return (PanicSummary::Unknown, None);
+        }
+        // Resolve trait methods to the implementation being called.
+        let callee = match Instance::try_resolve(cx.tcx, typing_env, callee, args) {
+            Ok(Some(instance)) if let ty::InstanceKind::Item(callee) = instance.def => callee,
+            _ => return (PanicSummary::Unknown, None),
+        };
+        let Some(local) = callee.as_local() else {
+            return (PanicSummary::Unknown, None);
+        };
+        match self.summary_in_cycle(cx, local) {
+            (PanicSummary::MayPanic { span, .. }, cycle) => (
+                PanicSummary::MayPanic {
+                    span,
+                    callee: Some(callee),
+                },
+                cycle,
+            ),
+            summary => summary,
+        }
+    }
+}
+
+/// Checks if the pattern binds or drops a value whose `Drop` implementation isn't analyzed.
+fn pat_has_unknown_drop<'tcx>(
+    cx: &LateContext<'tcx>,
+    typeck: &TypeckResults<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
+    pat: &Pat<'_>,
+) -> bool {
+    let mut unknown = false;
+    pat.walk_always(|p| {
+        if matches!(p.kind, PatKind::Binding(..)) || p.hir_id == pat.hir_id {
+            unknown |= has_unknown_drop(cx, typing_env, typeck.pat_ty(p));
+        }
+    });
+    unknown
+}
+
+/// Checks if dropping a value of the type may run a `Drop` implementation outside of the standard
+/// library, or one which isn't known.
+fn has_unknown_drop<'tcx>(cx: &LateContext<'tcx>, typing_env: ty::TypingEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    ty.needs_drop(cx.tcx, typing_env)
+        && ty
+            .walk()
+            .filter_map(ty::GenericArg::as_type)
+            .any(|ty| match *ty.kind() {
+                ty::Adt(adt, _) => adt.destructor(cx.tcx).is_some_and(|dtor| {
+                    !matches!(cx.tcx.crate_name(dtor.did.krate), sym::core | sym::alloc | sym::std)
+                }),
+                ty::Dynamic(..) | ty::Param(_) | ty::Alias(..) => true,
+                _ => false,
+            })
+}
+
+/// Checks if the operation is integer arithmetic which can overflow or divide by zero.
+fn is_panicking_arith(typeck: &TypeckResults<'_>, op: BinOpKind, lhs: &Expr<'_>) -> bool {
+    matches!(
+        op,
+        BinOpKind::Add
+            | BinOpKind::Sub
+            | BinOpKind::Mul
+            | BinOpKind::Div
+            | BinOpKind::Rem
+            | BinOpKind::Shl
+            | BinOpKind::Shr
+    ) && typeck.expr_ty(lhs).peel_refs().is_integral()
+}
@@ -4,6 +4,7 @@ use clippy_config::Conf;
 use clippy_utils::attrs::is_doc_hidden;
 use clippy_utils::diagnostics::{span_lint, span_lint_and_help, span_lint_and_then};
 use clippy_utils::{is_entrypoint_fn, is_trait_impl_item};
+use missing_headers::PanicAnalysis;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::Applicability;
 use rustc_hir::{Attribute, ImplItemKind, ItemKind, Node, Safety, TraitItemKind};
@@ -193,6 +194,9 @@ declare_clippy_lint! {
     /// }
     /// ```
     ///
+    /// With the `missing-panics-doc-transitive` configuration, functions which call another
+    /// function of the same crate which may panic are linted as well.
+    ///
     /// Individual panics within a function can be ignored with `#[expect]` or
     /// `#[allow]`:
     ///
@@ -211,6 +215,44 @@ declare_clippy_lint! {
     "`pub fn` may panic without `# Panics` in doc comment"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks the doc comments of publicly visible functions which can't panic and warns if there
+    /// is a `# Panics` section.
+    ///
+    /// A function is only considered to not panic if none of its code can panic, including all
+    /// of the functions it calls. This is only known for functions of the same crate, so a call
+    /// to a function of another crate is assumed to panic. Integer arithmetic and indexing are
+    /// also assumed to panic.
+    ///
+    /// ### Why is this bad?
+    /// Callers may go to some lengths to avoid a panic which can't happen. The section is often
+    /// left over after the code which could panic was removed.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// /// Returns the first element.
+    /// ///
+    /// /// # Panics
+    /// ///
+    /// /// Panics if the slice is empty.
+    /// pub fn first(x: &[u32]) -> Option<&u32> {
+    ///     if let [first, ..] = x { Some(first) } else { None }
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// /// Returns the first element, or `None` if the slice is empty.
+    /// pub fn first(x: &[u32]) -> Option<&u32> {
+    ///     if let [first, ..] = x { Some(first) } else { None }
+    /// }
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub UNNECESSARY_PANICS_DOC,
+    pedantic,
+    "`pub fn` which can't panic with `# Panics` in doc comment"
+}
+
 declare_clippy_lint! {
     /// ### What it does
     /// Checks for `fn main() { .. }` in doctests
@@ -701,6 +743,7 @@ declare_clippy_lint! {
 pub struct Documentation {
     valid_idents: FxHashSet<String>,
     check_private_items: bool,
+    panics: PanicAnalysis,
 }
 
 impl Documentation {
@@ -708,6 +751,7 @@ impl Documentation {
         Self {
             valid_idents: conf.doc_valid_idents.iter().cloned().collect(),
             check_private_items: conf.check_private_items,
+            panics: PanicAnalysis::new(conf.missing_panics_doc_transitive),
         }
     }
 }
@@ -721,6 +765,7 @@ impl_lint_pass!(Documentation => [
     MISSING_SAFETY_DOC,
     MISSING_ERRORS_DOC,
     MISSING_PANICS_DOC,
+    UNNECESSARY_PANICS_DOC,
     NEEDLESS_DOCTEST_MAIN,
     TEST_ATTR_IN_DOCTEST,
     UNNECESSARY_SAFETY_DOC,
@@ -768,6 +813,7 @@ impl<'tcx> LateLintPass<'tcx> for Documentation {
                                 headers,
                                 Some(body),
                                 self.check_private_items,
+                                &mut self.panics,
                             );
                         }
                     },
@@ -793,7 +839,15 @@ impl<'tcx> LateLintPass<'tcx> for Documentation {
                 if let TraitItemKind::Fn(sig, ..) = trait_item.kind
                     && !trait_item.span.in_external_macro(cx.tcx.sess.source_map())
                 {
-                    missing_headers::check(cx, trait_item.owner_id, sig, headers, None, self.check_private_items);
+                    missing_headers::check(
+                        cx,
+                        trait_item.owner_id,
+                        sig,
+                        headers,
+                        None,
+                        self.check_private_items,
+                        &mut self.panics,
+                    );
                 }
             },
             Node::ImplItem(impl_item) => {
@@ -808,6 +862,7 @@ impl<'tcx> LateLintPass<'tcx> for Documentation {
                         headers,
                         Some(body_id),
                         self.check_private_items,
+                        &mut self.panics,
                     );
                 }
             },
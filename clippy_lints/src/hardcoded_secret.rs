@@ -0,0 +1,275 @@
+use clippy_config::Conf;
+use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::{is_in_test, sym};
+use rustc_ast::LitKind;
+use rustc_hir::{Expr, ExprKind, ImplItemKind, ItemKind, Node, PatKind, QPath, TraitItemKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_session::impl_lint_pass;
+use rustc_span::Ident;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for string and byte string literals which look like secrets, such as passwords or
+    /// API keys. A literal is considered a secret if either:
+    /// * it's assigned to a constant, variable or field with a name listed in the `secret-names`
+    ///   configuration, e.g. `API_KEY`, and doesn't look like a placeholder or an identifier, e.g.
+    ///   the name of the environment variable holding the key, or
+    /// * it starts with the prefix of a well known kind of key, e.g. `ghp_` for GitHub tokens,
+    ///   and looks randomly generated.
+    ///
+    /// Values listed in the `allowed-secrets` configuration and literals in tests are ignored.
+    ///
+    /// ### Why restrict this?
+    /// Secrets in the source code end up in version control and in the compiled binary, where
+    /// anyone with access to either can read them. They also can't be rotated without a new
+    /// release.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// const API_KEY: &str = "a8f5f167f44f4964e6c998dee827110c";
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// let api_key = std::env::var("API_KEY");
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub HARDCODED_SECRET,
+    restriction,
+    "string literals which look like passwords or keys"
+}
+
+/// Prefixes of well known kinds of keys and tokens.
+const KEY_PREFIXES: &[&str] = &[
+    "AKIA",
+    "ASIA",
+    "AIza",
+    "ghp_",
+    "gho_",
+    "ghs_",
+    "ghu_",
+    "github_pat_",
+    "glpat-",
+    "sk_live_",
+    "rk_live_",
+    "sk-",
+    "xoxa-",
+    "xoxb-",
+    "xoxp-",
+    "SG.",
+];
+
+/// The minimum length and entropy (in bits per character) of a secret assigned to a secret name.
+/// Shorter or more repetitive values are most likely placeholders.
+const MIN_NAMED_SECRET_LEN: usize = 8;
+const MIN_NAMED_SECRET_ENTROPY: f64 = 2.5;
+
+/// The minimum length and entropy (in bits per character) of a value starting with one of the
+/// [`KEY_PREFIXES`].
+const MIN_KEY_LEN: usize = 16;
+const MIN_KEY_ENTROPY: f64 = 3.5;
+
+pub struct HardcodedSecret {
+    /// The words of each secret name.
+    secret_names: Vec<Vec<String>>,
+    allowed_secrets: &'static [String],
+}
+
+impl HardcodedSecret {
+    pub fn new(conf: &'static Conf) -> Self {
+        Self {
+            secret_names: conf
+                .secret_names
+                .iter()
+                .filter(|name| *name != "..")
+                .map(|name| words(name))
+                .collect(),
+            allowed_secrets: &conf.allowed_secrets,
+        }
+    }
+
+    fn is_secret_name(&self, ident: Ident) -> bool {
+        let ident = words(ident.as_str());
+        self.secret_names
+            .iter()
+            .any(|name| !name.is_empty() && ident.windows(name.len()).any(|window| window == name.as_slice()))
+    }
+}
+
+impl_lint_pass!(HardcodedSecret => [HARDCODED_SECRET]);
+
+impl<'tcx> LateLintPass<'tcx> for HardcodedSecret {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        if let ExprKind::Lit(lit) = expr.kind
+            && !expr.span.from_expansion()
+            && let Some(value) = match lit.node {
+                LitKind::Str(s, _) => Some(s.to_string()),
+                LitKind::ByteStr(bytes, _) => str::from_utf8(bytes.as_byte_str()).ok().map(ToString::to_string),
+                _ => None,
+            }
+            && !self
+                .allowed_secrets
+                .iter()
+                .any(|pattern| matches_pattern(pattern, &value))
+        {
+            let msg = if let Some(name) = binding_name(cx, expr)
+                && self.is_secret_name(name)
+                && is_named_secret(&value)
+            {
+                format!("hardcoded secret assigned to `{name}`")
+            } else if is_key(&value) {
+                "hardcoded secret key".to_owned()
+            } else {
+                return;
+            };
+            if is_in_test(cx.tcx, expr.hir_id) {
+                return;
+            }
+            span_lint_and_help(
+                cx,
+                HARDCODED_SECRET,
+                expr.span,
+                msg,
+                None,
+                "load the secret at runtime, e.g. from an environment variable or a secret store",
+            );
+        }
+    }
+}
+
+/// Gets the name of the constant, variable or field the literal is assigned to. This looks
+/// through borrows and conversions to an owned string.
+fn binding_name(cx: &LateContext<'_>, lit: &Expr<'_>) -> Option<Ident> {
+    let mut child = lit.hir_id;
+    for (_, node) in cx.tcx.hir_parent_iter(lit.hir_id) {
+        match node {
+            Node::Expr(e) => match e.kind {
+                ExprKind::AddrOf(..) => {},
+                ExprKind::MethodCall(path, recv, [], _)
+                    if recv.hir_id == child
+                        && matches!(
+                            path.ident.name,
+                            sym::to_string | sym::to_owned | sym::into | sym::as_bytes
+                        ) => {},
+                ExprKind::Call(func, [_])
+                    if let ExprKind::Path(QPath::TypeRelative(_, path)) = func.kind
+                        && path.ident.name == sym::from => {},
+                ExprKind::Assign(lhs, rhs, _) if rhs.hir_id == child => {
+                    return match lhs.kind {
+                        ExprKind::Field(_, ident) => Some(ident),
+                        ExprKind::Path(QPath::Resolved(None, path)) => path.segments.last().map(|s| s.ident),
+                        _ => None,
+                    };
+                },
+                _ => return None,
+            },
+            Node::ExprField(field) => return Some(field.ident),
+            Node::LetStmt(local) => {
+                return if let PatKind::Binding(_, _, ident, _) = local.pat.kind {
+                    Some(ident)
+                } else {
+                    None
+                };
+            },
+            Node::Item(item) => {
+                return match item.kind {
+                    ItemKind::Const(ident, ..) | ItemKind::Static(_, ident, ..) => Some(ident),
+                    _ => None,
+                };
+            },
+            Node::ImplItem(item) if let ImplItemKind::Const(..) = item.kind => return Some(item.ident),
+            Node::TraitItem(item) if let TraitItemKind::Const(..) = item.kind => return Some(item.ident),
+            _ => return None,
+        }
+        child = cx.tcx.parent_hir_id(child);
+    }
+    None
+}
+
+/// Checks if a value assigned to a secret name looks like a secret rather than e.g. the name of
+/// an environment variable or a form field holding it.
+fn is_named_secret(value: &str) -> bool {
+    value.len() >= MIN_NAMED_SECRET_LEN
+        && !value.contains(char::is_whitespace)
+        && !is_identifier_like(value)
+        && shannon_entropy(value) >= MIN_NAMED_SECRET_ENTROPY
+}
+
+/// Checks if the value is made of letters of a single case separated by `_` or `.`, e.g.
+/// `GITHUB_API_KEY`, `password` or `db.password`.
+fn is_identifier_like(value: &str) -> bool {
+    let is_sep = |b: u8| b == b'_' || b == b'.';
+    value.bytes().all(|b| b.is_ascii_lowercase() || is_sep(b))
+        || value.bytes().all(|b| b.is_ascii_uppercase() || is_sep(b))
+}
+
+/// Checks if the value starts with a known key prefix and looks randomly generated, or is a
+/// private key in PEM format.
+fn is_key(value: &str) -> bool {
+    if value.starts_with("-----BEGIN") && value.contains("PRIVATE KEY-----") {
+        return true;
+    }
+    KEY_PREFIXES.iter().any(|prefix| value.starts_with(prefix))
+        && value.len() >= MIN_KEY_LEN
+        && !value.contains(char::is_whitespace)
+        && shannon_entropy(value) >= MIN_KEY_ENTROPY
+}
+
+/// The average number of bits of information per character.
+#[expect(clippy::cast_precision_loss)]
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for b in value.bytes() {
+        counts[usize::from(b)] += 1;
+    }
+    let len = value.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Splits an identifier into lowercase words at underscores and case changes.
+fn words(ident: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in ident.chars() {
+        if c == '_' || c == '-' {
+            prev_lower = false;
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    words.extend((!word.is_empty()).then_some(word));
+    words
+}
+
+/// Matches the value against a pattern in which `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // No `*` in the pattern.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
@@ -0,0 +1,364 @@
+use clippy_utils::diagnostics::span_lint_hir_and_then;
+use clippy_utils::res::{MaybeDef, MaybeResPath};
+use clippy_utils::{paths, sym};
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_hir::intravisit::{FnKind, Visitor, walk_block, walk_expr};
+use rustc_hir::{Block, Body, Expr, ExprKind, FnDecl, HirId, LetStmt, PatKind, StmtKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::{self, Ty};
+use rustc_session::impl_lint_pass;
+use rustc_span::{Span, Symbol};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for locks which are acquired in a different order in different places of the crate,
+    /// e.g. one function locks `a` and then `b` while holding the guard of `a`, and another
+    /// function locks `b` and then `a`.
+    ///
+    /// Locks are identified by the field (or static) of the `Mutex` or `RwLock`, so
+    /// `self.accounts.lock()` locks the same mutex in every method of the type.
+    ///
+    /// ### Why is this bad?
+    /// If two threads run both functions at the same time, each can acquire its first lock and
+    /// then wait forever for the other thread to release the second one.
+    ///
+    /// ### Known problems
+    /// Locks are only tracked within a function, guards passed to or returned from another
+    /// function are not followed. Different instances of the same type are considered to be the
+    /// same lock, so transferring between two values of a type by locking the source first is
+    /// linted if another function locks them in the opposite order.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use std::sync::Mutex;
+    /// struct Bank {
+    ///     accounts: Mutex<Vec<u64>>,
+    ///     log: Mutex<Vec<String>>,
+    /// }
+    ///
+    /// impl Bank {
+    ///     fn deposit(&self, amount: u64) {
+    ///         let mut accounts = self.accounts.lock().unwrap();
+    ///         let mut log = self.log.lock().unwrap();
+    ///         accounts[0] += amount;
+    ///         log.push(format!("deposited {amount}"));
+    ///     }
+    ///
+    ///     fn audit(&self) {
+    ///         let log = self.log.lock().unwrap();
+    ///         let accounts = self.accounts.lock().unwrap();
+    ///         // ..
+    ///     }
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// # use std::sync::Mutex;
+    /// # struct Bank {
+    /// #     accounts: Mutex<Vec<u64>>,
+    /// #     log: Mutex<Vec<String>>,
+    /// # }
+    /// impl Bank {
+    ///     // ..
+    ///
+    ///     fn audit(&self) {
+    ///         let accounts = self.accounts.lock().unwrap();
+    ///         let log = self.log.lock().unwrap();
+    ///         // ..
+    ///     }
+    /// }
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub INCONSISTENT_LOCK_ORDER,
+    nursery,
+    "locks acquired in a different order in different places"
+}
+
+/// A lock, identified by the place it's stored in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum LockId {
+    /// A field of a struct, union or tuple struct.
+    Field(DefId, Symbol),
+    Static(DefId),
+}
+
+impl LockId {
+    fn describe(self, cx: &LateContext<'_>) -> String {
+        match self {
+            Self::Field(adt, field) => format!("{}.{field}", cx.tcx.def_path_str(adt)),
+            Self::Static(def_id) => cx.tcx.def_path_str(def_id),
+        }
+    }
+}
+
+/// Acquiring a lock while the guard of another one is alive.
+struct Edge {
+    /// Where the held lock was acquired.
+    held: Span,
+    /// Where the second lock is acquired.
+    acquired: Span,
+    hir_id: HirId,
+}
+
+#[derive(Default)]
+pub struct InconsistentLockOrder {
+    /// Every pair of locks acquired while holding the first one, in the order they were found.
+    edges: FxIndexMap<(LockId, LockId), Edge>,
+}
+
+impl_lint_pass!(InconsistentLockOrder => [INCONSISTENT_LOCK_ORDER]);
+
+impl<'tcx> LateLintPass<'tcx> for InconsistentLockOrder {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _: FnKind<'tcx>,
+        _: &'tcx FnDecl<'_>,
+        body: &'tcx Body<'_>,
+        _: Span,
+        _: LocalDefId,
+    ) {
+        let mut v = LockVisitor {
+            cx,
+            edges: &mut self.edges,
+            held: Vec::new(),
+        };
+        v.visit_expr(body.value);
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        let mut reported = FxHashSet::default();
+        for (idx, (&(first, second), edge)) in self.edges.iter().enumerate() {
+            let Some(mut cycle) = self.find_path(second, first) else {
+                continue;
+            };
+            cycle.push(idx);
+            let mut key = cycle.clone();
+            key.sort_unstable();
+            if !reported.insert(key) {
+                continue;
+            }
+            span_lint_hir_and_then(
+                cx,
+                INCONSISTENT_LOCK_ORDER,
+                edge.hir_id,
+                edge.acquired,
+                format!(
+                    "`{}` is locked while holding `{}`, which is locked in the opposite order elsewhere",
+                    second.describe(cx),
+                    first.describe(cx),
+                ),
+                |diag| {
+                    diag.span_note(edge.held, format!("`{}` is locked here", first.describe(cx)));
+                    for &other in &cycle[..cycle.len() - 1] {
+                        let (&(held, acquired), other) = self.edges.get_index(other).unwrap();
+                        diag.span_note(
+                            other.acquired,
+                            format!(
+                                "`{}` is locked here while holding `{}`",
+                                acquired.describe(cx),
+                                held.describe(cx),
+                            ),
+                        );
+                    }
+                    diag.help("acquire the locks in the same order everywhere");
+                },
+            );
+        }
+    }
+}
+
+impl InconsistentLockOrder {
+    /// Finds the shortest sequence of edges leading from one lock to another. Returns the indices
+    /// of the edges.
+    fn find_path(&self, from: LockId, to: LockId) -> Option<Vec<usize>> {
+        // The edge used to reach each lock.
+        let mut reached_by: FxIndexMap<LockId, Option<usize>> = FxIndexMap::default();
+        reached_by.insert(from, None);
+        let mut next = 0;
+        while let Some((&lock, _)) = reached_by.get_index(next) {
+            next += 1;
+            for (idx, (&(held, acquired), _)) in self.edges.iter().enumerate() {
+                if held == lock && !reached_by.contains_key(&acquired) {
+                    reached_by.insert(acquired, Some(idx));
+                    if acquired == to {
+                        let mut path = Vec::new();
+                        let mut lock = to;
+                        while let Some(&Some(idx)) = reached_by.get(&lock) {
+                            path.push(idx);
+                            lock = self.edges.get_index(idx).unwrap().0.0;
+                        }
+                        path.reverse();
+                        return Some(path);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A lock whose guard is alive.
+struct Held {
+    lock: LockId,
+    span: Span,
+    /// The acquiring call.
+    hir_id: HirId,
+    /// The local the guard is bound to. Temporary guards are dropped at the end of the statement.
+    binding: Option<HirId>,
+}
+
+struct LockVisitor<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    edges: &'a mut FxIndexMap<(LockId, LockId), Edge>,
+    held: Vec<Held>,
+}
+
+impl<'tcx> Visitor<'tcx> for LockVisitor<'_, 'tcx> {
+    fn visit_block(&mut self, block: &'tcx Block<'tcx>) {
+        let len = self.held.len();
+        walk_block(self, block);
+        self.held.truncate(len);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'tcx rustc_hir::Stmt<'tcx>) {
+        let len = self.held.len();
+        match stmt.kind {
+            StmtKind::Let(local) => self.visit_let(local, len),
+            StmtKind::Expr(e) | StmtKind::Semi(e) => {
+                self.visit_expr(e);
+                self.held.truncate(len);
+            },
+            // Nested items are checked on their own.
+            StmtKind::Item(_) => {},
+        }
+    }
+
+    fn visit_expr(&mut self, e: &'tcx Expr<'tcx>) {
+        match e.kind {
+            ExprKind::MethodCall(_, recv, [], _) if let Some(lock) = lock_id(self.cx, e, recv) => {
+                self.visit_expr(recv);
+                for held in &self.held {
+                    if held.lock != lock {
+                        self.edges.entry((held.lock, lock)).or_insert(Edge {
+                            held: held.span,
+                            acquired: e.span,
+                            hir_id: e.hir_id,
+                        });
+                    }
+                }
+                self.held.push(Held {
+                    lock,
+                    span: e.span,
+                    hir_id: e.hir_id,
+                    binding: None,
+                });
+            },
+            ExprKind::Call(func, [arg])
+                if func.basic_res().is_diag_item(self.cx, sym::mem_drop)
+                    && let Some(local) = arg.res_local_id() =>
+            {
+                self.held.retain(|held| held.binding != Some(local));
+            },
+            // Temporaries in the condition of an `if` are dropped before entering either branch.
+            ExprKind::If(cond, then, els) if !matches!(cond.kind, ExprKind::Let(_)) => {
+                let len = self.held.len();
+                self.visit_expr(cond);
+                self.held.truncate(len);
+                self.visit_expr(then);
+                if let Some(els) = els {
+                    self.visit_expr(els);
+                }
+            },
+            // Closures are checked on their own.
+            ExprKind::Closure(_) => {},
+            _ => walk_expr(self, e),
+        }
+    }
+}
+
+impl<'tcx> LockVisitor<'_, 'tcx> {
+    /// Visits a `let` statement. If the initializer is a lock acquisition bound to a variable,
+    /// the guard stays alive until the end of the block.
+    fn visit_let(&mut self, local: &'tcx LetStmt<'tcx>, len: usize) {
+        let Some(init) = local.init else {
+            return;
+        };
+        self.visit_expr(init);
+        let guard = if let PatKind::Binding(_, binding, ..) = local.pat.kind {
+            let mut init = init;
+            while let ExprKind::MethodCall(path, recv, _, _) = init.kind
+                && matches!(path.ident.name, sym::unwrap | sym::expect)
+            {
+                init = recv;
+            }
+            self.held[len..]
+                .iter()
+                .position(|held| held.hir_id == init.hir_id)
+                .map(|idx| (len + idx, binding))
+        } else {
+            None
+        };
+        if let Some((idx, binding)) = guard {
+            let mut held = self.held.swap_remove(idx);
+            held.binding = Some(binding);
+            self.held.truncate(len);
+            self.held.push(held);
+        } else {
+            self.held.truncate(len);
+        }
+        if let Some(els) = local.els {
+            self.visit_block(els);
+        }
+    }
+}
+
+/// If the expression acquires a `Mutex` or `RwLock` stored in a field or static, gets the
+/// identity of that lock.
+fn lock_id<'tcx>(cx: &LateContext<'tcx>, call: &Expr<'tcx>, recv: &Expr<'tcx>) -> Option<LockId> {
+    let ExprKind::MethodCall(path, ..) = call.kind else {
+        return None;
+    };
+    if call.span.from_expansion()
+        || !matches!(path.ident.name, sym::lock | sym::read | sym::write)
+        || !is_guard(cx, cx.typeck_results().expr_ty(call))
+    {
+        return None;
+    }
+    let mut recv = recv;
+    while let ExprKind::AddrOf(_, _, inner) = recv.kind {
+        recv = inner;
+    }
+    match recv.kind {
+        ExprKind::Field(base, field) => {
+            if let ty::Adt(adt, _) = cx.typeck_results().expr_ty_adjusted(base).peel_refs().kind() {
+                Some(LockId::Field(adt.did(), field.name))
+            } else {
+                None
+            }
+        },
+        ExprKind::Path(_) => match *recv.basic_res() {
+            Res::Def(DefKind::Static { .. }, def_id) => Some(LockId::Static(def_id)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Checks if the type is a lock guard, or a `Result` containing one.
+fn is_guard<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    let ty::Adt(adt, args) = ty.kind() else {
+        return false;
+    };
+    if cx.tcx.is_diagnostic_item(sym::Result, adt.did()) {
+        return args.types().next().is_some_and(|ty| is_guard(cx, ty));
+    }
+    match cx.tcx.get_diagnostic_name(adt.did()) {
+        Some(name) => matches!(name, sym::MutexGuard | sym::RwLockReadGuard | sym::RwLockWriteGuard),
+        None => paths::PARKING_LOT_GUARDS
+            .iter()
+            .any(|guard| guard.matches(cx, adt.did())),
+    }
+}
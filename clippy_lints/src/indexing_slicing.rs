@@ -2,6 +2,7 @@ use clippy_config::Conf;
 use clippy_utils::consts::{ConstEvalCtxt, Constant};
 use clippy_utils::diagnostics::{span_lint, span_lint_and_then};
 use clippy_utils::ty::{deref_chain, get_adt_inherent_method};
+use clippy_utils::value_range::{expr_int_range, is_below_len};
 use clippy_utils::{higher, is_from_proc_macro, is_in_test, sym};
 use rustc_ast::ast::RangeLimits;
 use rustc_hir::{Expr, ExprKind};
@@ -173,7 +174,7 @@ impl<'tcx> LateLintPass<'tcx> for IndexingSlicing {
                     (None, None) => return, // [..] is ok.
                 };
 
-                if allowed_in_tests {
+                if allowed_in_tests || is_range_in_bounds(cx, range, array) {
                     return;
                 }
 
@@ -215,7 +216,7 @@ impl<'tcx> LateLintPass<'tcx> for IndexingSlicing {
                     }
                 }
 
-                if allowed_in_tests {
+                if allowed_in_tests || is_below_len(cx, index, array, false) {
                     return;
                 }
 
@@ -231,6 +232,22 @@ impl<'tcx> LateLintPass<'tcx> for IndexingSlicing {
     }
 }
 
+/// Checks if the bounds of the range are known to be within the length of `array`, e.g. after
+/// `assert!(n <= v.len())` for `&v[..n]`.
+fn is_range_in_bounds(cx: &LateContext<'_>, range: higher::Range<'_>, array: &Expr<'_>) -> bool {
+    let end_in_bounds = |end| is_below_len(cx, end, array, range.limits == RangeLimits::HalfOpen);
+    match (range.start, range.end) {
+        (Some(start), None) => is_below_len(cx, start, array, true),
+        (None, Some(end)) => end_in_bounds(end),
+        (Some(start), Some(end)) => {
+            let start_max = expr_int_range(cx, start).and_then(|range| range.max);
+            let end_min = expr_int_range(cx, end).and_then(|range| range.min);
+            end_in_bounds(end) && start_max.zip(end_min).is_some_and(|(start, end)| start <= end)
+        },
+        (None, None) => true,
+    }
+}
+
 /// Returns a tuple of options with the start and end (exclusive) values of
 /// the range. If the start or end is not constant, None is returned.
 fn to_const_range(cx: &LateContext<'_>, range: higher::Range<'_>, array_size: u128) -> (Option<u128>, Option<u128>) {
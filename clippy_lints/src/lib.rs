@@ -55,6 +55,7 @@ extern crate clippy_utils;
 #[macro_use]
 extern crate declare_clippy_lint;
 
+mod custom_lints;
 mod utils;
 
 pub mod declared_lints;
@@ -72,8 +73,10 @@ mod assertions_on_constants;
 mod assertions_on_result_states;
 mod assigning_clones;
 mod async_yields_async;
+mod atomic_check_then_act;
 mod attrs;
 mod await_holding_invalid;
+mod blocking_in_async;
 mod blocks_in_conditions;
 mod bool_assert_comparison;
 mod bool_comparison;
@@ -146,6 +149,7 @@ mod from_raw_with_void_ptr;
 mod from_str_radix_10;
 mod functions;
 mod future_not_send;
+mod hardcoded_secret;
 mod if_let_mutex;
 mod if_not_else;
 mod if_then_some_else_none;
@@ -158,6 +162,7 @@ mod implicit_saturating_add;
 mod implicit_saturating_sub;
 mod implied_bounds_in_impls;
 mod incompatible_msrv;
+mod inconsistent_lock_order;
 mod inconsistent_struct_constructor;
 mod index_refutable_slice;
 mod indexing_slicing;
@@ -334,6 +339,7 @@ mod single_range_in_vec_init;
 mod size_of_in_element_count;
 mod size_of_ref;
 mod slow_vector_initialization;
+mod sql_injection;
 mod std_instead_of_core;
 mod string_patterns;
 mod strings;
@@ -430,11 +436,22 @@ pub fn explain(name: &str) -> i32 {
     }
 }
 
+/// Registers the pass reporting `#[allow]` attributes for Clippy lints which don't suppress any
+/// lint, and starts tracking the attributes which do. This must be called after
+/// [`register_lint_passes`].
+///
+/// Used in `./src/driver.rs` for `cargo clippy --unused-allows`.
+pub fn register_unused_allows_pass(store: &mut rustc_lint::LintStore, conf: &'static Conf, to_expect: bool) {
+    clippy_utils::diagnostics::track_allow_attributes();
+    store.register_late_pass(move |_| Box::new(attrs::UnusedClippyAllow::new(conf, to_expect)));
+}
+
 /// Register all lints and lint groups with the rustc lint store
 ///
 /// Used in `./src/driver.rs`.
 #[expect(clippy::too_many_lines)]
 pub fn register_lint_passes(store: &mut rustc_lint::LintStore, conf: &'static Conf) {
+    clippy_utils::diagnostics::set_documented_lints(declared_lints::LINTS.iter().map(|info| info.lint));
     for (old_name, new_name) in deprecated_lints::RENAMED {
         store.register_renamed(old_name, new_name);
     }
@@ -829,7 +846,7 @@ pub fn register_lint_passes(store: &mut rustc_lint::LintStore, conf: &'static Co
         Box::new(move |_| Box::new(macro_metavars_in_unsafe::ExprMetavarsInUnsafe::new(conf))),
         Box::new(move |_| Box::new(string_patterns::StringPatterns::new(conf))),
         Box::new(|_| Box::new(set_contains_or_insert::SetContainsOrInsert)),
-        Box::new(|_| Box::new(zombie_processes::ZombieProcesses)),
+        Box::new(move |tcx| Box::new(zombie_processes::ZombieProcesses::new(tcx, conf))),
         Box::new(|_| Box::new(pointers_in_nomem_asm_block::PointersInNomemAsmBlock)),
         Box::new(move |_| Box::new(manual_is_power_of_two::ManualIsPowerOfTwo::new(conf))),
         Box::new(|_| Box::new(non_zero_suggestions::NonZeroSuggestions)),
@@ -852,7 +869,14 @@ pub fn register_lint_passes(store: &mut rustc_lint::LintStore, conf: &'static Co
         Box::new(|_| Box::new(volatile_composites::VolatileComposites)),
         Box::new(|_| Box::<replace_box::ReplaceBox>::default()),
         Box::new(move |_| Box::new(manual_ilog2::ManualIlog2::new(conf))),
+        Box::new(move |tcx| Box::new(sql_injection::SqlInjection::new(tcx, conf))),
+        Box::new(move |_| Box::new(hardcoded_secret::HardcodedSecret::new(conf))),
+        Box::new(|_| Box::<inconsistent_lock_order::InconsistentLockOrder>::default()),
+        Box::new(move |tcx| Box::new(blocking_in_async::BlockingInAsync::new(tcx, conf))),
+        Box::new(|_| Box::<atomic_check_then_act::AtomicCheckThenAct>::default()),
         // add late passes here, used by `cargo dev new_lint`
     ];
     store.late_passes.extend(late_lints);
+
+    custom_lints::register(store, conf);
 }
@@ -106,6 +106,7 @@ mod return_and_then;
 mod search_is_some;
 mod seek_from_current;
 mod seek_to_start_instead_of_rewind;
+mod shell_command_injection;
 mod should_implement_trait;
 mod single_char_add_str;
 mod skip_while_next;
@@ -4749,6 +4750,38 @@ declare_clippy_lint! {
     "filtering `std::io::Lines` with `filter_map()`, `flat_map()`, or `flatten()` might cause an infinite loop"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `Command`s running a shell script, e.g. `sh -c <script>`, where the script is
+    /// built from a formatted string.
+    ///
+    /// ### Why is this bad?
+    /// The shell interprets the formatted values as part of the script. A value containing
+    /// characters such as `;`, `|` or `$(...)` will run arbitrary commands, which is a security
+    /// issue when the value comes from an untrusted source. Running the program directly and
+    /// passing each value as a separate argument avoids any interpretation by a shell.
+    ///
+    /// ### Known problems
+    /// Only commands built in a single method chain are recognized.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// # use std::process::Command;
+    /// # let file = "";
+    /// Command::new("sh").arg("-c").arg(format!("wc -l {file}")).status();
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// # use std::process::Command;
+    /// # let file = "";
+    /// Command::new("wc").arg("-l").arg(file).status();
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub SHELL_COMMAND_INJECTION,
+    pedantic,
+    "shell scripts built from formatted strings"
+}
+
 #[expect(clippy::struct_excessive_bools)]
 pub struct Methods {
     avoid_breaking_exported_api: bool,
@@ -4933,6 +4966,7 @@ impl_lint_pass!(Methods => [
     REDUNDANT_ITER_CLONED,
     UNNECESSARY_OPTION_MAP_OR_ELSE,
     LINES_FILTER_MAP_OK,
+    SHELL_COMMAND_INJECTION,
 ]);
 
 /// Extracts a method call name, args, and `Span` of the method name.
@@ -5694,6 +5728,12 @@ impl Methods {
             unnecessary_to_owned::check(cx, expr, path.ident.name, recv, args, self.msrv);
 
             match (path.ident.name, args) {
+                (sym::arg, [arg]) => {
+                    shell_command_injection::check(cx, &self.format_args, recv, arg);
+                },
+                (sym::args, [arg]) => {
+                    shell_command_injection::check_args(cx, &self.format_args, recv, arg);
+                },
                 (sym::clone, []) => {
                     clone_on_ref_ptr::check(cx, expr, recv);
                     clone_on_copy::check(cx, expr, recv);
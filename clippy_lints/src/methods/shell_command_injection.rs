@@ -0,0 +1,140 @@
+use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::macros::{FormatArgsStorage, root_macro_call_first_node};
+use clippy_utils::res::MaybeDef;
+use clippy_utils::{expr_or_init, sym};
+use rustc_ast::LitKind;
+use rustc_hir::{BinOpKind, Expr, ExprKind, LangItem, QPath};
+use rustc_lint::LateContext;
+use rustc_span::Symbol;
+
+use super::SHELL_COMMAND_INJECTION;
+
+/// Shells which take a script as the argument following [`SCRIPT_FLAGS`].
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh", "cmd", "powershell", "pwsh"];
+
+/// The flags telling a shell to run the next argument as a script.
+const SCRIPT_FLAGS: &[&str] = &["-c", "/c", "-command"];
+
+/// Checks a call to `Command::arg`.
+pub(super) fn check(cx: &LateContext<'_>, format_args: &FormatArgsStorage, recv: &Expr<'_>, arg: &Expr<'_>) {
+    if is_formatted(cx, format_args, arg) && is_shell_script_next(cx, recv) {
+        lint(cx, arg);
+    }
+}
+
+/// Checks a call to `Command::args`.
+pub(super) fn check_args(cx: &LateContext<'_>, format_args: &FormatArgsStorage, recv: &Expr<'_>, arg: &Expr<'_>) {
+    let arg = peel_ref(arg);
+    let ExprKind::Array(elements) = arg.kind else {
+        return;
+    };
+    for (i, element) in elements.iter().enumerate() {
+        let after_flag = match i.checked_sub(1) {
+            Some(prev) => is_script_flag(&elements[prev]),
+            None => is_shell_script_next(cx, recv),
+        };
+        if after_flag && is_formatted(cx, format_args, element) {
+            lint(cx, element);
+        }
+    }
+}
+
+fn lint(cx: &LateContext<'_>, arg: &Expr<'_>) {
+    span_lint_and_help(
+        cx,
+        SHELL_COMMAND_INJECTION,
+        arg.span,
+        "shell script built from a formatted string",
+        None,
+        "run the program directly and pass each value as a separate argument",
+    );
+}
+
+/// Checks if the next argument added to the `Command` is run as a shell script, i.e. the command
+/// runs a shell and its last argument is `-c` or similar.
+fn is_shell_script_next(cx: &LateContext<'_>, recv: &Expr<'_>) -> bool {
+    let mut recv = expr_or_init(cx, recv);
+    let mut last_arg_is_flag = None;
+    loop {
+        match recv.kind {
+            ExprKind::MethodCall(path, inner, args, _)
+                if cx
+                    .typeck_results()
+                    .expr_ty(inner)
+                    .peel_refs()
+                    .is_diag_item(cx, sym::Command) =>
+            {
+                if last_arg_is_flag.is_none() {
+                    last_arg_is_flag = match (path.ident.name, args) {
+                        (sym::arg, [arg]) => Some(is_script_flag(arg)),
+                        (sym::args, [arg]) => {
+                            Some(matches!(peel_ref(arg).kind, ExprKind::Array([.., last]) if is_script_flag(last)))
+                        },
+                        _ => None,
+                    };
+                }
+                recv = expr_or_init(cx, inner);
+            },
+            ExprKind::Call(func, [program])
+                if let ExprKind::Path(QPath::TypeRelative(ty, name)) = func.kind
+                    && name.ident.name == sym::new
+                    && cx.typeck_results().node_type(ty.hir_id).is_diag_item(cx, sym::Command) =>
+            {
+                return last_arg_is_flag == Some(true) && is_shell(program);
+            },
+            _ => return false,
+        }
+    }
+}
+
+fn is_shell(program: &Expr<'_>) -> bool {
+    str_lit(program).is_some_and(|program| {
+        let program = program.as_str();
+        let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+        let name = name.strip_suffix(".exe").unwrap_or(name);
+        SHELLS.iter().any(|shell| shell.eq_ignore_ascii_case(name))
+    })
+}
+
+fn is_script_flag(arg: &Expr<'_>) -> bool {
+    str_lit(peel_ref(arg)).is_some_and(|arg| SCRIPT_FLAGS.iter().any(|flag| flag.eq_ignore_ascii_case(arg.as_str())))
+}
+
+fn str_lit(e: &Expr<'_>) -> Option<Symbol> {
+    if let ExprKind::Lit(lit) = e.kind
+        && let LitKind::Str(s, _) = lit.node
+    {
+        Some(s)
+    } else {
+        None
+    }
+}
+
+fn peel_ref<'a, 'tcx>(mut e: &'a Expr<'tcx>) -> &'a Expr<'tcx> {
+    while let ExprKind::AddrOf(_, _, inner) = e.kind {
+        e = inner;
+    }
+    e
+}
+
+/// Checks if the argument is a string built with `format!` or `+` from at least one value.
+fn is_formatted(cx: &LateContext<'_>, format_args: &FormatArgsStorage, arg: &Expr<'_>) -> bool {
+    let mut arg = peel_ref(arg);
+    if let ExprKind::MethodCall(path, recv, [], _) = arg.kind
+        && matches!(path.ident.name, sym::as_str | sym::as_ref)
+    {
+        arg = recv;
+    }
+    let arg = expr_or_init(cx, peel_ref(arg));
+    if let Some(macro_call) = root_macro_call_first_node(cx, arg)
+        && cx.tcx.is_diagnostic_item(sym::format_macro, macro_call.def_id)
+    {
+        format_args
+            .get(cx, arg, macro_call.expn)
+            .is_some_and(|args| !args.arguments.all_args().is_empty())
+    } else if let ExprKind::Binary(op, _, _) = arg.kind {
+        op.node == BinOpKind::Add && cx.typeck_results().expr_ty(arg).is_lang_item(cx, LangItem::String)
+    } else {
+        false
+    }
+}
@@ -1,5 +1,5 @@
 use clippy_config::Conf;
-use clippy_utils::diagnostics::span_lint;
+use clippy_utils::diagnostics::{ExpectAttrContext, span_lint};
 use clippy_utils::is_from_proc_macro;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_hir::def::{DefKind, Res};
@@ -202,7 +202,7 @@ impl Visitor<'_> for IdentVisitor<'_, '_> {
     }
 }
 
-fn emit_min_ident_chars(conf: &MinIdentChars, cx: &impl LintContext, ident: &str, span: Span) {
+fn emit_min_ident_chars(conf: &MinIdentChars, cx: &impl ExpectAttrContext, ident: &str, span: Span) {
     let help = if conf.min_ident_chars_threshold == 1 {
         Cow::Borrowed("this ident consists of a single char")
     } else {
@@ -4,12 +4,15 @@ use clippy_config::Conf;
 use clippy_utils::consts::{ConstEvalCtxt, Constant};
 use clippy_utils::diagnostics::span_lint;
 use clippy_utils::res::MaybeDef;
+use clippy_utils::value_range::is_overflow_free;
+use clippy_utils::visitors::for_each_expr_without_closures;
 use clippy_utils::{expr_or_init, is_from_proc_macro, is_lint_allowed, peel_hir_expr_refs, peel_hir_expr_unary, sym};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::{self, Ty, UintTy};
 use rustc_session::impl_lint_pass;
 use rustc_span::{Span, Symbol};
+use std::ops::ControlFlow;
 use {rustc_ast as ast, rustc_hir as hir};
 
 pub struct ArithmeticSideEffects {
@@ -146,10 +149,10 @@ impl ArithmeticSideEffects {
         ) {
             return;
         }
-        let (mut actual_lhs, lhs_ref_counter) = peel_hir_expr_refs(lhs);
-        let (mut actual_rhs, rhs_ref_counter) = peel_hir_expr_refs(rhs);
-        actual_lhs = expr_or_init(cx, actual_lhs);
-        actual_rhs = expr_or_init(cx, actual_rhs);
+        let (peeled_lhs, lhs_ref_counter) = peel_hir_expr_refs(lhs);
+        let (peeled_rhs, rhs_ref_counter) = peel_hir_expr_refs(rhs);
+        let actual_lhs = expr_or_init(cx, peeled_lhs);
+        let actual_rhs = expr_or_init(cx, peeled_rhs);
 
         // `NonZeroU*.get() - 1`, will never overflow
         if let hir::BinOpKind::Sub = op
@@ -201,6 +204,10 @@ impl ArithmeticSideEffects {
                 (Some(_), Some(_)) if matches!((lhs_ref_counter, rhs_ref_counter), (0, 0)) => return,
                 _ => {},
             }
+            // Operands bounded by e.g. an enclosing `if` can't overflow.
+            if is_overflow_free(cx, op, peeled_lhs, peeled_rhs) {
+                return;
+            }
         }
         self.issue_lint(cx, expr);
     }
@@ -415,6 +422,9 @@ fn is_safe_due_to_smaller_source_type(
 ///
 /// Literal integers can be originated from adhoc declarations like `1`, associated constants
 /// like `i32::MAX` or constant references like `N` from `const N: i32 = 1;`,
+///
+/// Calls to `const fn`s are not considered literals as rustc's overflow checks don't see through
+/// them.
 fn literal_integer(cx: &LateContext<'_>, expr: &hir::Expr<'_>) -> Option<u128> {
     let actual = peel_hir_expr_unary(expr).0;
     if let hir::ExprKind::Lit(lit) = actual.kind
@@ -422,7 +432,15 @@ fn literal_integer(cx: &LateContext<'_>, expr: &hir::Expr<'_>) -> Option<u128> {
     {
         return Some(n.get());
     }
-    if let Some(Constant::Int(n)) = ConstEvalCtxt::new(cx).eval(expr) {
+    let has_call = for_each_expr_without_closures(actual, |e| {
+        if matches!(e.kind, hir::ExprKind::Call(..) | hir::ExprKind::MethodCall(..)) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_some();
+    if !has_call && let Some(Constant::Int(n)) = ConstEvalCtxt::new(cx).eval(expr) {
         return Some(n);
     }
     None
@@ -0,0 +1,111 @@
+use clippy_config::Conf;
+use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::fn_has_unsatisfiable_preds;
+use clippy_utils::mir::taint::{TaintConfig, find_tainted_sinks};
+use rustc_hir::intravisit::FnKind;
+use rustc_hir::{Body, FnDecl};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::impl_lint_pass;
+use rustc_span::Span;
+use rustc_span::def_id::LocalDefId;
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for SQL queries built from untrusted input, e.g. by using `format!` to insert the
+    /// value of an environment variable into the query.
+    ///
+    /// Untrusted input is the result of any function listed in the `taint-sources` configuration,
+    /// and any value derived from it which hasn't passed through one of the functions listed in
+    /// `taint-sanitizers`. The functions executing queries are listed in `sql-injection-sinks`,
+    /// the query is their first argument after the receiver. Only queries built by formatting or
+    /// concatenating untrusted input are linted, passing it as a parameter of the query is fine.
+    ///
+    /// ### Why is this bad?
+    /// Whoever controls the input can change the meaning of the query, e.g. to read or delete
+    /// data they shouldn't have access to.
+    ///
+    /// ### Known problems
+    /// Values are only tracked within a single function. A value is considered tainted if it is
+    /// derived from untrusted input anywhere in the function, even when that only happens on a
+    /// different path than the one reaching the query.
+    ///
+    /// Functions other than the ones building strings are assumed to return a value derived from
+    /// their arguments if they return a string, and an unrelated value otherwise.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// let name = std::env::var("USER_NAME")?;
+    /// let query = format!("SELECT * FROM users WHERE name = '{name}'");
+    /// sqlx::query(&query).fetch_all(&pool).await?;
+    /// ```
+    ///
+    /// Use instead:
+    /// ```rust,ignore
+    /// let name = std::env::var("USER_NAME")?;
+    /// sqlx::query("SELECT * FROM users WHERE name = $1")
+    ///     .bind(name)
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub SQL_INJECTION,
+    nursery,
+    "SQL queries built from untrusted input"
+}
+
+pub struct SqlInjection {
+    config: TaintConfig,
+}
+
+impl SqlInjection {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        Self {
+            config: TaintConfig::new(
+                tcx,
+                &conf.taint_sources,
+                &conf.taint_sanitizers,
+                &conf.sql_injection_sinks,
+            ),
+        }
+    }
+}
+
+impl_lint_pass!(SqlInjection => [SQL_INJECTION]);
+
+impl<'tcx> LateLintPass<'tcx> for SqlInjection {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _: FnKind<'tcx>,
+        _: &'tcx FnDecl<'_>,
+        _: &'tcx Body<'_>,
+        _: Span,
+        def_id: LocalDefId,
+    ) {
+        // Building MIR for `fn`s with unsatisfiable preds results in ICE.
+        if !self.config.has_sinks() || fn_has_unsatisfiable_preds(cx, def_id.to_def_id()) {
+            return;
+        }
+
+        let mir = cx.tcx.optimized_mir(def_id.to_def_id());
+        for sink in find_tainted_sinks(cx.tcx, mir, &self.config) {
+            if sink.span.from_expansion() {
+                continue;
+            }
+            span_lint_and_then(
+                cx,
+                SQL_INJECTION,
+                sink.span,
+                format!(
+                    "`{}` is called with a query built from untrusted input",
+                    cx.tcx.def_path_str(sink.callee)
+                ),
+                |diag| {
+                    diag.span_note(sink.source, "the untrusted input comes from here");
+                    diag.help("pass the untrusted values as parameters of the query instead");
+                },
+            );
+        }
+    }
+}
@@ -6,11 +6,12 @@ use rustc_ast::ast::{LitFloatType, LitKind};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def_id::DefId;
 use rustc_hir::{
-    self as hir, BindingMode, CaptureBy, Closure, ClosureKind, ConstArg, ConstArgKind, CoroutineKind, ExprKind,
-    FnRetTy, HirId, Lit, PatExprKind, PatKind, QPath, StmtKind, StructTailExpr,
+    self as hir, BindingMode, CaptureBy, Closure, ClosureKind, ConstArg, ConstArgKind, ConstItemRhs, CoroutineKind,
+    DotDotPos, ExprKind, FnRetTy, HirId, ImplItemKind, ItemKind, Lit, PatExprKind, PatKind, QPath, StmtKind,
+    StructTailExpr, TraitFn, TraitItemKind, TyKind, VariantData,
 };
 use rustc_lint::{LateContext, LateLintPass, LintContext};
-use rustc_middle::ty::{FloatTy, IntTy, UintTy};
+use rustc_middle::ty::{FloatTy, IntTy, TypeckResults, UintTy};
 use rustc_session::declare_lint_pass;
 use rustc_span::symbol::{Ident, Symbol};
 use std::cell::Cell;
@@ -34,6 +35,9 @@ declare_lint_pass!(
     /// }
     /// ```
     ///
+    /// The attribute can also be put on items, statements, match arms and function or closure
+    /// parameters, in which case the parameter's pattern is printed.
+    ///
     /// Running `TESTNAME=ui/my_lint cargo uitest` will produce
     /// a `./tests/ui/new_lint.stdout` file with the generated code:
     ///
@@ -43,11 +47,13 @@ declare_lint_pass!(
     ///     && let ExprKind::Binary(BinOp::Eq, ref left, ref right) = cond.kind
     ///     && let ExprKind::Path(ref path) = left.kind
     ///     && let ExprKind::Lit(ref lit) = right.kind
-    ///     && let LitKind::Int(42, _) = lit.node
+    ///     && let LitKind::Int(Pu128(42), _) = lit.node
     /// {
     ///     // report your lint here
     /// }
     /// ```
+    ///
+    /// `cargo dev author <file>` uses this output to generate a whole lint.
     Author => []
 );
 
@@ -101,15 +107,29 @@ macro_rules! chain {
 
 impl<'tcx> LateLintPass<'tcx> for Author {
     fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
-        check_item(cx, item.hir_id());
+        check_node(cx, item.hir_id(), |v| {
+            v.item(&v.bind("item", item));
+        });
     }
 
     fn check_impl_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::ImplItem<'_>) {
-        check_item(cx, item.hir_id());
+        check_node(cx, item.hir_id(), |v| {
+            v.impl_item(&v.bind("impl_item", item));
+        });
     }
 
     fn check_trait_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::TraitItem<'_>) {
-        check_item(cx, item.hir_id());
+        check_node(cx, item.hir_id(), |v| {
+            v.trait_item(&v.bind("trait_item", item));
+        });
+    }
+
+    fn check_body(&mut self, cx: &LateContext<'tcx>, body: &hir::Body<'tcx>) {
+        for param in body.params {
+            check_node(cx, param.hir_id, |v| {
+                v.pat(&v.bind("pat", param.pat));
+            });
+        }
     }
 
     fn check_arm(&mut self, cx: &LateContext<'tcx>, arm: &'tcx hir::Arm<'_>) {
@@ -135,14 +155,6 @@ impl<'tcx> LateLintPass<'tcx> for Author {
     }
 }
 
-fn check_item(cx: &LateContext<'_>, hir_id: HirId) {
-    if let Some(body) = cx.tcx.hir_maybe_body_owned_by(hir_id.expect_owner().def_id) {
-        check_node(cx, hir_id, |v| {
-            v.expr(&v.bind("expr", body.value));
-        });
-    }
-}
-
 fn check_node(cx: &LateContext<'_>, hir_id: HirId, f: impl Fn(&PrintVisitor<'_, '_>)) {
     if has_attr(cx, hir_id) {
         f(&PrintVisitor::new(cx));
@@ -204,6 +216,17 @@ struct PrintVisitor<'a, 'tcx> {
     ids: Cell<FxHashMap<&'static str, u32>>,
     /// Currently at the first condition in the if chain
     first: Cell<bool>,
+    /// The typeck results of the body being printed when the node isn't inside of a body, e.g. for
+    /// items. They're only fetched by the generated code once a path needs to be resolved.
+    typeck: Cell<Option<BodyTypeck<'tcx>>>,
+}
+
+struct BodyTypeck<'tcx> {
+    results: &'tcx TypeckResults<'tcx>,
+    /// The binding of the `BodyId` in the generated code
+    body_id: String,
+    /// The binding of the typeck results in the generated code, once they've been fetched
+    name: Option<String>,
 }
 
 impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
@@ -212,6 +235,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             cx,
             ids: Cell::default(),
             first: Cell::new(true),
+            typeck: Cell::new(None),
         }
     }
 
@@ -278,26 +302,57 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 self,
                 "let Some({def_id}) = cx.qpath_res({qpath}, {hir_id_binding}.hir_id).opt_def_id()"
             );
-            if let Some(name) = self.cx.tcx.get_diagnostic_name(def_id.value) {
-                chain!(self, "cx.tcx.is_diagnostic_item(sym::{name}, {def_id})");
-            } else {
-                chain!(
-                    self,
-                    "paths::{}.matches(cx, {def_id}) // Add the path to `clippy_utils::paths` if needed",
-                    paths_static_name(self.cx, def_id.value)
-                );
-            }
+            self.def_id(def_id);
+        }
+    }
+
+    fn trait_ref(&self, trait_ref: &Binding<&hir::TraitRef<'_>>) {
+        if let Some(def_id) = trait_ref.value.trait_def_id()
+            && !def_id.is_local()
+        {
+            bind!(self, def_id);
+            chain!(self, "let Some({def_id}) = {trait_ref}.trait_def_id()");
+            self.def_id(def_id);
+        }
+    }
+
+    fn def_id(&self, def_id: &Binding<DefId>) {
+        if let Some(name) = self.cx.tcx.get_diagnostic_name(def_id.value) {
+            chain!(self, "cx.tcx.is_diagnostic_item(sym::{name}, {def_id})");
+        } else {
+            chain!(
+                self,
+                "paths::{}.matches(cx, {def_id}) // Add the path to `clippy_utils::paths` if needed",
+                paths_static_name(self.cx, def_id.value)
+            );
         }
     }
 
     fn maybe_path<'p>(&self, path: &Binding<impl MaybeQPath<'p>>) {
-        if let Some(id) = path.value.res(self.cx).opt_def_id()
+        let mut typeck = self.typeck.take();
+        let res = match &typeck {
+            Some(typeck) => path.value.res(typeck.results),
+            None => path.value.res(self.cx),
+        };
+        if let Some(id) = res.opt_def_id()
             && !id.is_local()
         {
+            let typeck_name = match &mut typeck {
+                Some(BodyTypeck { body_id, name, .. }) => &**name.get_or_insert_with(|| {
+                    let typeck = self.next("typeck");
+                    chain!(self, "let {typeck} = cx.tcx.typeck_body({body_id})");
+                    typeck
+                }),
+                None => "cx",
+            };
             if let Some(lang) = self.cx.tcx.lang_items().from_def_id(id) {
-                chain!(self, "{path}.res(cx).is_lang_item(cx, LangItem::{}", lang.name());
+                chain!(
+                    self,
+                    "{path}.res({typeck_name}).is_lang_item(cx, LangItem::{})",
+                    lang.name()
+                );
             } else if let Some(name) = self.cx.tcx.get_diagnostic_name(id) {
-                chain!(self, "{path}.res(cx).is_diag_item(cx, sym::{name})");
+                chain!(self, "{path}.res({typeck_name}).is_diag_item(cx, sym::{name})");
             } else {
                 chain!(
                     self,
@@ -306,6 +361,20 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 );
             }
         }
+        self.typeck.set(typeck);
+    }
+
+    /// Checks the path of a type outside of a body, where `res(cx)` can't be used.
+    fn ty(&self, ty: &Binding<&hir::Ty<'_>>) {
+        if let TyKind::Path(QPath::Resolved(None, path)) = ty.value.kind
+            && let Some(def_id) = path.res.opt_def_id()
+            && !def_id.is_local()
+        {
+            bind!(self, path, def_id);
+            chain!(self, "let TyKind::Path(QPath::Resolved(None, {path})) = {ty}.kind");
+            chain!(self, "let Some({def_id}) = {path}.res.opt_def_id()");
+            self.def_id(def_id);
+        }
     }
 
     fn const_arg(&self, const_arg: &Binding<&ConstArg<'_>>) {
@@ -361,7 +430,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                     },
                     LitIntType::Unsuffixed => String::from("LitIntType::Unsuffixed"),
                 };
-                kind!("Int({i}, {int_ty})");
+                kind!("Int(Pu128({i}), {int_ty})");
             },
             LitKind::Float(_, suffix) => {
                 let float_ty = match suffix {
@@ -705,9 +774,17 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
     }
 
     fn body(&self, body_id: &Binding<hir::BodyId>) {
+        if self.cx.maybe_typeck_results().is_none() {
+            let typeck = self.typeck.take();
+            self.typeck.set(Some(typeck.unwrap_or_else(|| BodyTypeck {
+                results: self.cx.tcx.typeck_body(body_id.value),
+                body_id: body_id.name.clone(),
+                name: None,
+            })));
+        }
         let expr = self.cx.tcx.hir_body(body_id.value).value;
         bind!(self, expr);
-        chain!(self, "{expr} = &cx.tcx.hir_body({body_id}).value");
+        chain!(self, "let {expr} = cx.tcx.hir_body({body_id}).value");
         self.expr(expr);
     }
 
@@ -772,15 +849,17 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 kind!("Or({fields})");
                 self.slice(fields, |pat| self.pat(pat));
             },
-            PatKind::TupleStruct(ref qpath, fields, skip_pos) => {
-                bind!(self, qpath, fields);
-                kind!("TupleStruct(ref {qpath}, {fields}, {skip_pos:?})");
+            PatKind::TupleStruct(ref qpath, fields, dotdot) => {
+                bind!(self, qpath, fields, dotdot);
+                kind!("TupleStruct(ref {qpath}, {fields}, {dotdot})");
+                self.dotdot(dotdot);
                 self.qpath(qpath, &pat.name, pat.value.hir_id);
                 self.slice(fields, |pat| self.pat(pat));
             },
-            PatKind::Tuple(fields, skip_pos) => {
-                bind!(self, fields);
-                kind!("Tuple({fields}, {skip_pos:?})");
+            PatKind::Tuple(fields, dotdot) => {
+                bind!(self, fields, dotdot);
+                kind!("Tuple({fields}, {dotdot})");
+                self.dotdot(dotdot);
                 self.slice(fields, |field| self.pat(field));
             },
             PatKind::Box(pat) => {
@@ -795,7 +874,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             },
             PatKind::Ref(pat, pinn, muta) => {
                 bind!(self, pat);
-                kind!("Ref({pat}, Pinning::{pinn:?}, Mutability::{muta:?})");
+                kind!("Ref({pat}, Pinnedness::{pinn:?}, Mutability::{muta:?})");
                 self.pat(pat);
             },
             PatKind::Guard(pat, cond) => {
@@ -827,6 +906,200 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
         }
     }
 
+    fn const_item_rhs(&self, rhs: &Binding<ConstItemRhs<'_>>) {
+        match rhs.value {
+            ConstItemRhs::Body(body) => {
+                bind!(self, body);
+                chain!(self, "let ConstItemRhs::Body({body}) = {rhs}");
+                self.body(body);
+            },
+            ConstItemRhs::TypeConst(const_arg) => {
+                bind!(self, const_arg);
+                chain!(self, "let ConstItemRhs::TypeConst({const_arg}) = {rhs}");
+                self.const_arg(const_arg);
+            },
+        }
+    }
+
+    fn variant_data(&self, data: &Binding<&VariantData<'_>>) {
+        let kind = |kind| chain!(self, "let VariantData::{kind} = {data}");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match *data.value {
+            VariantData::Struct { fields, .. } => {
+                bind!(self, fields);
+                kind!("Struct {{ fields: {fields}, .. }}");
+                self.slice(fields, |field| self.ident(field!(field.ident)));
+            },
+            VariantData::Tuple(fields, ..) => {
+                bind!(self, fields);
+                kind!("Tuple({fields}, ..)");
+                self.slice(fields, |_| {});
+            },
+            VariantData::Unit(..) => kind!("Unit(..)"),
+        }
+    }
+
+    fn item(&self, item: &Binding<&hir::Item<'_>>) {
+        let kind = |kind| chain!(self, "let ItemKind::{kind} = {item}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match item.value.kind {
+            ItemKind::Fn { ident, body, .. } => {
+                bind!(self, ident, body);
+                kind!("Fn {{ ident: {ident}, body: {body}, .. }}");
+                self.ident(ident);
+                self.body(body);
+            },
+            ItemKind::Static(mutability, ident, _, body) => {
+                bind!(self, ident, body);
+                kind!("Static(Mutability::{mutability:?}, {ident}, _, {body})");
+                self.ident(ident);
+                self.body(body);
+            },
+            ItemKind::Const(ident, _, _, rhs) => {
+                bind!(self, ident, rhs);
+                kind!("Const({ident}, _, _, {rhs})");
+                self.ident(ident);
+                self.const_item_rhs(rhs);
+            },
+            ItemKind::Struct(ident, _, ref data) => {
+                bind!(self, ident, data);
+                kind!("Struct({ident}, _, ref {data})");
+                self.ident(ident);
+                self.variant_data(data);
+            },
+            ItemKind::Union(ident, _, ref data) => {
+                bind!(self, ident, data);
+                kind!("Union({ident}, _, ref {data})");
+                self.ident(ident);
+                self.variant_data(data);
+            },
+            ItemKind::Enum(ident, _, ref enum_def) => {
+                bind!(self, ident, enum_def);
+                kind!("Enum({ident}, _, ref {enum_def})");
+                self.ident(ident);
+                self.slice(field!(enum_def.variants), |variant| {
+                    self.ident(field!(variant.ident));
+                    self.variant_data(&Binding {
+                        name: format!("&{variant}.data"),
+                        value: &variant.value.data,
+                    });
+                });
+            },
+            ItemKind::Impl(ref impl_) => {
+                bind!(self, impl_);
+                kind!("Impl(ref {impl_})");
+                self.option(field!(impl_.of_trait), "of_trait", |of_trait| {
+                    self.trait_ref(&Binding {
+                        name: format!("{of_trait}.trait_ref"),
+                        value: &of_trait.value.trait_ref,
+                    });
+                });
+                self.ty(field!(impl_.self_ty));
+            },
+            ItemKind::Trait(_, _, _, ident, ..) => {
+                bind!(self, ident);
+                kind!("Trait(_, _, _, {ident}, ..)");
+                self.ident(ident);
+            },
+            ItemKind::TraitAlias(_, ident, ..) => {
+                bind!(self, ident);
+                kind!("TraitAlias(_, {ident}, ..)");
+                self.ident(ident);
+            },
+            ItemKind::TyAlias(ident, _, ty) => {
+                bind!(self, ident, ty);
+                kind!("TyAlias({ident}, _, {ty})");
+                self.ident(ident);
+                self.ty(ty);
+            },
+            ItemKind::Mod(ident, _) => {
+                bind!(self, ident);
+                kind!("Mod({ident}, _)");
+                self.ident(ident);
+            },
+            ItemKind::Macro(ident, ..) => {
+                bind!(self, ident);
+                kind!("Macro({ident}, ..)");
+                self.ident(ident);
+            },
+            ItemKind::ExternCrate(..) => kind!("ExternCrate(..)"),
+            ItemKind::Use(..) => kind!("Use(..)"),
+            ItemKind::ForeignMod { .. } => kind!("ForeignMod {{ .. }}"),
+            ItemKind::GlobalAsm { .. } => kind!("GlobalAsm {{ .. }}"),
+        }
+    }
+
+    fn impl_item(&self, item: &Binding<&hir::ImplItem<'_>>) {
+        let kind = |kind| chain!(self, "let ImplItemKind::{kind} = {item}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match item.value.kind {
+            ImplItemKind::Fn(_, body) => {
+                bind!(self, body);
+                kind!("Fn(_, {body})");
+                self.ident(field!(item.ident));
+                self.body(body);
+            },
+            ImplItemKind::Const(_, rhs) => {
+                bind!(self, rhs);
+                kind!("Const(_, {rhs})");
+                self.ident(field!(item.ident));
+                self.const_item_rhs(rhs);
+            },
+            ImplItemKind::Type(ty) => {
+                bind!(self, ty);
+                kind!("Type({ty})");
+                self.ident(field!(item.ident));
+                self.ty(ty);
+            },
+        }
+    }
+
+    fn trait_item(&self, item: &Binding<&hir::TraitItem<'_>>) {
+        let kind = |kind| chain!(self, "let TraitItemKind::{kind} = {item}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match item.value.kind {
+            TraitItemKind::Fn(_, TraitFn::Provided(body)) => {
+                bind!(self, body);
+                kind!("Fn(_, TraitFn::Provided({body}))");
+                self.ident(field!(item.ident));
+                self.body(body);
+            },
+            TraitItemKind::Fn(_, TraitFn::Required(_)) => {
+                kind!("Fn(_, TraitFn::Required(_))");
+                self.ident(field!(item.ident));
+            },
+            TraitItemKind::Const(_, rhs) => {
+                bind!(self, rhs);
+                kind!("Const(_, {rhs})");
+                self.ident(field!(item.ident));
+                self.option(rhs, "rhs", |rhs| self.const_item_rhs(rhs));
+            },
+            TraitItemKind::Type(..) => {
+                kind!("Type(..)");
+                self.ident(field!(item.ident));
+            },
+        }
+    }
+
+    fn dotdot(&self, dotdot: &Binding<DotDotPos>) {
+        match dotdot.value.as_opt_usize() {
+            Some(pos) => chain!(self, "{dotdot}.as_opt_usize() == Some({pos})"),
+            None => chain!(self, "{dotdot}.as_opt_usize().is_none()"),
+        }
+    }
+
     fn stmt(&self, stmt: &Binding<&hir::Stmt<'_>>) {
         let kind = |kind| chain!(self, "let StmtKind::{kind} = {stmt}.kind");
         macro_rules! kind {
@@ -1,17 +1,20 @@
 use ControlFlow::{Break, Continue};
+use clippy_config::Conf;
 use clippy_utils::diagnostics::span_lint_and_then;
+use clippy_utils::paths::{PathNS, lookup_path_str};
 use clippy_utils::res::{MaybeDef, MaybeResPath};
-use clippy_utils::{fn_def_id, get_enclosing_block};
+use clippy_utils::{fn_def_id, get_enclosing_block, sym};
 use rustc_ast::Mutability;
 use rustc_ast::visit::visit_opt;
 use rustc_errors::Applicability;
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def_id::{DefIdSet, LocalDefId};
 use rustc_hir::intravisit::{Visitor, walk_block, walk_expr};
 use rustc_hir::{Expr, ExprKind, HirId, LetStmt, Node, PatKind, Stmt, StmtKind};
-use rustc_lint::{LateContext, LateLintPass};
+use rustc_lint::{LateContext, LateLintPass, Lint};
 use rustc_middle::hir::nested_filter;
-use rustc_session::declare_lint_pass;
-use rustc_span::{Span, sym};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::impl_lint_pass;
+use rustc_span::Span;
 use std::ops::ControlFlow;
 
 declare_clippy_lint! {
@@ -54,13 +57,73 @@ declare_clippy_lint! {
     suspicious,
     "not waiting on a spawned child process"
 }
-declare_lint_pass!(ZombieProcesses => [ZOMBIE_PROCESSES]);
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Looks for code that spawns a thread but drops its `JoinHandle` without calling `join()`.
+    /// Other handles which should be joined, e.g. the handles of async tasks, can be added with
+    /// the `join-handle-types` configuration.
+    ///
+    /// Like `zombie_processes`, if the handle is assigned to a binding, the lint conservatively
+    /// checks that all uses of the variable definitely don't join it, and the `join()` call must
+    /// be unconditionally reachable after the spawn expression.
+    ///
+    /// ### Why restrict this?
+    /// Dropping the handle detaches the thread. If the thread panics, the panic is silently lost
+    /// instead of being returned by `join()`, and nothing waits for the thread to finish its work
+    /// before the program exits. This is intended for threads which run in the background for the
+    /// whole lifetime of the program, but not for workers whose failures must be handled.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// std::thread::spawn(|| {
+    ///     // ..
+    /// });
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// let handle = std::thread::spawn(|| {
+    ///     // ..
+    /// });
+    /// handle.join().expect("worker panicked");
+    /// ```
+    #[clippy::version = "1.93.0"]
+    pub DETACHED_JOIN_HANDLES,
+    restriction,
+    "not joining a spawned thread"
+}
+
+pub struct ZombieProcesses {
+    join_handle_types: DefIdSet,
+}
+
+impl ZombieProcesses {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf) -> Self {
+        Self {
+            join_handle_types: conf
+                .join_handle_types
+                .iter()
+                .flat_map(|path| lookup_path_str(tcx, PathNS::Type, path))
+                .collect(),
+        }
+    }
+}
+
+impl_lint_pass!(ZombieProcesses => [ZOMBIE_PROCESSES, DETACHED_JOIN_HANDLES]);
 
 impl<'tcx> LateLintPass<'tcx> for ZombieProcesses {
     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
         if let ExprKind::Call(..) | ExprKind::MethodCall(..) = expr.kind
-            && let child_ty = cx.typeck_results().expr_ty(expr)
-            && child_ty.is_diag_item(cx, sym::Child)
+            && let handle_ty = cx.typeck_results().expr_ty(expr)
+            && let Some(handle) = if handle_ty.is_diag_item(cx, sym::Child) {
+                Some(Handle::Child)
+            } else if let ty::Adt(adt, _) = handle_ty.kind()
+                && self.join_handle_types.contains(&adt.did())
+            {
+                Some(Handle::Join)
+            } else {
+                None
+            }
         {
             match cx.tcx.parent_hir_node(expr.hir_id) {
                 Node::LetStmt(local)
@@ -69,6 +132,7 @@ impl<'tcx> LateLintPass<'tcx> for ZombieProcesses {
                 {
                     let mut vis = WaitFinder {
                         cx,
+                        handle,
                         local_id,
                         create_id: expr.hir_id,
                         body_id: cx.tcx.hir_enclosing_body_owner(expr.hir_id),
@@ -98,18 +162,18 @@ impl<'tcx> LateLintPass<'tcx> for ZombieProcesses {
                     };
 
                     // Don't emit a suggestion since the binding is used later
-                    check(cx, expr, cause, false);
+                    check(cx, expr, handle, cause, false);
                 },
                 Node::LetStmt(&LetStmt { pat, .. }) if let PatKind::Wild = pat.kind => {
                     // `let _ = child;`, also dropped immediately without `wait()`ing
-                    check(cx, expr, Cause::NeverWait, true);
+                    check(cx, expr, handle, Cause::NeverWait, true);
                 },
                 Node::Stmt(&Stmt {
                     kind: StmtKind::Semi(_),
                     ..
                 }) => {
                     // Immediately dropped. E.g. `std::process::Command::new("echo").spawn().unwrap();`
-                    check(cx, expr, Cause::NeverWait, true);
+                    check(cx, expr, handle, Cause::NeverWait, true);
                 },
                 _ => {},
             }
@@ -117,6 +181,44 @@ impl<'tcx> LateLintPass<'tcx> for ZombieProcesses {
     }
 }
 
+/// The kind of handle which needs to be waited on.
+#[derive(Clone, Copy)]
+enum Handle {
+    /// A `std::process::Child`.
+    Child,
+    /// One of the `join-handle-types`.
+    Join,
+}
+
+impl Handle {
+    fn lint(self) -> &'static Lint {
+        match self {
+            Handle::Child => ZOMBIE_PROCESSES,
+            Handle::Join => DETACHED_JOIN_HANDLES,
+        }
+    }
+
+    fn wait_call(self) -> &'static str {
+        match self {
+            Handle::Child => "`wait()`",
+            Handle::Join => "`join()`",
+        }
+    }
+
+    /// Checks if the method call uses the handle without waiting on it.
+    fn is_non_waiting_call(self, cx: &LateContext<'_>, expr: &Expr<'_>) -> bool {
+        match self {
+            Handle::Child => fn_def_id(cx, expr)
+                .and_then(|fn_did| cx.tcx.get_diagnostic_name(fn_did))
+                .is_some_and(|fn_name| matches!(fn_name, sym::child_id | sym::child_kill)),
+            Handle::Join => matches!(
+                expr.kind,
+                ExprKind::MethodCall(path, ..) if matches!(path.ident.name, sym::thread | sym::is_finished)
+            ),
+        }
+    }
+}
+
 struct MaybeWait(Span);
 
 /// A visitor responsible for finding a `wait()` call on a local variable.
@@ -132,6 +234,7 @@ struct MaybeWait(Span);
 /// Doing it like this means more FNs, but FNs are better than FPs.
 struct WaitFinder<'a, 'tcx> {
     cx: &'a LateContext<'tcx>,
+    handle: Handle,
     local_id: HirId,
     create_id: HirId,
     body_id: LocalDefId,
@@ -176,10 +279,7 @@ impl<'tcx> Visitor<'tcx> for WaitFinder<'_, 'tcx> {
                 }) => {},
                 Node::Expr(expr) if let ExprKind::Field(..) = expr.kind => {},
                 Node::Expr(expr) if let ExprKind::AddrOf(_, Mutability::Not, _) = expr.kind => {},
-                Node::Expr(expr)
-                    if let Some(fn_did) = fn_def_id(self.cx, expr)
-                        && let Some(fn_name) = self.cx.tcx.get_diagnostic_name(fn_did)
-                        && matches!(fn_name, sym::child_id | sym::child_kill) => {},
+                Node::Expr(expr) if self.handle.is_non_waiting_call(self.cx, expr) => {},
 
                 // Conservatively assume that all other kinds of nodes call `.wait()` somehow.
                 _ => return Break(MaybeWait(ex.span)),
@@ -268,21 +368,33 @@ enum Cause {
 }
 
 impl Cause {
-    fn message(self) -> &'static str {
-        match self {
-            Cause::NeverWait => "spawned process is never `wait()`ed on",
-            Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. } => {
-                "spawned process is not `wait()`ed on in all code paths"
-            },
+    fn message(self, handle: Handle) -> &'static str {
+        match (self, handle) {
+            (Cause::NeverWait, Handle::Child) => "spawned process is never `wait()`ed on",
+            (Cause::NeverWait, Handle::Join) => "join handle is dropped without being joined",
+            (
+                Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. },
+                Handle::Child,
+            ) => "spawned process is not `wait()`ed on in all code paths",
+            (
+                Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. },
+                Handle::Join,
+            ) => "join handle is not joined in all code paths",
         }
     }
 
-    fn fallback_help(self) -> &'static str {
-        match self {
-            Cause::NeverWait => "consider calling `.wait()`",
-            Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. } => {
-                "consider calling `.wait()` in all code paths"
-            },
+    fn fallback_help(self, handle: Handle) -> &'static str {
+        match (self, handle) {
+            (Cause::NeverWait, Handle::Child) => "consider calling `.wait()`",
+            (Cause::NeverWait, Handle::Join) => "consider joining the handle",
+            (
+                Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. },
+                Handle::Child,
+            ) => "consider calling `.wait()` in all code paths",
+            (
+                Cause::EarlyReturn { .. } | Cause::MissingWaitInBranch { .. } | Cause::MissingElse { .. },
+                Handle::Join,
+            ) => "consider joining the handle in all code paths",
         }
     }
 }
@@ -294,7 +406,13 @@ impl Cause {
 /// `let _ = <expr that spawns child>;`.
 ///
 /// This checks if the program doesn't unconditionally exit after the spawn expression.
-fn check<'tcx>(cx: &LateContext<'tcx>, spawn_expr: &'tcx Expr<'tcx>, cause: Cause, emit_suggestion: bool) {
+fn check<'tcx>(
+    cx: &LateContext<'tcx>,
+    spawn_expr: &'tcx Expr<'tcx>,
+    handle: Handle,
+    cause: Cause,
+    emit_suggestion: bool,
+) {
     let Some(block) = get_enclosing_block(cx, spawn_expr.hir_id) else {
         return;
     };
@@ -308,45 +426,55 @@ fn check<'tcx>(cx: &LateContext<'tcx>, spawn_expr: &'tcx Expr<'tcx>, cause: Caus
         return;
     }
 
-    span_lint_and_then(cx, ZOMBIE_PROCESSES, spawn_expr.span, cause.message(), |diag| {
+    span_lint_and_then(cx, handle.lint(), spawn_expr.span, cause.message(handle), |diag| {
+        let wait = handle.wait_call();
         match cause {
             Cause::EarlyReturn { wait_span, return_span } => {
                 diag.span_note(
                     return_span,
-                    "no `wait()` call exists on the code path to this early return",
+                    format!("no {wait} call exists on the code path to this early return"),
                 );
                 diag.span_note(
                     wait_span,
-                    "`wait()` call exists, but it is unreachable due to the early return",
+                    format!("{wait} call exists, but it is unreachable due to the early return"),
                 );
             },
             Cause::MissingWaitInBranch { wait_span, branch_span } => {
-                diag.span_note(branch_span, "`wait()` is not called in this if branch");
-                diag.span_note(wait_span, "`wait()` is called in the other branch");
+                diag.span_note(branch_span, format!("{wait} is not called in this if branch"));
+                diag.span_note(wait_span, format!("{wait} is called in the other branch"));
             },
             Cause::MissingElse { if_span, wait_span } => {
                 diag.span_note(
                     if_span,
-                    "this if expression has a `wait()` call, but it is missing an else block",
+                    format!("this if expression has a {wait} call, but it is missing an else block"),
                 );
-                diag.span_note(wait_span, "`wait()` called here");
+                diag.span_note(wait_span, format!("{wait} called here"));
             },
             Cause::NeverWait => {},
         }
 
-        if emit_suggestion {
-            diag.span_suggestion(
-                spawn_expr.span.shrink_to_hi(),
-                "try",
-                ".wait()",
-                Applicability::MaybeIncorrect,
-            );
-        } else {
-            diag.help(cause.fallback_help());
-        }
+        match handle {
+            Handle::Child => {
+                if emit_suggestion {
+                    diag.span_suggestion(
+                        spawn_expr.span.shrink_to_hi(),
+                        "try",
+                        ".wait()",
+                        Applicability::MaybeIncorrect,
+                    );
+                } else {
+                    diag.help(cause.fallback_help(handle));
+                }
 
-        diag.note("not doing so might leave behind zombie processes")
-            .note("see https://doc.rust-lang.org/stable/std/process/struct.Child.html#warning");
+                diag.note("not doing so might leave behind zombie processes")
+                    .note("see https://doc.rust-lang.org/stable/std/process/struct.Child.html#warning");
+            },
+            // The configured handle types aren't necessarily joined with a `join()` method.
+            Handle::Join => {
+                diag.help(cause.fallback_help(handle));
+                diag.note("dropping the handle detaches the thread or task, so a panic in it goes unnoticed");
+            },
+        }
     });
 }
 
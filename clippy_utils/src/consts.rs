@@ -6,28 +6,33 @@
 
 use crate::res::MaybeDef;
 use crate::source::{SpanRangeExt, walk_span_to_context};
-use crate::{clip, is_direct_expn_of, sext, sym, unsext};
+use crate::visitors::for_each_expr_without_closures;
+use crate::{clip, is_direct_expn_of, is_inside_always_const_context, sext, sym, unsext};
 
 use rustc_abi::Size;
 use rustc_apfloat::Float;
 use rustc_apfloat::ieee::{Half, Quad};
 use rustc_ast::ast::{LitFloatType, LitKind};
-use rustc_hir::def::{DefKind, Res};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
+use rustc_hir::def_id::DefId;
 use rustc_hir::{
-    BinOpKind, Block, ConstArgKind, ConstBlock, ConstItemRhs, Expr, ExprKind, HirId, PatExpr, PatExprKind, QPath,
-    TyKind, UnOp,
+    BinOpKind, BindingMode, Block, ConstArgKind, ConstBlock, ConstItemRhs, Expr, ExprKind, HirId, PatExpr, PatExprKind,
+    PatKind, QPath, StructTailExpr, TyKind, UnOp,
 };
 use rustc_lexer::{FrontmatterAllowed, tokenize};
 use rustc_lint::LateContext;
 use rustc_middle::mir::ConstValue;
 use rustc_middle::mir::interpret::{Scalar, alloc_range};
-use rustc_middle::ty::{self, FloatTy, IntTy, ScalarInt, Ty, TyCtxt, TypeckResults, UintTy};
+use rustc_middle::ty::adjustment::{Adjust, AutoBorrow};
+use rustc_middle::ty::{self, FloatTy, GenericArgsRef, Instance, IntTy, ScalarInt, Ty, TyCtxt, TypeckResults, UintTy};
 use rustc_middle::{bug, mir, span_bug};
 use rustc_span::{Symbol, SyntaxContext};
 use std::cell::Cell;
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
 use std::iter;
+use std::ops::ControlFlow;
 
 /// A `LitKind`-like enum to fold constant `Expr`s into.
 #[derive(Debug, Clone)]
@@ -442,13 +447,16 @@ pub fn lit_to_mir_constant(lit: &LitKind, ty: Option<Ty<'_>>) -> Constant {
 }
 
 /// The source of a constant value.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ConstantSource {
     /// The value is determined solely from the expression.
     Local,
     /// The value is dependent on another definition that may change independently from the local
     /// expression.
     NonLocal,
+    /// The value is the result of a call to a `const fn`, which computes it from its current
+    /// implementation.
+    ConstFnCall,
 }
 impl ConstantSource {
     pub fn is_local(self) -> bool {
@@ -502,6 +510,10 @@ pub struct ConstEvalCtxt<'tcx> {
     typeck: &'tcx TypeckResults<'tcx>,
     source: Cell<ConstantSource>,
     ctxt: Cell<SyntaxContext>,
+    /// The values of the parameters when evaluating the body of a `const fn`.
+    params: FxHashMap<HirId, Constant>,
+    /// The number of `const fn` bodies being evaluated.
+    depth: u32,
 }
 
 impl<'tcx> ConstEvalCtxt<'tcx> {
@@ -514,6 +526,8 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
             typeck: cx.typeck_results(),
             source: Cell::new(ConstantSource::Local),
             ctxt: Cell::new(SyntaxContext::root()),
+            params: FxHashMap::default(),
+            depth: 0,
         }
     }
 
@@ -525,6 +539,8 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
             typeck,
             source: Cell::new(ConstantSource::Local),
             ctxt: Cell::new(SyntaxContext::root()),
+            params: FxHashMap::default(),
+            depth: 0,
         }
     }
 
@@ -583,13 +599,22 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
         }
     }
 
+    /// Records that the value depends on the given source, unless it already depends on a less
+    /// local one.
+    fn set_source(&self, source: ConstantSource) {
+        self.source.set(self.source.get().max(source));
+    }
+
     fn check_ctxt(&self, ctxt: SyntaxContext) {
         if self.ctxt.get() != ctxt {
-            self.source.set(ConstantSource::NonLocal);
+            self.set_source(ConstantSource::NonLocal);
         }
     }
 
     fn qpath(&self, qpath: &QPath<'_>, hir_id: HirId) -> Option<Constant> {
+        if let Res::Local(id) = self.typeck.qpath_res(qpath, hir_id) {
+            return self.params.get(&id).cloned();
+        }
         self.fetch_path(qpath, hir_id)
             .and_then(|c| mir_to_const(self.tcx, c, self.typeck.node_type(hir_id)))
     }
@@ -628,23 +653,8 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
                 self.check_ctxt(e.span.ctxt());
                 self.binop(op.node, left, right)
             },
-            ExprKind::Call(callee, []) => {
-                // We only handle a few const functions for now.
-                if let ExprKind::Path(qpath) = &callee.kind
-                    && let Some(did) = self.typeck.qpath_res(qpath, callee.hir_id).opt_def_id()
-                {
-                    match self.tcx.get_diagnostic_name(did) {
-                        Some(sym::i8_legacy_fn_max_value) => Some(Constant::Int(i8::MAX as u128)),
-                        Some(sym::i16_legacy_fn_max_value) => Some(Constant::Int(i16::MAX as u128)),
-                        Some(sym::i32_legacy_fn_max_value) => Some(Constant::Int(i32::MAX as u128)),
-                        Some(sym::i64_legacy_fn_max_value) => Some(Constant::Int(i64::MAX as u128)),
-                        Some(sym::i128_legacy_fn_max_value) => Some(Constant::Int(i128::MAX as u128)),
-                        _ => None,
-                    }
-                } else {
-                    None
-                }
-            },
+            ExprKind::Call(callee, args) => self.call(e, callee, args),
+            ExprKind::MethodCall(path, receiver, args, _) => self.method_call(e, path.ident.name, receiver, args),
             ExprKind::Index(arr, index, _) => self.index(arr, index),
             ExprKind::AddrOf(_, _, inner) => self.expr(inner).map(|r| Constant::Ref(Box::new(r))),
             ExprKind::Field(base, ref field)
@@ -662,10 +672,151 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
                 self.check_ctxt(field.span.ctxt());
                 mir_to_const(self.tcx, desired_field, ty)
             },
+            // Aggregates are only looked through inside `const fn` bodies, where they are built from the
+            // arguments.
+            ExprKind::Field(base, ref field)
+                if self.depth != 0
+                    && self.typeck.expr_adjustments(base).is_empty()
+                    && let Some(field_expr) = literal_field(self.typeck, base, field.name) =>
+            {
+                self.check_ctxt(field.span.ctxt());
+                self.expr(field_expr)
+            },
+            ExprKind::Field(base, ref field)
+                if self.depth != 0
+                    && self.typeck.expr_adjustments(base).is_empty()
+                    && let Some(Constant::Tuple(fields)) = self.expr(base) =>
+            {
+                self.check_ctxt(field.span.ctxt());
+                fields.into_iter().nth(field.name.as_str().parse().ok()?)
+            },
             _ => None,
         }
     }
 
+    fn call(&self, e: &Expr<'_>, callee: &Expr<'_>, args: &[Expr<'_>]) -> Option<Constant> {
+        if args.is_empty()
+            && let ExprKind::Path(qpath) = &callee.kind
+            && let Some(did) = self.typeck.qpath_res(qpath, callee.hir_id).opt_def_id()
+        {
+            // These are deprecated, but not `const fn`s.
+            match self.tcx.get_diagnostic_name(did) {
+                Some(sym::i8_legacy_fn_max_value) => return Some(Constant::Int(i8::MAX as u128)),
+                Some(sym::i16_legacy_fn_max_value) => return Some(Constant::Int(i16::MAX as u128)),
+                Some(sym::i32_legacy_fn_max_value) => return Some(Constant::Int(i32::MAX as u128)),
+                Some(sym::i64_legacy_fn_max_value) => return Some(Constant::Int(i64::MAX as u128)),
+                Some(sym::i128_legacy_fn_max_value) => return Some(Constant::Int(i128::MAX as u128)),
+                _ => {},
+            }
+        }
+        let ty::FnDef(did, generic_args) = *self.typeck.expr_ty(callee).kind() else {
+            return None;
+        };
+        let args = args
+            .iter()
+            .map(|arg| self.adjusted_expr(arg))
+            .collect::<Option<Vec<_>>>()?;
+        self.call_const_fn(e, did, generic_args, args)
+    }
+
+    fn method_call(&self, e: &Expr<'_>, name: Symbol, receiver: &Expr<'_>, args: &[Expr<'_>]) -> Option<Constant> {
+        if args.is_empty()
+            && matches!(name, sym::len | sym::is_empty)
+            && let Some(len) = self.expr(receiver).and_then(|receiver| match receiver.peel_refs() {
+                Constant::Str(s) => Some(s.len()),
+                Constant::Binary(b) => Some(b.len()),
+                Constant::Vec(v) => Some(v.len()),
+                Constant::Repeat(_, n) => n.try_into().ok(),
+                _ => None,
+            })
+        {
+            // Don't treat e.g. `"a".len()` and `1` as the same expression.
+            self.set_source(ConstantSource::NonLocal);
+            return Some(if name == sym::is_empty {
+                Constant::Bool(len == 0)
+            } else {
+                Constant::Int(len as u128)
+            });
+        }
+        let (DefKind::AssocFn, did) = self.typeck.type_dependent_def(e.hir_id)? else {
+            return None;
+        };
+        let args = iter::once(receiver)
+            .chain(args)
+            .map(|arg| self.adjusted_expr(arg))
+            .collect::<Option<Vec<_>>>()?;
+        self.call_const_fn(e, did, self.typeck.node_args(e.hir_id), args)
+    }
+
+    /// Evaluates a function argument after its adjustments are applied.
+    fn adjusted_expr(&self, e: &Expr<'_>) -> Option<Constant> {
+        let mut value = self.expr(e)?;
+        for adjustment in self.typeck.expr_adjustments(e) {
+            value = match adjustment.kind {
+                Adjust::Borrow(AutoBorrow::Ref(_)) => Constant::Ref(Box::new(value)),
+                Adjust::Deref(None) if let Constant::Ref(inner) = value => *inner,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Evaluates a call to a `const fn` of the local crate by evaluating its body with the
+    /// arguments bound to its parameters.
+    ///
+    /// Functions of other crates and functions whose body uses `cfg!` aren't evaluated, as their
+    /// result may depend on the target or on the configuration. Neither are calls in always-const
+    /// contexts, which rustc evaluates itself.
+    fn call_const_fn(
+        &self,
+        e: &Expr<'_>,
+        did: DefId,
+        generic_args: GenericArgsRef<'tcx>,
+        args: Vec<Constant>,
+    ) -> Option<Constant> {
+        if self.depth >= MAX_CONST_FN_DEPTH
+            || !self.tcx.is_const_fn(did)
+            || is_inside_always_const_context(self.tcx, e.hir_id)
+        {
+            return None;
+        }
+        // Resolve trait methods to the implementation being called.
+        let generic_args = self.tcx.erase_and_anonymize_regions(generic_args);
+        let instance = Instance::try_resolve(self.tcx, self.typing_env, did, generic_args).ok()??;
+        let ty::InstanceKind::Item(did) = instance.def else {
+            return None;
+        };
+        let local_did = did.as_local()?;
+        if !self.tcx.is_const_fn(did) || self.tcx.generics_of(did).count() != 0 {
+            return None;
+        }
+        let body = self.tcx.hir_maybe_body_owned_by(local_did)?;
+        if body.params.len() != args.len() || uses_cfg(body.value) {
+            return None;
+        }
+        let params = body
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| match param.pat.kind {
+                PatKind::Binding(BindingMode::NONE, id, _, None) => Some((id, arg)),
+                _ => None,
+            })
+            .collect::<Option<_>>()?;
+        let ecx = Self {
+            tcx: self.tcx,
+            typing_env: ty::TypingEnv::post_analysis(self.tcx, did),
+            typeck: self.tcx.typeck(local_did),
+            source: Cell::new(ConstantSource::Local),
+            ctxt: Cell::new(body.value.span.ctxt()),
+            params,
+            depth: self.depth + 1,
+        };
+        let res = ecx.expr(body.value)?;
+        self.set_source(ConstantSource::ConstFnCall);
+        Some(res)
+    }
+
     /// Simple constant folding to determine if an expression is an empty slice, str, array, …
     /// `None` will be returned if the constness cannot be determined, or if the resolution
     /// leaves the local crate.
@@ -845,7 +996,7 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
                 did
             },
             _ if let Res::Def(DefKind::Const | DefKind::AssocConst, did) = self.typeck.qpath_res(qpath, id) => {
-                self.source.set(ConstantSource::NonLocal);
+                self.set_source(ConstantSource::NonLocal);
                 did
             },
             _ => return None,
@@ -866,6 +1017,7 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
 
         match (lhs, index) {
             (Some(Constant::Vec(vec)), Some(Constant::Int(index))) => match vec.get(index as usize) {
+                Some(Constant::Int(x)) => Some(Constant::Int(*x)),
                 Some(Constant::F16(x)) => Some(Constant::F16(*x)),
                 Some(Constant::F32(x)) => Some(Constant::F32(*x)),
                 Some(Constant::F64(x)) => Some(Constant::F64(*x)),
@@ -909,11 +1061,11 @@ impl<'tcx> ConstEvalCtxt<'tcx> {
                         .filter(|t| !matches!(t, Whitespace | LineComment { .. } | BlockComment { .. } | Semi))
                         .eq([OpenBrace])
                     {
-                        self.source.set(ConstantSource::NonLocal);
+                        self.set_source(ConstantSource::NonLocal);
                     }
                 } else {
                     // Unable to access the source. Assume a non-local dependency.
-                    self.source.set(ConstantSource::NonLocal);
+                    self.set_source(ConstantSource::NonLocal);
                 }
             }
 
@@ -1070,23 +1222,69 @@ pub fn mir_to_const<'tcx>(tcx: TyCtxt<'tcx>, val: ConstValue, ty: Ty<'tcx>) -> O
         (ConstValue::Indirect { alloc_id, offset }, ty::Array(sub_type, len)) => {
             let alloc = tcx.global_alloc(alloc_id).unwrap_memory().inner();
             let len = len.try_to_target_usize(tcx)?;
-            let ty::Float(flt) = sub_type.kind() else {
-                return None;
+            let width = tcx.sess.target.pointer_width;
+            let bits = match *sub_type.kind() {
+                ty::Float(flt) => flt.bit_width(),
+                ty::Int(ity) => ity.normalize(width).bit_width()?,
+                ty::Uint(uty) => uty.normalize(width).bit_width()?,
+                _ => return None,
             };
-            let size = Size::from_bits(flt.bit_width());
+            let size = Size::from_bits(bits);
             let mut res = Vec::new();
             for idx in 0..len {
                 let range = alloc_range(offset + size * idx, size);
                 let val = alloc.read_scalar(&tcx, range, /* read_provenance */ false).ok()?;
-                res.push(match flt {
-                    FloatTy::F16 => Constant::F16(val.to_u16().discard_err()?),
-                    FloatTy::F32 => Constant::F32(f32::from_bits(val.to_u32().discard_err()?)),
-                    FloatTy::F64 => Constant::F64(f64::from_bits(val.to_u64().discard_err()?)),
-                    FloatTy::F128 => Constant::F128(val.to_u128().discard_err()?),
+                res.push(match *sub_type.kind() {
+                    ty::Float(FloatTy::F16) => Constant::F16(val.to_u16().discard_err()?),
+                    ty::Float(FloatTy::F32) => Constant::F32(f32::from_bits(val.to_u32().discard_err()?)),
+                    ty::Float(FloatTy::F64) => Constant::F64(f64::from_bits(val.to_u64().discard_err()?)),
+                    ty::Float(FloatTy::F128) => Constant::F128(val.to_u128().discard_err()?),
+                    _ => Constant::Int(val.to_bits(size).discard_err()?),
                 });
             }
             Some(Constant::Vec(res))
         },
+        (_, ty::Tuple(_)) => {
+            let fields = tcx.try_destructure_mir_constant_for_user_output(val, ty)?.fields;
+            fields
+                .iter()
+                .map(|&(val, ty)| mir_to_const(tcx, val, ty))
+                .collect::<Option<_>>()
+                .map(Constant::Tuple)
+        },
+        _ => None,
+    }
+}
+
+/// How deeply nested the `const fn` calls evaluated from an expression can be.
+const MAX_CONST_FN_DEPTH: u32 = 8;
+
+/// Checks if the body of a function uses `cfg!`.
+fn uses_cfg(body: &Expr<'_>) -> bool {
+    for_each_expr_without_closures(body, |e| {
+        if is_direct_expn_of(e.span, sym::cfg).is_some() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .is_some()
+}
+
+/// If the expression is a struct literal or a call to a tuple struct constructor, gets the
+/// expression the given field is initialized with.
+fn literal_field<'a>(typeck: &TypeckResults<'_>, base: &'a Expr<'a>, field: Symbol) -> Option<&'a Expr<'a>> {
+    match base.kind {
+        ExprKind::Struct(_, fields, StructTailExpr::None) => {
+            fields.iter().find(|f| f.ident.name == field).map(|f| f.expr)
+        },
+        ExprKind::Call(callee, args)
+            if let ExprKind::Path(qpath) = &callee.kind
+                && let Res::Def(DefKind::Ctor(CtorOf::Struct, CtorKind::Fn), _) =
+                    typeck.qpath_res(qpath, callee.hir_id) =>
+        {
+            args.get(field.as_str().parse::<usize>().ok()?)
+        },
         _ => None,
     }
 }
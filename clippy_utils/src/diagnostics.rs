@@ -8,16 +8,101 @@
 //! Thank you!
 //! ~The `INTERNAL_METADATA_COLLECTOR` lint
 
-use rustc_errors::{Applicability, Diag, DiagMessage, MultiSpan, SubdiagMessage};
+use crate::source::snippet_indent;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_errors::{Applicability, Diag, DiagMessage, MultiSpan, SubdiagMessage, SuggestionStyle};
 #[cfg(debug_assertions)]
 use rustc_errors::{EmissionGuarantee, SubstitutionPart, Suggestions};
-use rustc_hir::HirId;
-use rustc_lint::{LateContext, Lint, LintContext};
+use rustc_hir::{HirId, Node};
+use rustc_lint::{EarlyContext, LateContext, Level, Lint, LintContext, LintId};
+use rustc_middle::lint::{LevelAndSource, LintLevelSource};
+use rustc_middle::ty::TyCtxt;
 use rustc_span::Span;
-use std::env;
+use std::cell::Cell;
+use std::sync::{Mutex, OnceLock};
+use std::{env, iter};
+
+/// The spans of the lint names in `#[allow]` attributes which suppressed a Clippy lint. Only set
+/// when tracked by [`track_allow_attributes`].
+static USED_ALLOWS: OnceLock<Mutex<FxHashSet<Span>>> = OnceLock::new();
+
+/// The lints listed in Clippy's lint list, set by [`set_documented_lints`].
+static DOCUMENTED_LINTS: OnceLock<FxHashSet<LintId>> = OnceLock::new();
+
+/// Sets the lints which get a link to Clippy's lint list added to their diagnostics.
+pub fn set_documented_lints(lints: impl IntoIterator<Item = &'static Lint>) {
+    DOCUMENTED_LINTS.get_or_init(|| lints.into_iter().map(LintId::of).collect());
+}
+
+/// Starts recording which `#[allow]` attributes for Clippy lints suppress a lint emitted by the
+/// functions in this module.
+///
+/// Attributes which [`is_lint_allowed`](crate::is_lint_allowed) or
+/// [`fulfill_or_allowed`](crate::fulfill_or_allowed) find the lint to be allowed by are recorded as
+/// well, since the lint may not be checked at all when it's allowed.
+pub fn track_allow_attributes() {
+    let _ = USED_ALLOWS.set(Mutex::default());
+}
+
+/// Checks if the lint level was set by an `#[allow]` attribute for Clippy lints while
+/// [`track_allow_attributes`] is enabled.
+fn is_tracked_allow(level: &LevelAndSource) -> bool {
+    USED_ALLOWS.get().is_some()
+        && level.level == Level::Allow
+        && matches!(level.src, LintLevelSource::Node { name, .. } if name.as_str().starts_with("clippy::"))
+}
+
+/// Gets the spans of the lint names in `#[allow]` attributes which suppressed a lint so far.
+pub fn used_allow_attributes() -> FxHashSet<Span> {
+    USED_ALLOWS
+        .get()
+        .map(|used| used.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+/// Records the `#[allow]` attribute the lint level was set by as used while
+/// [`track_allow_attributes`] is enabled.
+pub(crate) fn record_allow(level: impl FnOnce() -> LevelAndSource) {
+    if let Some(used) = USED_ALLOWS.get() {
+        let level = level();
+        if is_tracked_allow(&level)
+            && let LintLevelSource::Node { span, .. } = level.src
+        {
+            used.lock().unwrap().insert(span);
+        }
+    }
+}
+
+thread_local! {
+    static EMITTING_LINT: Cell<Option<&'static Lint>> = const { Cell::new(None) };
+}
+
+/// Gets the Clippy lint which is currently being emitted by one of the functions in this module.
+///
+/// This is meant to be called from a diagnostic tracking hook, which is called while the lint is
+/// emitted.
+pub fn emitting_lint() -> Option<&'static Lint> {
+    EMITTING_LINT.get()
+}
+
+/// Records the lint being emitted until it's dropped.
+struct EmittingLint(Option<&'static Lint>);
+
+impl EmittingLint {
+    fn set(lint: &'static Lint) -> Self {
+        Self(EMITTING_LINT.replace(Some(lint)))
+    }
+}
+
+impl Drop for EmittingLint {
+    fn drop(&mut self) {
+        EMITTING_LINT.set(self.0);
+    }
+}
 
 fn docs_link(diag: &mut Diag<'_, ()>, lint: &'static Lint) {
     if env::var("CLIPPY_DISABLE_DOCS_LINKS").is_err()
+        && is_documented(lint)
         && let Some(lint) = lint.name_lower().strip_prefix("clippy::")
     {
         diag.help(format!(
@@ -34,6 +119,77 @@ fn docs_link(diag: &mut Diag<'_, ()>, lint: &'static Lint) {
     }
 }
 
+/// Checks if the lint is documented in Clippy's lint list. This isn't the case for the lints
+/// defined in the configuration or loaded from other libraries.
+fn is_documented(lint: &'static Lint) -> bool {
+    DOCUMENTED_LINTS
+        .get()
+        .is_some_and(|lints| lints.contains(&LintId::of(lint)))
+}
+
+/// A lint context the functions in this module can emit lints with.
+///
+/// This is implemented for both [`LateContext`] and [`EarlyContext`].
+pub trait ExpectAttrContext: LintContext {
+    /// Gets the span of the statement or item enclosing the node the lint level of the lints
+    /// emitted with the context is taken from, if there is one.
+    fn expect_attr_span(&self) -> Option<Span>;
+}
+
+impl ExpectAttrContext for LateContext<'_> {
+    fn expect_attr_span(&self) -> Option<Span> {
+        expect_attr_span(self.tcx, self.last_node_with_lint_attrs)
+    }
+}
+
+impl ExpectAttrContext for EarlyContext<'_> {
+    /// The early context doesn't keep track of the AST node the lint level is taken from, so early
+    /// lints don't get an `#[expect]` suggestion.
+    fn expect_attr_span(&self) -> Option<Span> {
+        None
+    }
+}
+
+/// Gets the span of the statement or item enclosing the given node which an `#[expect]` attribute
+/// can be added to.
+fn expect_attr_span(tcx: TyCtxt<'_>, hir_id: HirId) -> Option<Span> {
+    iter::once(tcx.hir_node(hir_id))
+        .chain(tcx.hir_parent_iter(hir_id).map(|(_, node)| node))
+        .filter_map(|node| match node {
+            Node::Stmt(stmt) => Some(stmt.span),
+            Node::Item(item) => Some(item.span),
+            Node::TraitItem(item) => Some(item.span),
+            Node::ImplItem(item) => Some(item.span),
+            Node::ForeignItem(item) => Some(item.span),
+            _ => None,
+        })
+        .find(|span| !span.from_expansion())
+}
+
+/// Adds a suggestion to insert an `#[expect]` attribute for the lint at the given span.
+///
+/// The suggestion isn't shown in the rendered output, it's meant to be offered as a code action by
+/// editors. It can be disabled with the `CLIPPY_DISABLE_EXPECT_SUGGESTIONS` environment variable.
+fn expect_suggestion(
+    diag: &mut Diag<'_, ()>,
+    cx: &impl LintContext,
+    lint: &'static Lint,
+    span: impl FnOnce() -> Option<Span>,
+) {
+    if env::var_os("CLIPPY_DISABLE_EXPECT_SUGGESTIONS").is_none_or(|v| v == "0")
+        && let Some(span) = span()
+    {
+        let indent = snippet_indent(cx.sess(), span).unwrap_or_default();
+        diag.span_suggestion_with_style(
+            span.shrink_to_lo(),
+            "suppress the lint with `#[expect]`",
+            format!("#[expect({}, reason = \"...\")]\n{indent}", lint.name_lower()),
+            Applicability::HasPlaceholders,
+            SuggestionStyle::CompletelyHidden,
+        );
+    }
+}
+
 /// Makes sure that a diagnostic is well formed.
 ///
 /// rustc debug asserts a few properties about spans,
@@ -103,10 +259,18 @@ fn validate_diag(diag: &Diag<'_, impl EmissionGuarantee>) {
 ///    |     ^^^^^^^^^^^^^^^^^^^^^^^
 /// ```
 #[track_caller]
-pub fn span_lint<T: LintContext>(cx: &T, lint: &'static Lint, sp: impl Into<MultiSpan>, msg: impl Into<DiagMessage>) {
+pub fn span_lint<T: ExpectAttrContext>(
+    cx: &T,
+    lint: &'static Lint,
+    sp: impl Into<MultiSpan>,
+    msg: impl Into<DiagMessage>,
+) {
+    record_allow(|| cx.get_lint_level(lint));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, sp, |diag| {
         diag.primary_message(msg);
+        expect_suggestion(diag, cx, lint, || cx.expect_attr_span());
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -149,7 +313,7 @@ pub fn span_lint<T: LintContext>(cx: &T, lint: &'static Lint, sp: impl Into<Mult
 ///    = help: consider using `f64::NAN` if you would like a constant representing NaN
 /// ```
 #[track_caller]
-pub fn span_lint_and_help<T: LintContext>(
+pub fn span_lint_and_help<T: ExpectAttrContext>(
     cx: &T,
     lint: &'static Lint,
     span: impl Into<MultiSpan>,
@@ -157,6 +321,8 @@ pub fn span_lint_and_help<T: LintContext>(
     help_span: Option<Span>,
     help: impl Into<SubdiagMessage>,
 ) {
+    record_allow(|| cx.get_lint_level(lint));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, span, |diag| {
         diag.primary_message(msg);
@@ -165,6 +331,7 @@ pub fn span_lint_and_help<T: LintContext>(
         } else {
             diag.help(help.into());
         }
+        expect_suggestion(diag, cx, lint, || cx.expect_attr_span());
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -210,7 +377,7 @@ pub fn span_lint_and_help<T: LintContext>(
 ///    |            ^^^^^^^^^^^
 /// ```
 #[track_caller]
-pub fn span_lint_and_note<T: LintContext>(
+pub fn span_lint_and_note<T: ExpectAttrContext>(
     cx: &T,
     lint: &'static Lint,
     span: impl Into<MultiSpan>,
@@ -218,6 +385,8 @@ pub fn span_lint_and_note<T: LintContext>(
     note_span: Option<Span>,
     note: impl Into<SubdiagMessage>,
 ) {
+    record_allow(|| cx.get_lint_level(lint));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, span, |diag| {
         diag.primary_message(msg);
@@ -226,6 +395,7 @@ pub fn span_lint_and_note<T: LintContext>(
         } else {
             diag.note(note.into());
         }
+        expect_suggestion(diag, cx, lint, || cx.expect_attr_span());
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -254,15 +424,18 @@ pub fn span_lint_and_note<T: LintContext>(
 #[track_caller]
 pub fn span_lint_and_then<C, S, M, F>(cx: &C, lint: &'static Lint, sp: S, msg: M, f: F)
 where
-    C: LintContext,
+    C: ExpectAttrContext,
     S: Into<MultiSpan>,
     M: Into<DiagMessage>,
     F: FnOnce(&mut Diag<'_, ()>),
 {
+    record_allow(|| cx.get_lint_level(lint));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, sp, |diag| {
         diag.primary_message(msg);
         f(diag);
+        expect_suggestion(diag, cx, lint, || cx.expect_attr_span());
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -296,9 +469,12 @@ where
 /// the `#[allow]` will work.
 #[track_caller]
 pub fn span_lint_hir(cx: &LateContext<'_>, lint: &'static Lint, hir_id: HirId, sp: Span, msg: impl Into<DiagMessage>) {
+    record_allow(|| cx.tcx.lint_level_at_node(lint, hir_id));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.tcx.node_span_lint(lint, hir_id, sp, |diag| {
         diag.primary_message(msg);
+        expect_suggestion(diag, cx, lint, || expect_attr_span(cx.tcx, hir_id));
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -339,10 +515,13 @@ pub fn span_lint_hir_and_then(
     msg: impl Into<DiagMessage>,
     f: impl FnOnce(&mut Diag<'_, ()>),
 ) {
+    record_allow(|| cx.tcx.lint_level_at_node(lint, hir_id));
+    let _emitting = EmittingLint::set(lint);
     #[expect(clippy::disallowed_methods)]
     cx.tcx.node_span_lint(lint, hir_id, sp, |diag| {
         diag.primary_message(msg);
         f(diag);
+        expect_suggestion(diag, cx, lint, || expect_attr_span(cx.tcx, hir_id));
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -385,7 +564,7 @@ pub fn span_lint_hir_and_then(
 /// ```
 #[cfg_attr(not(debug_assertions), expect(clippy::collapsible_span_lint_calls))]
 #[track_caller]
-pub fn span_lint_and_sugg<T: LintContext>(
+pub fn span_lint_and_sugg<T: ExpectAttrContext>(
     cx: &T,
     lint: &'static Lint,
     sp: Span,
@@ -0,0 +1,522 @@
+//! Expression patterns written in a Rust-like syntax, e.g. `$x.lock().unwrap()`, which can be
+//! matched against HIR expressions.
+//!
+//! See [`ExprPattern`] for the supported syntax.
+
+use crate::hir_utils::SpanlessEq;
+use crate::paths::{PathNS, lookup_path_str};
+use rustc_ast::{BorrowKind, LitKind, Mutability};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::{BinOpKind, Expr, ExprKind, HirId, QPath, TyKind, UnOp};
+use rustc_lexer::{FrontmatterAllowed, LiteralKind, TokenKind, tokenize};
+use rustc_lint::LateContext;
+use rustc_span::Symbol;
+
+/// A parsed expression pattern.
+///
+/// Patterns are written like Rust expressions, with metavariables written as `$name` which match
+/// any expression. A metavariable used multiple times has to match equal expressions each time,
+/// while `$_` matches any expression without binding it.
+///
+/// Supported are paths, literals, tuples, calls, method calls, field accesses, indexing, references
+/// as well as unary and binary operators. A path matches if it's written the same way, e.g.
+/// `Mutex::new` matches both `Mutex::new` and `std::sync::Mutex::new`, or resolves to the same
+/// item as the pattern. Generic arguments are ignored.
+#[derive(Debug)]
+pub struct ExprPattern {
+    root: PatExpr,
+    metavars: Vec<String>,
+}
+
+#[derive(Debug)]
+enum PatExpr {
+    /// `$name`, or `$_` which is `None`.
+    MetaVar(Option<String>),
+    Path(Vec<String>),
+    Lit(PatLit),
+    Tup(Vec<PatExpr>),
+    Call(Box<PatExpr>, Vec<PatExpr>),
+    MethodCall(Box<PatExpr>, String, Vec<PatExpr>),
+    Field(Box<PatExpr>, String),
+    Index(Box<PatExpr>, Box<PatExpr>),
+    AddrOf(Mutability, Box<PatExpr>),
+    Unary(UnOp, Box<PatExpr>),
+    Binary(BinOpKind, Box<PatExpr>, Box<PatExpr>),
+}
+
+#[derive(Debug)]
+enum PatLit {
+    Bool(bool),
+    Int(u128),
+    Float(String),
+    Str(String),
+    Char(char),
+}
+
+/// Binary operators ordered so that operators which are a prefix of another come after it.
+const BIN_OPS: &[(&str, BinOpKind, u8)] = &[
+    ("||", BinOpKind::Or, 1),
+    ("&&", BinOpKind::And, 2),
+    ("==", BinOpKind::Eq, 3),
+    ("!=", BinOpKind::Ne, 3),
+    ("<=", BinOpKind::Le, 3),
+    (">=", BinOpKind::Ge, 3),
+    ("<<", BinOpKind::Shl, 7),
+    (">>", BinOpKind::Shr, 7),
+    ("<", BinOpKind::Lt, 3),
+    (">", BinOpKind::Gt, 3),
+    ("|", BinOpKind::BitOr, 4),
+    ("^", BinOpKind::BitXor, 5),
+    ("&", BinOpKind::BitAnd, 6),
+    ("+", BinOpKind::Add, 8),
+    ("-", BinOpKind::Sub, 8),
+    ("*", BinOpKind::Mul, 9),
+    ("/", BinOpKind::Div, 9),
+    ("%", BinOpKind::Rem, 9),
+];
+
+impl ExprPattern {
+    /// Parses a pattern, returning a description of the problem if it's invalid.
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(src, FrontmatterAllowed::No)
+                .scan(0, |pos, token| {
+                    let start = *pos;
+                    *pos += token.len as usize;
+                    Some((token.kind, &src[start..*pos], start))
+                })
+                .filter(|(kind, ..)| {
+                    !matches!(
+                        kind,
+                        TokenKind::Whitespace | TokenKind::LineComment { .. } | TokenKind::BlockComment { .. }
+                    )
+                })
+                .collect(),
+            pos: 0,
+            metavars: Vec::new(),
+        };
+        let root = parser.parse_expr(0)?;
+        if let Some(&(_, text, _)) = parser.tokens.get(parser.pos) {
+            return Err(format!("unexpected `{text}`"));
+        }
+        Ok(Self {
+            root,
+            metavars: parser.metavars,
+        })
+    }
+
+    /// Gets the names of the metavariables used in the pattern, excluding `$_`.
+    pub fn metavars(&self) -> impl Iterator<Item = &str> {
+        self.metavars.iter().map(String::as_str)
+    }
+
+    /// Matches the pattern against an expression, returning the expression each metavariable
+    /// matched.
+    pub fn matches<'tcx>(
+        &self,
+        cx: &LateContext<'tcx>,
+        expr: &'tcx Expr<'tcx>,
+    ) -> Option<Vec<(&str, &'tcx Expr<'tcx>)>> {
+        let mut bindings = Vec::new();
+        matches_expr(cx, &self.root, expr, &mut bindings).then_some(bindings)
+    }
+}
+
+/// Replaces the metavariables (`$name`) in a template using the given function. Returns `None` if
+/// the function returns `None` for any of them.
+pub fn fill_template<'a>(template: &'a str, mut f: impl FnMut(&'a str) -> Option<String>) -> Option<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            out.push('$');
+        } else {
+            out.push_str(&f(&rest[..len])?);
+            rest = &rest[len..];
+        }
+    }
+    out.push_str(rest);
+    Some(out)
+}
+
+struct Parser<'a> {
+    /// The kind, text and position of each token.
+    tokens: Vec<(TokenKind, &'a str, usize)>,
+    pos: usize,
+    metavars: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<(TokenKind, &'a str)> {
+        self.tokens.get(self.pos).map(|&(kind, text, _)| (kind, text))
+    }
+
+    fn peek_text(&self) -> &'a str {
+        self.peek().map_or("end of pattern", |(_, text)| text)
+    }
+
+    /// Checks if the next tokens are the given punctuation without any whitespace in between.
+    fn check(&self, punct: &str) -> bool {
+        let Some(&(_, _, start)) = self.tokens.get(self.pos) else {
+            return false;
+        };
+        punct.char_indices().all(|(i, c)| {
+            self.tokens
+                .get(self.pos + i)
+                .is_some_and(|&(_, text, pos)| pos == start + i && text.len() == 1 && text.starts_with(c))
+        })
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = self.check(punct);
+        if found {
+            self.pos += punct.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(format!("expected `{punct}`, found `{}`", self.peek_text()))
+        }
+    }
+
+    fn ident(&mut self) -> Result<&'a str, String> {
+        match self.peek() {
+            Some((TokenKind::Ident, text)) => {
+                self.pos += 1;
+                Ok(text)
+            },
+            _ => Err(format!("expected an identifier, found `{}`", self.peek_text())),
+        }
+    }
+
+    fn parse_expr(&mut self, min_prec: u8) -> Result<PatExpr, String> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(&(op, kind, prec)) = BIN_OPS.iter().find(|(op, ..)| self.check(op))
+            && prec >= min_prec
+        {
+            self.pos += op.len();
+            let rhs = self.parse_expr(prec + 1)?;
+            lhs = PatExpr::Binary(kind, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<PatExpr, String> {
+        let op = if self.eat("!") {
+            UnOp::Not
+        } else if self.eat("-") {
+            UnOp::Neg
+        } else if self.eat("*") {
+            UnOp::Deref
+        } else if self.eat("&") {
+            let mutbl = if self.peek() == Some((TokenKind::Ident, "mut")) {
+                self.pos += 1;
+                Mutability::Mut
+            } else {
+                Mutability::Not
+            };
+            return Ok(PatExpr::AddrOf(mutbl, Box::new(self.parse_unary()?)));
+        } else {
+            return self.parse_postfix();
+        };
+        Ok(PatExpr::Unary(op, Box::new(self.parse_unary()?)))
+    }
+
+    fn parse_postfix(&mut self) -> Result<PatExpr, String> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            if self.eat(".") {
+                if let Some((TokenKind::Literal { .. }, text)) = self.peek()
+                    && text.bytes().all(|b| b.is_ascii_digit())
+                {
+                    self.pos += 1;
+                    expr = PatExpr::Field(Box::new(expr), text.into());
+                    continue;
+                }
+                let name = self.ident()?;
+                if self.eat("::") {
+                    self.skip_generic_args()?;
+                }
+                expr = if self.eat("(") {
+                    PatExpr::MethodCall(Box::new(expr), name.into(), self.parse_args(")")?)
+                } else {
+                    PatExpr::Field(Box::new(expr), name.into())
+                };
+            } else if self.eat("(") {
+                expr = PatExpr::Call(Box::new(expr), self.parse_args(")")?);
+            } else if self.eat("[") {
+                let index = self.parse_expr(0)?;
+                self.expect("]")?;
+                expr = PatExpr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<PatExpr, String> {
+        match self.peek() {
+            Some((TokenKind::Dollar, _)) => {
+                self.pos += 1;
+                let name = self.ident()?;
+                if name == "_" {
+                    return Ok(PatExpr::MetaVar(None));
+                }
+                if !self.metavars.iter().any(|var| var == name) {
+                    self.metavars.push(name.into());
+                }
+                Ok(PatExpr::MetaVar(Some(name.into())))
+            },
+            Some((TokenKind::Ident, "true")) => {
+                self.pos += 1;
+                Ok(PatExpr::Lit(PatLit::Bool(true)))
+            },
+            Some((TokenKind::Ident, "false")) => {
+                self.pos += 1;
+                Ok(PatExpr::Lit(PatLit::Bool(false)))
+            },
+            Some((TokenKind::Ident, _)) => {
+                let mut segments = vec![self.ident()?.to_owned()];
+                while self.eat("::") {
+                    if self.check("<") {
+                        self.skip_generic_args()?;
+                    } else {
+                        segments.push(self.ident()?.into());
+                    }
+                }
+                Ok(PatExpr::Path(segments))
+            },
+            Some((TokenKind::Literal { kind, suffix_start }, text)) => {
+                self.pos += 1;
+                parse_lit(kind, &text[..suffix_start as usize])
+                    .map(PatExpr::Lit)
+                    .ok_or_else(|| format!("invalid literal `{text}`"))
+            },
+            Some((TokenKind::OpenParen, _)) => {
+                self.pos += 1;
+                if self.eat(")") {
+                    return Ok(PatExpr::Tup(Vec::new()));
+                }
+                let expr = self.parse_expr(0)?;
+                if self.eat(")") {
+                    return Ok(expr);
+                }
+                self.expect(",")?;
+                let mut exprs = self.parse_args(")")?;
+                exprs.insert(0, expr);
+                Ok(PatExpr::Tup(exprs))
+            },
+            _ => Err(format!("expected an expression, found `{}`", self.peek_text())),
+        }
+    }
+
+    /// Parses comma separated expressions up to the closing delimiter.
+    fn parse_args(&mut self, close: &str) -> Result<Vec<PatExpr>, String> {
+        let mut args = Vec::new();
+        while !self.eat(close) {
+            args.push(self.parse_expr(0)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(args)
+    }
+
+    /// Skips generic arguments starting at the opening `<`.
+    fn skip_generic_args(&mut self) -> Result<(), String> {
+        self.expect("<")?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.peek() {
+                Some((TokenKind::Lt, _)) => depth += 1,
+                Some((TokenKind::Gt, _)) => depth -= 1,
+                Some(_) => {},
+                None => return Err("unclosed generic arguments".into()),
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+}
+
+fn parse_lit(kind: LiteralKind, text: &str) -> Option<PatLit> {
+    match kind {
+        LiteralKind::Int { .. } => {
+            let text = text.replace('_', "");
+            let (radix, digits) = match text.get(..2) {
+                Some("0x") => (16, &text[2..]),
+                Some("0o") => (8, &text[2..]),
+                Some("0b") => (2, &text[2..]),
+                _ => (10, &*text),
+            };
+            u128::from_str_radix(digits, radix).ok().map(PatLit::Int)
+        },
+        LiteralKind::Float { .. } => Some(PatLit::Float(text.replace('_', ""))),
+        LiteralKind::Str { terminated: true } => unescape(&text[1..text.len() - 1]).map(PatLit::Str),
+        LiteralKind::RawStr { n_hashes: Some(n) } => {
+            let n = usize::from(n);
+            Some(PatLit::Str(text[2 + n..text.len() - 1 - n].into()))
+        },
+        LiteralKind::Char { terminated: true } => {
+            let text = unescape(&text[1..text.len() - 1])?;
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(PatLit::Char(c)),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            c @ ('\\' | '\'' | '"') => c,
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let end = rest.find('}')?;
+                let c = char::from_u32(u32::from_str_radix(&rest[..end].replace('_', ""), 16).ok()?)?;
+                chars = rest[end + 1..].chars();
+                c
+            },
+            _ => return None,
+        });
+    }
+    Some(out)
+}
+
+fn matches_expr<'p, 'tcx>(
+    cx: &LateContext<'tcx>,
+    pat: &'p PatExpr,
+    mut expr: &'tcx Expr<'tcx>,
+    bindings: &mut Vec<(&'p str, &'tcx Expr<'tcx>)>,
+) -> bool {
+    while let ExprKind::DropTemps(inner) = expr.kind {
+        expr = inner;
+    }
+    match (pat, expr.kind) {
+        (PatExpr::MetaVar(None), _) => true,
+        (PatExpr::MetaVar(Some(name)), _) => {
+            if let Some(&(_, bound)) = bindings.iter().find(|(bound, _)| bound == name) {
+                SpanlessEq::new(cx).eq_expr(bound, expr)
+            } else {
+                bindings.push((name, expr));
+                true
+            }
+        },
+        (PatExpr::Path(segments), ExprKind::Path(ref qpath)) => matches_path(cx, segments, qpath, expr.hir_id),
+        (PatExpr::Lit(lit), ExprKind::Lit(hir_lit)) => matches_lit(lit, hir_lit.node),
+        (PatExpr::Tup(pats), ExprKind::Tup(exprs)) => matches_exprs(cx, pats, exprs, bindings),
+        (PatExpr::Call(func, args), ExprKind::Call(hir_func, hir_args)) => {
+            matches_expr(cx, func, hir_func, bindings) && matches_exprs(cx, args, hir_args, bindings)
+        },
+        (PatExpr::MethodCall(recv, name, args), ExprKind::MethodCall(segment, hir_recv, hir_args, _)) => {
+            segment.ident.name.as_str() == name
+                && matches_expr(cx, recv, hir_recv, bindings)
+                && matches_exprs(cx, args, hir_args, bindings)
+        },
+        (PatExpr::Field(base, name), ExprKind::Field(hir_base, ident)) => {
+            ident.name.as_str() == name && matches_expr(cx, base, hir_base, bindings)
+        },
+        (PatExpr::Index(base, index), ExprKind::Index(hir_base, hir_index, _)) => {
+            matches_expr(cx, base, hir_base, bindings) && matches_expr(cx, index, hir_index, bindings)
+        },
+        (PatExpr::AddrOf(mutbl, inner), ExprKind::AddrOf(BorrowKind::Ref, hir_mutbl, hir_inner)) => {
+            *mutbl == hir_mutbl && matches_expr(cx, inner, hir_inner, bindings)
+        },
+        (PatExpr::Unary(op, inner), ExprKind::Unary(hir_op, hir_inner)) => {
+            *op == hir_op && matches_expr(cx, inner, hir_inner, bindings)
+        },
+        (PatExpr::Binary(op, lhs, rhs), ExprKind::Binary(hir_op, hir_lhs, hir_rhs)) => {
+            *op == hir_op.node && matches_expr(cx, lhs, hir_lhs, bindings) && matches_expr(cx, rhs, hir_rhs, bindings)
+        },
+        _ => false,
+    }
+}
+
+fn matches_exprs<'p, 'tcx>(
+    cx: &LateContext<'tcx>,
+    pats: &'p [PatExpr],
+    exprs: &'tcx [Expr<'tcx>],
+    bindings: &mut Vec<(&'p str, &'tcx Expr<'tcx>)>,
+) -> bool {
+    pats.len() == exprs.len()
+        && pats
+            .iter()
+            .zip(exprs)
+            .all(|(pat, expr)| matches_expr(cx, pat, expr, bindings))
+}
+
+fn matches_lit(lit: &PatLit, hir_lit: LitKind) -> bool {
+    match (lit, hir_lit) {
+        (PatLit::Bool(l), LitKind::Bool(r)) => *l == r,
+        (PatLit::Int(l), LitKind::Int(r, _)) => *l == r.get(),
+        (PatLit::Float(l), LitKind::Float(r, _)) => *l == r.as_str().replace('_', ""),
+        (PatLit::Str(l), LitKind::Str(r, _)) => l == r.as_str(),
+        (PatLit::Char(l), LitKind::Char(r)) => *l == r,
+        _ => false,
+    }
+}
+
+fn matches_path(cx: &LateContext<'_>, segments: &[String], qpath: &QPath<'_>, hir_id: HirId) -> bool {
+    let written: Vec<Symbol> = match *qpath {
+        QPath::Resolved(_, path) => path.segments.iter().map(|segment| segment.ident.name).collect(),
+        QPath::TypeRelative(ty, segment) => match ty.kind {
+            TyKind::Path(QPath::Resolved(None, path)) => path
+                .segments
+                .iter()
+                .chain([segment])
+                .map(|segment| segment.ident.name)
+                .collect(),
+            _ => vec![segment.ident.name],
+        },
+    };
+    if written.last().map(Symbol::as_str) != segments.last().map(String::as_str) {
+        return false;
+    }
+    if written.len() >= segments.len()
+        && written[written.len() - segments.len()..]
+            .iter()
+            .zip(segments)
+            .all(|(written, segment)| written.as_str() == segment)
+    {
+        return true;
+    }
+
+    let Some(def_id) = cx.qpath_res(qpath, hir_id).opt_def_id() else {
+        return false;
+    };
+    let path = match segments.split_first() {
+        Some((root, rest)) if root == "crate" => {
+            let mut path = cx.tcx.crate_name(LOCAL_CRATE).to_string();
+            for segment in rest {
+                path.push_str("::");
+                path.push_str(segment);
+            }
+            path
+        },
+        _ => segments.join("::"),
+    };
+    lookup_path_str(cx.tcx, PathNS::Value, &path).contains(&def_id)
+}
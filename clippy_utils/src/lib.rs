@@ -57,6 +57,7 @@ pub mod comparisons;
 pub mod consts;
 pub mod diagnostics;
 pub mod eager_or_lazy;
+pub mod expr_pattern;
 pub mod higher;
 mod hir_utils;
 pub mod macros;
@@ -72,6 +73,7 @@ pub mod sugg;
 pub mod sym;
 pub mod ty;
 pub mod usage;
+pub mod value_range;
 pub mod visitors;
 
 pub use self::attrs::*;
@@ -1625,7 +1627,9 @@ pub fn fulfill_or_allowed(cx: &LateContext<'_>, lint: &'static Lint, ids: impl I
     let mut suppress_lint = false;
 
     for id in ids {
-        let LevelAndSource { level, lint_id, .. } = cx.tcx.lint_level_at_node(lint, id);
+        let level_and_source = cx.tcx.lint_level_at_node(lint, id);
+        diagnostics::record_allow(|| level_and_source);
+        let LevelAndSource { level, lint_id, .. } = level_and_source;
         if let Some(expectation) = lint_id {
             cx.fulfill_expectation(expectation);
         }
@@ -1647,7 +1651,10 @@ pub fn fulfill_or_allowed(cx: &LateContext<'_>, lint: &'static Lint, ids: impl I
 /// make sure to use `span_lint_hir` functions to emit the lint. This ensures that
 /// expectations at the checked nodes will be fulfilled.
 pub fn is_lint_allowed(cx: &LateContext<'_>, lint: &'static Lint, id: HirId) -> bool {
-    cx.tcx.lint_level_at_node(lint, id).level == Level::Allow
+    let level = cx.tcx.lint_level_at_node(lint, id);
+    // The lint may have been skipped because of the `#[allow]` attribute, so it counts as used.
+    diagnostics::record_allow(|| level);
+    level.level == Level::Allow
 }
 
 pub fn strip_pat_refs<'hir>(mut pat: &'hir Pat<'hir>) -> &'hir Pat<'hir> {
@@ -11,6 +11,8 @@ pub use possible_borrower::PossibleBorrowerMap;
 
 mod possible_origin;
 
+pub mod taint;
+
 mod transitive_relation;
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,309 @@
+use crate::paths::{PathNS, lookup_path_str};
+use rustc_hir::LangItem;
+use rustc_hir::def_id::{DefId, DefIdSet};
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::mir::{
+    self, BasicBlock, Body, BorrowKind, Local, Location, Operand, RawPtrKind, Rvalue, StatementKind, TerminatorKind,
+};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::Span;
+
+/// The functions building a string out of their arguments, in addition to `String + &str` and
+/// `String += &str`.
+const STRING_BUILDERS: &[&str] = &[
+    "alloc::fmt::format",
+    "alloc::string::String::insert",
+    "alloc::string::String::insert_str",
+    "alloc::string::String::push",
+    "alloc::string::String::push_str",
+    "slice::concat",
+    "slice::join",
+];
+
+/// The functions which introduce, remove and consume untrusted values.
+///
+/// * A call to a source taints its return value and everything passed to it by mutable reference
+///   (e.g. the buffer given to `Stdin::read_line`).
+/// * A call to a sanitizer returns an untainted value no matter what is passed to it.
+/// * A sink is a function which must not be called with a string built from a tainted value. The
+///   string is its first argument after the receiver, if any.
+#[derive(Default)]
+pub struct TaintConfig {
+    sources: DefIdSet,
+    sanitizers: DefIdSet,
+    sinks: DefIdSet,
+    builders: DefIdSet,
+}
+
+impl TaintConfig {
+    /// Resolves the function paths of each kind, ignoring any that can't be found.
+    pub fn new(tcx: TyCtxt<'_>, sources: &[String], sanitizers: &[String], sinks: &[String]) -> Self {
+        let resolve = |paths: &[String]| {
+            paths
+                .iter()
+                .flat_map(|path| lookup_path_str(tcx, PathNS::Value, path))
+                .collect()
+        };
+        Self {
+            sources: resolve(sources),
+            sanitizers: resolve(sanitizers),
+            sinks: resolve(sinks),
+            builders: STRING_BUILDERS
+                .iter()
+                .flat_map(|path| lookup_path_str(tcx, PathNS::Value, path))
+                .collect(),
+        }
+    }
+
+    /// Whether any sink was found. Nothing can be reported otherwise.
+    pub fn has_sinks(&self) -> bool {
+        !self.sinks.is_empty()
+    }
+}
+
+/// A call to a sink with a tainted argument.
+pub struct TaintedSink {
+    /// The span of the call.
+    pub span: Span,
+    /// The function being called.
+    pub callee: DefId,
+    /// The span of the source call the tainted argument was derived from.
+    pub source: Span,
+}
+
+/// Finds all calls to a sink with a string built by formatting or concatenating a value derived
+/// from a source, e.g. using `format!`, `String::push_str` or `+`.
+///
+/// The analysis is flow-insensitive: a local is considered tainted if a tainted value is assigned
+/// to it anywhere in the body. The result of any other call is only tainted if it's a string (or
+/// contains one, e.g. `Result<String, _>`) and one of the arguments is tainted. Passing a tainted
+/// value as a query parameter or to the constructor of a client thus doesn't taint the query.
+pub fn find_tainted_sinks<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, config: &TaintConfig) -> Vec<TaintedSink> {
+    let calls_sink = body.basic_blocks.iter().any(|data| {
+        if let TerminatorKind::Call { func, .. } = &data.terminator().kind
+            && let Some((callee, _)) = func.const_fn_def()
+        {
+            config.sinks.contains(&callee)
+        } else {
+            false
+        }
+    });
+    if !calls_sink {
+        return Vec::new();
+    }
+
+    let taint = TaintedLocals::new(tcx, body, config);
+    body.basic_blocks
+        .iter()
+        .filter_map(|data| {
+            let terminator = data.terminator();
+            if let TerminatorKind::Call { func, args, .. } = &terminator.kind
+                && let Some((callee, _)) = func.const_fn_def()
+                && config.sinks.contains(&callee)
+            {
+                let query = if tcx.opt_associated_item(callee).is_some_and(|item| item.is_method()) {
+                    args.get(1)
+                } else {
+                    args.first()
+                }?;
+                let taint = taint.operand_taint(&query.node)?;
+                taint.built.then_some(TaintedSink {
+                    span: terminator.source_info.span,
+                    callee,
+                    source: taint.source,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+struct Taint {
+    /// The span of the source call.
+    source: Span,
+    /// Whether the value is a string built by formatting or concatenating the tainted value.
+    built: bool,
+}
+
+/// The locals of a body holding a value derived from a source.
+struct TaintedLocals {
+    taints: IndexVec<Local, Option<Taint>>,
+    /// Pairs of a mutable reference and the local it borrows from. Writing a tainted value through
+    /// the reference also taints the borrowed local.
+    mut_borrows: Vec<(Local, Local)>,
+    changed: bool,
+}
+
+impl TaintedLocals {
+    fn new<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, config: &TaintConfig) -> Self {
+        let mut this = Self {
+            taints: IndexVec::from_elem(None, &body.local_decls),
+            mut_borrows: Vec::new(),
+            changed: true,
+        };
+        for data in body.basic_blocks.iter() {
+            for stmt in &data.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                    && let Some(borrowed) = mut_borrowed_local(body, rvalue)
+                {
+                    this.mut_borrows.push((place.local, borrowed));
+                }
+            }
+        }
+
+        // Every iteration can only add tainted locals, so this will eventually stop.
+        while this.changed {
+            this.changed = false;
+            for (bb, data) in body.basic_blocks.iter_enumerated() {
+                for (idx, stmt) in data.statements.iter().enumerate() {
+                    if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind {
+                        let location = Location {
+                            block: bb,
+                            statement_index: idx,
+                        };
+                        if let Some(taint) = this.rvalue_taint(rvalue, location) {
+                            this.taint(place.local, taint);
+                        }
+                    }
+                }
+                this.visit_call(tcx, body, bb, config);
+            }
+        }
+        this
+    }
+
+    fn visit_call<'tcx>(&mut self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>, bb: BasicBlock, config: &TaintConfig) {
+        let terminator = body.basic_blocks[bb].terminator();
+        let TerminatorKind::Call {
+            func,
+            args,
+            destination,
+            ..
+        } = &terminator.kind
+        else {
+            return;
+        };
+        let callee = func.const_fn_def();
+        let arg_taint = || {
+            // Prefer the taint of a built string, it's what gets reported.
+            args.iter()
+                .filter_map(|arg| self.operand_taint(&arg.node))
+                .max_by_key(|taint| taint.built)
+        };
+        let taint = match callee {
+            Some((callee, _)) if config.sources.contains(&callee) => Taint {
+                source: terminator.source_info.span,
+                built: false,
+            },
+            Some((callee, _)) if config.sanitizers.contains(&callee) => return,
+            Some((callee, generic_args))
+                if config.builders.contains(&callee) || is_string_concat(tcx, callee, generic_args) =>
+            {
+                let Some(taint) = arg_taint() else { return };
+                Taint { built: true, ..taint }
+            },
+            _ => {
+                if let Some(taint) = arg_taint()
+                    && contains_string(tcx, destination.ty(body, tcx).ty)
+                {
+                    self.taint(destination.local, taint);
+                }
+                return;
+            },
+        };
+
+        self.taint(destination.local, taint);
+        for arg in args {
+            if let Some(place) = arg.node.place()
+                && let ty::Ref(_, _, ty::Mutability::Mut) | ty::RawPtr(_, ty::Mutability::Mut) =
+                    place.ty(body, tcx).ty.kind()
+            {
+                self.taint(place.local, taint);
+            }
+        }
+    }
+
+    fn taint(&mut self, local: Local, taint: Taint) {
+        if self.taints[local].is_none_or(|old| taint.built && !old.built) {
+            self.taints[local] = Some(taint);
+            self.changed = true;
+            let borrowed = self
+                .mut_borrows
+                .iter()
+                .filter(|&&(reference, _)| reference == local)
+                .map(|&(_, borrowed)| borrowed)
+                .collect::<Vec<_>>();
+            for borrowed in borrowed {
+                self.taint(borrowed, taint);
+            }
+        }
+    }
+
+    fn operand_taint(&self, operand: &Operand<'_>) -> Option<Taint> {
+        operand.place().and_then(|place| self.taints[place.local])
+    }
+
+    fn rvalue_taint(&self, rvalue: &Rvalue<'_>, location: Location) -> Option<Taint> {
+        let mut v = FindTaint {
+            taints: &self.taints,
+            found: None,
+        };
+        v.visit_rvalue(rvalue, location);
+        v.found
+    }
+}
+
+/// Checks if the callee is `<String as Add<&str>>::add` or `<String as
+/// AddAssign<&str>>::add_assign`.
+fn is_string_concat<'tcx>(tcx: TyCtxt<'tcx>, callee: DefId, generic_args: ty::GenericArgsRef<'tcx>) -> bool {
+    let lang_items = tcx.lang_items();
+    tcx.trait_of_assoc(callee).is_some_and(|trait_id| {
+        Some(trait_id) == lang_items.add_trait() || Some(trait_id) == lang_items.add_assign_trait()
+    }) && matches!(generic_args.type_at(0).kind(), ty::Adt(adt, _) if tcx.is_lang_item(adt.did(), LangItem::String))
+}
+
+/// Checks if the type is a string, a character or the arguments of `format!`, or contains one of
+/// them.
+fn contains_string<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    ty.walk().filter_map(ty::GenericArg::as_type).any(|ty| match ty.kind() {
+        ty::Str | ty::Char => true,
+        ty::Adt(adt, _) => {
+            tcx.is_lang_item(adt.did(), LangItem::String)
+                || tcx.is_lang_item(adt.did(), LangItem::FormatArguments)
+                || tcx.is_lang_item(adt.did(), LangItem::FormatArgument)
+        },
+        _ => false,
+    })
+}
+
+/// Gets the local which is mutably borrowed by the rvalue, if any.
+fn mut_borrowed_local(body: &Body<'_>, rvalue: &Rvalue<'_>) -> Option<Local> {
+    match rvalue {
+        Rvalue::Ref(_, BorrowKind::Mut { .. }, place) | Rvalue::RawPtr(RawPtrKind::Mut, place) => Some(place.local),
+        // Moving or reborrowing an existing mutable reference.
+        Rvalue::Use(Operand::Move(place) | Operand::Copy(place)) | Rvalue::CopyForDeref(place)
+            if body.local_decls[place.local].ty.is_mutable_ptr() =>
+        {
+            Some(place.local)
+        },
+        _ => None,
+    }
+}
+
+struct FindTaint<'a> {
+    taints: &'a IndexVec<Local, Option<Taint>>,
+    found: Option<Taint>,
+}
+
+impl Visitor<'_> for FindTaint<'_> {
+    fn visit_local(&mut self, local: Local, _: mir::visit::PlaceContext, _: Location) {
+        if let Some(taint) = self.taints[local]
+            && self.found.is_none_or(|found| taint.built && !found.built)
+        {
+            self.found = Some(taint);
+        }
+    }
+}
@@ -196,6 +196,7 @@ generate! {
     is_empty,
     is_err,
     is_file,
+    is_finished,
     is_none,
     is_none_or,
     is_ok,
@@ -0,0 +1,979 @@
+//! A simple value range analysis for integer expressions.
+//!
+//! The range of an expression is computed from constants, the bounds of its type, a few operators
+//! and the conditions which are known to hold where the expression is evaluated. These are the
+//! conditions of enclosing `if`s, `while`s and `&&` chains, the patterns of enclosing `match` arms
+//! and `if let`s, the ranges of enclosing `for` loops and preceding early exits such as
+//! `assert!(cond)` or `if cond { return; }`.
+//!
+//! Facts about a local are only used if the local is never mutated, so they hold wherever the
+//! local is used.
+//!
+//! This is a syntactic analysis of the HIR, not a dataflow analysis of the MIR. Only the conditions
+//! enclosing the expression or preceding it in an enclosing block are used, so e.g. a bound which
+//! only holds after a loop, or which is established by assigning to a mutable local, is not known.
+
+use crate::consts::{ConstEvalCtxt, FullInt};
+use crate::res::MaybeResPath;
+use crate::usage::mutated_variables;
+use crate::{find_binding_init, higher, is_never_expr, sym};
+use rustc_ast::ast::RangeLimits;
+use rustc_hir::{
+    BinOpKind, Expr, ExprKind, HirId, HirIdSet, Mutability, Node, Pat, PatExpr, PatKind, RangeEnd, StmtKind, UnOp,
+};
+use rustc_lint::LateContext;
+use rustc_middle::ty::{self, Ty};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::rc::Rc;
+
+/// How deep the range of an expression is computed before it's approximated by its type.
+const MAX_DEPTH: u32 = 8;
+
+thread_local! {
+    /// The locals mutated in the body the last range was computed in, as lints usually compute
+    /// the ranges of many expressions of the same body.
+    static MUTATED: RefCell<Option<(HirId, Rc<Option<HirIdSet>>)>> = const { RefCell::new(None) };
+}
+
+/// Gets the locals mutated in the body, or `None` if they can't be determined.
+fn mutated_in_body<'tcx>(cx: &LateContext<'tcx>, body: &'tcx Expr<'tcx>) -> Rc<Option<HirIdSet>> {
+    MUTATED.with_borrow_mut(|cache| match cache {
+        Some((id, mutated)) if *id == body.hir_id => mutated.clone(),
+        _ => {
+            let mutated = Rc::new(mutated_variables(body, cx));
+            *cache = Some((body.hir_id, mutated.clone()));
+            mutated
+        },
+    })
+}
+
+/// An inclusive range of integer values. A bound of `None` is unknown, i.e. it's outside of the
+/// values an `i128` can hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntRange {
+    pub min: Option<i128>,
+    pub max: Option<i128>,
+}
+
+impl IntRange {
+    /// A range containing a single value.
+    pub fn exact(value: i128) -> Self {
+        Self {
+            min: Some(value),
+            max: Some(value),
+        }
+    }
+
+    /// The range of all values of an integer type.
+    pub fn of_ty(cx: &LateContext<'_>, ty: Ty<'_>) -> Option<Self> {
+        Some(Self::of_bits(int_bit_width(cx, ty)?, ty.is_signed()))
+    }
+
+    /// The range of all values of an integer type with the given bit width and signedness.
+    fn of_bits(bits: u64, signed: bool) -> Self {
+        if signed {
+            let max = i128::MAX >> (128 - bits);
+            Self {
+                min: Some(-max - 1).filter(|_| bits < 128),
+                max: Some(max).filter(|_| bits < 128),
+            }
+        } else {
+            Self {
+                min: Some(0),
+                max: (u128::MAX >> (128 - bits)).try_into().ok(),
+            }
+        }
+    }
+
+    /// Checks if every value in the range is a value of the integer type `ty`.
+    pub fn fits_in(self, cx: &LateContext<'_>, ty: Ty<'_>) -> bool {
+        Self::of_ty(cx, ty).is_some_and(|ty_range| self.fits_in_range(ty_range))
+    }
+
+    /// Checks if every value in the range is in the range of an integer type.
+    fn fits_in_range(self, ty_range: Self) -> bool {
+        let fits_min = match (self.min, ty_range.min) {
+            (Some(min), Some(ty_min)) => min >= ty_min,
+            (_, None) => true,
+            (None, Some(_)) => false,
+        };
+        let fits_max = match (self.max, ty_range.max) {
+            (Some(max), Some(ty_max)) => max <= ty_max,
+            (_, None) => self.max.is_some(),
+            (None, Some(_)) => false,
+        };
+        fits_min && fits_max
+    }
+
+    pub fn is_non_negative(self) -> bool {
+        self.min.is_some_and(|min| min >= 0)
+    }
+
+    /// The values in both ranges. An empty intersection is returned as `self`, as the code in
+    /// question is then unreachable.
+    #[must_use]
+    pub fn intersect(self, other: Self) -> Self {
+        let min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return self;
+        }
+        Self { min, max }
+    }
+
+    /// The smallest range containing both ranges.
+    #[must_use]
+    pub fn hull(self, other: Self) -> Self {
+        Self {
+            min: self.min.zip(other.min).map(|(a, b)| a.min(b)),
+            max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            min: self.min.zip(other.min).and_then(|(a, b)| a.checked_add(b)),
+            max: self.max.zip(other.max).and_then(|(a, b)| a.checked_add(b)),
+        }
+    }
+
+    fn neg(self) -> Self {
+        Self {
+            min: self.max.and_then(i128::checked_neg),
+            max: self.min.and_then(i128::checked_neg),
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let (Some(a_min), Some(a_max), Some(b_min), Some(b_max)) = (self.min, self.max, other.min, other.max) else {
+            return Self { min: None, max: None };
+        };
+        let products = [
+            a_min.checked_mul(b_min),
+            a_min.checked_mul(b_max),
+            a_max.checked_mul(b_min),
+            a_max.checked_mul(b_max),
+        ];
+        if products.contains(&None) {
+            return Self { min: None, max: None };
+        }
+        Self {
+            min: products.iter().flatten().min().copied(),
+            max: products.iter().flatten().max().copied(),
+        }
+    }
+
+    fn contains(self, value: i128) -> bool {
+        self.min.is_none_or(|min| min <= value) && self.max.is_none_or(|max| value <= max)
+    }
+
+    /// The range of a binary operation on integers of the given bit width. Returns `None` if
+    /// nothing more than the range of the type is known.
+    ///
+    /// A shift by the bit width or more either panics or is done by the shift modulo the bit
+    /// width, so only shifts known to be less than the bit width narrow the range.
+    fn binary(op: BinOpKind, lhs: Self, rhs: Self, bits: u64) -> Option<Self> {
+        match op {
+            BinOpKind::Add => Some(lhs.add(rhs)),
+            BinOpKind::Sub => Some(lhs.add(rhs.neg())),
+            BinOpKind::Mul => Some(lhs.mul(rhs)),
+            BinOpKind::Div if rhs.min.is_some_and(|min| min > 0) && lhs.is_non_negative() => Some(Self {
+                min: lhs.min.zip(rhs.max).map(|(a, b)| a / b),
+                max: lhs.max.zip(rhs.min).map(|(a, b)| a / b),
+            }),
+            BinOpKind::Rem if rhs.min.is_some_and(|min| min > 0) => {
+                let max = rhs.max? - 1;
+                Some(if lhs.is_non_negative() {
+                    Self {
+                        min: Some(0),
+                        max: Some(lhs.max.map_or(max, |lhs| lhs.min(max))),
+                    }
+                } else {
+                    Self {
+                        min: Some(-max),
+                        max: Some(max),
+                    }
+                })
+            },
+            BinOpKind::BitAnd if lhs.is_non_negative() || rhs.is_non_negative() => {
+                let max = [lhs, rhs]
+                    .iter()
+                    .filter(|range| range.is_non_negative())
+                    .filter_map(|range| range.max)
+                    .min()?;
+                Some(Self {
+                    min: Some(0),
+                    max: Some(max),
+                })
+            },
+            BinOpKind::Shr if lhs.is_non_negative() => {
+                let shift = rhs
+                    .min
+                    .zip(rhs.max)
+                    .filter(|&(min, max)| min >= 0 && max < i128::from(bits))
+                    .and_then(|(min, _)| u32::try_from(min).ok())
+                    .unwrap_or(0);
+                Some(Self {
+                    min: Some(0),
+                    max: lhs.max.map(|max| max >> shift),
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Gets the bit width of an integer type.
+fn int_bit_width(cx: &LateContext<'_>, ty: Ty<'_>) -> Option<u64> {
+    let width = cx.tcx.sess.target.pointer_width;
+    match *ty.kind() {
+        ty::Int(ty) => ty.normalize(width).bit_width(),
+        ty::Uint(ty) => ty.normalize(width).bit_width(),
+        _ => None,
+    }
+}
+
+/// Computes the range of values an integer expression can have. Returns `None` if the expression
+/// is not an integer.
+pub fn expr_int_range(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<IntRange> {
+    RangeCtxt::new(cx, expr.hir_id).range(expr)
+}
+
+/// Computes the range of values an integer expression can have, if it's narrowed by a guard on
+/// one of the locals it uses. Returns `None` if the expression is not an integer or if no guard
+/// applies, so constants and plain initializers are left to the callers.
+pub fn guarded_int_range(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<IntRange> {
+    let ctxt = RangeCtxt::new(cx, expr.hir_id);
+    ctxt.range(expr).filter(|_| ctxt.guarded.get())
+}
+
+/// Checks if the integer operation `lhs op rhs` can neither overflow nor divide by zero because of
+/// a guard on one of its operands.
+pub fn is_overflow_free(cx: &LateContext<'_>, op: BinOpKind, lhs: &Expr<'_>, rhs: &Expr<'_>) -> bool {
+    let ctxt = RangeCtxt::new(cx, lhs.hir_id);
+    let ty = cx.typeck_results().expr_ty(lhs).peel_refs();
+    let (Some(lhs_range), Some(rhs_range)) = (ctxt.range(lhs), ctxt.range(rhs)) else {
+        return false;
+    };
+    if !ctxt.guarded.get() {
+        return false;
+    }
+    match op {
+        BinOpKind::Add => lhs_range.add(rhs_range).fits_in(cx, ty),
+        BinOpKind::Sub => lhs_range.add(rhs_range.neg()).fits_in(cx, ty),
+        BinOpKind::Mul => lhs_range.mul(rhs_range).fits_in(cx, ty),
+        // Only `MIN / -1` and `MIN % -1` overflow.
+        BinOpKind::Div | BinOpKind::Rem => {
+            !rhs_range.contains(0)
+                && (!rhs_range.contains(-1) || IntRange::of_ty(cx, ty).is_some_and(|ty| ty.min != lhs_range.min))
+        },
+        _ => false,
+    }
+}
+
+/// Checks if `index` is less than the length of `indexed`, or not greater if `or_equal` is set.
+///
+/// This holds if `index` is bounded by the length of an array, or if `indexed` is a local and
+/// `index < indexed.len()` is known to hold.
+pub fn is_below_len(cx: &LateContext<'_>, index: &Expr<'_>, indexed: &Expr<'_>, or_equal: bool) -> bool {
+    let ctxt = RangeCtxt::new(cx, index.hir_id);
+    if let ty::Array(_, len) = cx.typeck_results().expr_ty(indexed).peel_refs().kind()
+        && let Some(len) = len.try_to_target_usize(cx.tcx)
+        && let Some(max) = ctxt.range(index).and_then(|range| range.max)
+        && ctxt.guarded.get()
+        && let len = i128::from(len)
+        && (max < len || (or_equal && max == len))
+    {
+        return true;
+    }
+    if let Some(id) = index.res_local_id()
+        && let Some(slice) = peel_refs_and_derefs(indexed).res_local_id()
+        && ctxt.is_immutable(id)
+        && ctxt.is_immutable(slice)
+    {
+        return ctxt
+            .conds
+            .iter()
+            .any(|&(cond, positive)| implies_below_len(cond, positive, id, slice, or_equal))
+            || ctxt.loops.iter().any(|for_loop| {
+                for_loop.pat.hir_id == id
+                    && higher::Range::hir(cx, for_loop.arg).is_some_and(|range| {
+                        range.limits == RangeLimits::HalfOpen && range.end.is_some_and(|end| is_len_of(end, slice))
+                    })
+            });
+    }
+    false
+}
+
+fn full_int_to_i128(value: FullInt) -> Option<i128> {
+    match value {
+        FullInt::S(value) => Some(value),
+        FullInt::U(value) => value.try_into().ok(),
+    }
+}
+
+/// Checks if `cond` evaluating to `positive` implies `id < slice.len()`, or `id <= slice.len()`
+/// if `or_equal` is set.
+fn implies_below_len(cond: &Expr<'_>, positive: bool, id: HirId, slice: HirId, or_equal: bool) -> bool {
+    match cond.kind {
+        ExprKind::DropTemps(cond) => implies_below_len(cond, positive, id, slice, or_equal),
+        ExprKind::Unary(UnOp::Not, cond) => implies_below_len(cond, !positive, id, slice, or_equal),
+        ExprKind::Binary(op, lhs, rhs)
+            if (op.node == BinOpKind::And && positive) || (op.node == BinOpKind::Or && !positive) =>
+        {
+            implies_below_len(lhs, positive, id, slice, or_equal)
+                || implies_below_len(rhs, positive, id, slice, or_equal)
+        },
+        ExprKind::Binary(op, lhs, rhs) => {
+            let Some(op) = (if positive {
+                Some(op.node)
+            } else {
+                negate_comparison(op.node)
+            }) else {
+                return false;
+            };
+            let op = if lhs.res_local_id() == Some(id) && is_len_of(rhs, slice) {
+                op
+            } else if rhs.res_local_id() == Some(id) && is_len_of(lhs, slice) {
+                swap_comparison(op)
+            } else {
+                return false;
+            };
+            op == BinOpKind::Lt || (or_equal && matches!(op, BinOpKind::Le | BinOpKind::Eq))
+        },
+        _ => false,
+    }
+}
+
+fn peel_refs_and_derefs<'a>(mut expr: &'a Expr<'a>) -> &'a Expr<'a> {
+    while let ExprKind::AddrOf(_, _, e) | ExprKind::Unary(UnOp::Deref, e) = expr.kind {
+        expr = e;
+    }
+    expr
+}
+
+/// Checks if `expr` is `slice.len()`.
+fn is_len_of(expr: &Expr<'_>, slice: HirId) -> bool {
+    if let ExprKind::MethodCall(method, receiver, [], _) = expr.kind
+        && method.ident.name == sym::len
+    {
+        peel_refs_and_derefs(receiver).res_local_id() == Some(slice)
+    } else {
+        false
+    }
+}
+
+/// Negates a comparison, e.g. `<` to `>=`. Returns `None` for other operators.
+fn negate_comparison(op: BinOpKind) -> Option<BinOpKind> {
+    Some(match op {
+        BinOpKind::Lt => BinOpKind::Ge,
+        BinOpKind::Le => BinOpKind::Gt,
+        BinOpKind::Gt => BinOpKind::Le,
+        BinOpKind::Ge => BinOpKind::Lt,
+        BinOpKind::Eq => BinOpKind::Ne,
+        BinOpKind::Ne => BinOpKind::Eq,
+        _ => return None,
+    })
+}
+
+/// Swaps the operands of a comparison, e.g. `a < b` to `b > a`.
+fn swap_comparison(op: BinOpKind) -> BinOpKind {
+    match op {
+        BinOpKind::Lt => BinOpKind::Gt,
+        BinOpKind::Le => BinOpKind::Ge,
+        BinOpKind::Gt => BinOpKind::Lt,
+        BinOpKind::Ge => BinOpKind::Le,
+        op => op,
+    }
+}
+
+/// The conditions known to hold at an expression.
+struct RangeCtxt<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    /// Conditions which evaluated to the given value.
+    conds: Vec<(&'tcx Expr<'tcx>, bool)>,
+    /// Patterns matched by a local.
+    pats: Vec<(HirId, &'tcx Pat<'tcx>)>,
+    /// Enclosing `for` loops.
+    loops: Vec<higher::ForLoop<'tcx>>,
+    /// The outermost body containing the expression.
+    body: Option<&'tcx Expr<'tcx>>,
+    mutated: OnceCell<Rc<Option<HirIdSet>>>,
+    depth: Cell<u32>,
+    /// Whether a computed range depends on a guard.
+    guarded: Cell<bool>,
+}
+
+impl<'a, 'tcx> RangeCtxt<'a, 'tcx> {
+    fn new(cx: &'a LateContext<'tcx>, hir_id: HirId) -> Self {
+        let mut ctxt = Self {
+            cx,
+            conds: Vec::new(),
+            pats: Vec::new(),
+            loops: Vec::new(),
+            body: None,
+            mutated: OnceCell::new(),
+            depth: Cell::new(0),
+            guarded: Cell::new(false),
+        };
+        let mut visited = vec![hir_id];
+        let mut child = hir_id;
+        for (id, node) in cx.tcx.hir_parent_iter(hir_id) {
+            match node {
+                Node::Expr(e) => ctxt.add_expr(e, child, &visited),
+                Node::Block(block) => {
+                    let stmts = match block.stmts.iter().position(|stmt| stmt.hir_id == child) {
+                        Some(i) => &block.stmts[..i],
+                        None => block.stmts,
+                    };
+                    for stmt in stmts {
+                        if let StmtKind::Expr(e) | StmtKind::Semi(e) = stmt.kind
+                            && let ExprKind::If(cond, then, None) = e.kind
+                            && is_never_expr(cx, then).is_some()
+                        {
+                            ctxt.conds.push((cond, false));
+                        }
+                    }
+                },
+                Node::Arm(arm) => {
+                    if let Node::Expr(Expr {
+                        kind: ExprKind::Match(scrutinee, ..),
+                        ..
+                    }) = cx.tcx.parent_hir_node(id)
+                        && let Some(local) = scrutinee.res_local_id()
+                    {
+                        ctxt.pats.push((local, arm.pat));
+                    }
+                    if let Some(guard) = arm.guard
+                        && guard.hir_id != child
+                    {
+                        ctxt.conds.push((guard, true));
+                    }
+                },
+                Node::Item(_) | Node::ImplItem(_) | Node::TraitItem(_) | Node::AnonConst(_) | Node::ConstBlock(_) => {
+                    break;
+                },
+                _ => {},
+            }
+            visited.push(id);
+            child = id;
+        }
+        let owner = cx.tcx.hir_enclosing_body_owner(hir_id);
+        ctxt.body = cx
+            .tcx
+            .typeck_root_def_id(owner.to_def_id())
+            .as_local()
+            .and_then(|root| cx.tcx.hir_maybe_body_owned_by(root))
+            .map(|body| body.value);
+        ctxt
+    }
+
+    fn add_expr(&mut self, e: &'tcx Expr<'tcx>, child: HirId, visited: &[HirId]) {
+        match e.kind {
+            ExprKind::If(cond, then, els) => {
+                if then.hir_id == child {
+                    self.conds.push((cond, true));
+                } else if els.is_some_and(|els| els.hir_id == child) {
+                    self.conds.push((cond, false));
+                }
+            },
+            ExprKind::Binary(op, lhs, rhs) if rhs.hir_id == child => match op.node {
+                BinOpKind::And => self.conds.push((lhs, true)),
+                BinOpKind::Or => self.conds.push((lhs, false)),
+                _ => {},
+            },
+            _ => {
+                if let Some(for_loop) = higher::ForLoop::hir(e)
+                    && visited.contains(&for_loop.body.hir_id)
+                {
+                    self.loops.push(for_loop);
+                }
+            },
+        }
+    }
+
+    /// Checks if the local can't change its value after it's initialized.
+    fn is_immutable(&self, id: HirId) -> bool {
+        if let Node::Pat(Pat {
+            kind: PatKind::Binding(mode, ..),
+            ..
+        }) = self.cx.tcx.hir_node(id)
+            && mode.1.is_not()
+            && !matches!(
+                self.cx.typeck_results().node_type(id).kind(),
+                ty::Ref(_, _, Mutability::Mut)
+            )
+        {
+            return true;
+        }
+        self.body.is_some_and(|body| {
+            self.mutated
+                .get_or_init(|| mutated_in_body(self.cx, body))
+                .as_ref()
+                .as_ref()
+                .is_some_and(|mutated| !mutated.contains(&id))
+        })
+    }
+
+    fn range(&self, expr: &Expr<'_>) -> Option<IntRange> {
+        let ty = self.cx.typeck_results().expr_ty(expr);
+        let ty_range = IntRange::of_ty(self.cx, ty)?;
+        if let Some(value) = ConstEvalCtxt::new(self.cx)
+            .eval(expr)
+            .and_then(|c| c.int_value(self.cx.tcx, ty))
+            .and_then(full_int_to_i128)
+        {
+            return Some(IntRange::exact(value));
+        }
+        if self.depth.get() >= MAX_DEPTH {
+            return Some(ty_range);
+        }
+        self.depth.set(self.depth.get() + 1);
+        let range = self.expr_range(expr, ty_range);
+        self.depth.set(self.depth.get() - 1);
+        Some(range.map_or(
+            ty_range,
+            |range| {
+                if range.fits_in(self.cx, ty) { range } else { ty_range }
+            },
+        ))
+    }
+
+    fn expr_range(&self, expr: &Expr<'_>, ty_range: IntRange) -> Option<IntRange> {
+        match expr.kind {
+            ExprKind::Path(_) => self.local_range(expr.res_local_id()?, ty_range),
+            ExprKind::Cast(inner, _) | ExprKind::DropTemps(inner) => self.range(inner),
+            ExprKind::Block(block, _) => self.range(block.expr?),
+            ExprKind::Unary(UnOp::Neg, inner) => Some(self.range(inner)?.neg()),
+            ExprKind::Binary(op, lhs, rhs) => {
+                let bits = int_bit_width(self.cx, self.cx.typeck_results().expr_ty(lhs))?;
+                IntRange::binary(op.node, self.range(lhs)?, self.range(rhs)?, bits)
+            },
+            ExprKind::MethodCall(method, receiver, args, _) => {
+                let receiver = self.range(receiver)?;
+                let args = args.iter().map(|arg| self.range(arg)).collect::<Option<Vec<_>>>()?;
+                let min_of = |a: Option<i128>, b: Option<i128>| a.zip(b).map(|(a, b)| a.min(b));
+                let max_of = |a: Option<i128>, b: Option<i128>| a.zip(b).map(|(a, b)| a.max(b));
+                match (method.ident.name, args.as_slice()) {
+                    (sym::min, [other]) => Some(IntRange {
+                        min: min_of(receiver.min, other.min),
+                        max: min_of(receiver.max, other.max).or(receiver.max.or(other.max)),
+                    }),
+                    (sym::max, [other]) => Some(IntRange {
+                        min: max_of(receiver.min, other.min).or(receiver.min.or(other.min)),
+                        max: max_of(receiver.max, other.max),
+                    }),
+                    (sym::clamp, [min, max]) => Some(IntRange {
+                        min: min.min,
+                        max: max.max,
+                    }),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn local_range(&self, id: HirId, ty_range: IntRange) -> Option<IntRange> {
+        if !self.is_immutable(id) {
+            return None;
+        }
+        let mut range = ty_range;
+        if let Some(init) = find_binding_init(self.cx, id)
+            && let Some(init) = self.range(init)
+        {
+            range = range.intersect(init);
+        }
+        let mut guards = Vec::new();
+        if let Node::Pat(pat) = self.cx.tcx.hir_node(id) {
+            guards.push(self.pat_range(pat));
+        }
+        for &(local, pat) in &self.pats {
+            if local == id {
+                guards.push(self.pat_range(pat));
+            }
+        }
+        for &(cond, positive) in &self.conds {
+            guards.push(self.cond_range(cond, positive, id));
+        }
+        for for_loop in &self.loops {
+            if for_loop.pat.hir_id == id
+                && let Some(loop_range) = higher::Range::hir(self.cx, for_loop.arg)
+            {
+                let end_offset = i128::from(loop_range.limits == RangeLimits::HalfOpen);
+                guards.push(Some(IntRange {
+                    min: loop_range.start.and_then(|start| self.range(start)?.min),
+                    max: loop_range
+                        .end
+                        .and_then(|end| self.range(end)?.max?.checked_sub(end_offset)),
+                }));
+            }
+        }
+        for guard in guards.into_iter().flatten() {
+            range = range.intersect(guard);
+            self.guarded.set(true);
+        }
+        Some(range)
+    }
+
+    /// The range of the values matched by a pattern.
+    fn pat_range(&self, pat: &Pat<'_>) -> Option<IntRange> {
+        match pat.kind {
+            PatKind::Expr(expr) => Some(IntRange::exact(self.pat_expr_value(expr)?)),
+            PatKind::Range(start, end, range_end) => {
+                let ty_range = IntRange::of_ty(self.cx, self.cx.typeck_results().pat_ty(pat))?;
+                let max = match (end, range_end) {
+                    (Some(end), RangeEnd::Included) => Some(self.pat_expr_value(end)?),
+                    (Some(end), RangeEnd::Excluded) => Some(self.pat_expr_value(end)? - 1),
+                    (None, _) => ty_range.max,
+                };
+                let min = match start {
+                    Some(start) => Some(self.pat_expr_value(start)?),
+                    None => ty_range.min,
+                };
+                Some(IntRange { min, max })
+            },
+            PatKind::Or(pats) => pats
+                .iter()
+                .map(|pat| self.pat_range(pat))
+                .reduce(|a, b| a.zip(b).map(|(a, b)| a.hull(b)))?,
+            PatKind::Binding(.., Some(pat)) => self.pat_range(pat),
+            _ => None,
+        }
+    }
+
+    fn pat_expr_value(&self, expr: &PatExpr<'_>) -> Option<i128> {
+        let ty = self.cx.typeck_results().node_type(expr.hir_id);
+        ConstEvalCtxt::new(self.cx)
+            .eval_pat_expr(expr)?
+            .int_value(self.cx.tcx, ty)
+            .and_then(full_int_to_i128)
+    }
+
+    /// The range of the local `id` implied by `cond` evaluating to `positive`.
+    fn cond_range(&self, cond: &Expr<'_>, positive: bool, id: HirId) -> Option<IntRange> {
+        match cond.kind {
+            ExprKind::DropTemps(cond) => self.cond_range(cond, positive, id),
+            ExprKind::Unary(UnOp::Not, cond) => self.cond_range(cond, !positive, id),
+            ExprKind::Binary(op, lhs, rhs)
+                if (op.node == BinOpKind::And && positive) || (op.node == BinOpKind::Or && !positive) =>
+            {
+                match (self.cond_range(lhs, positive, id), self.cond_range(rhs, positive, id)) {
+                    (Some(a), Some(b)) => Some(a.intersect(b)),
+                    (a, b) => a.or(b),
+                }
+            },
+            ExprKind::Binary(op, lhs, rhs) => {
+                let op = if positive { op.node } else { negate_comparison(op.node)? };
+                let (op, other) = if lhs.res_local_id() == Some(id) {
+                    (op, rhs)
+                } else if rhs.res_local_id() == Some(id) {
+                    (swap_comparison(op), lhs)
+                } else {
+                    return None;
+                };
+                let other = self.range(other)?;
+                Some(match op {
+                    BinOpKind::Lt => IntRange {
+                        min: None,
+                        max: other.max.and_then(|max| max.checked_sub(1)),
+                    },
+                    BinOpKind::Le => IntRange {
+                        min: None,
+                        max: other.max,
+                    },
+                    BinOpKind::Gt => IntRange {
+                        min: other.min.and_then(|min| min.checked_add(1)),
+                        max: None,
+                    },
+                    BinOpKind::Ge => IntRange {
+                        min: other.min,
+                        max: None,
+                    },
+                    BinOpKind::Eq => other,
+                    _ => return None,
+                })
+            },
+            ExprKind::Let(let_expr) if positive && let_expr.init.res_local_id() == Some(id) => {
+                self.pat_range(let_expr.pat)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn range(min: i128, max: i128) -> IntRange {
+        IntRange {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    const UNKNOWN: IntRange = IntRange { min: None, max: None };
+
+    #[test]
+    fn of_bits() {
+        assert_eq!(IntRange::of_bits(8, false), range(0, 255));
+        assert_eq!(IntRange::of_bits(8, true), range(-128, 127));
+        assert_eq!(IntRange::of_bits(64, false), range(0, u64::MAX.into()));
+        assert_eq!(IntRange::of_bits(64, true), range(i64::MIN.into(), i64::MAX.into()));
+        assert_eq!(
+            IntRange::of_bits(128, false),
+            IntRange {
+                min: Some(0),
+                max: None
+            }
+        );
+        assert_eq!(IntRange::of_bits(128, true), UNKNOWN);
+    }
+
+    #[test]
+    fn fits_in_range() {
+        let u8_range = IntRange::of_bits(8, false);
+        assert!(range(0, 255).fits_in_range(u8_range));
+        assert!(!range(0, 256).fits_in_range(u8_range));
+        assert!(!range(-1, 0).fits_in_range(u8_range));
+        assert!(!UNKNOWN.fits_in_range(u8_range));
+
+        let u128_range = IntRange::of_bits(128, false);
+        assert!(range(0, i128::MAX).fits_in_range(u128_range));
+        assert!(!range(-1, 0).fits_in_range(u128_range));
+        assert!(
+            !IntRange {
+                min: Some(0),
+                max: None
+            }
+            .fits_in_range(u128_range)
+        );
+
+        let i128_range = IntRange::of_bits(128, true);
+        assert!(range(i128::MIN, i128::MAX).fits_in_range(i128_range));
+        assert!(!UNKNOWN.fits_in_range(i128_range));
+    }
+
+    #[test]
+    fn intersect() {
+        assert_eq!(range(0, 10).intersect(range(5, 20)), range(5, 10));
+        assert_eq!(range(0, 10).intersect(range(2, 3)), range(2, 3));
+        assert_eq!(
+            IntRange {
+                min: None,
+                max: Some(10)
+            }
+            .intersect(IntRange {
+                min: Some(0),
+                max: None
+            }),
+            range(0, 10)
+        );
+        assert_eq!(UNKNOWN.intersect(UNKNOWN), UNKNOWN);
+        // An empty intersection keeps the first range.
+        assert_eq!(range(0, 10).intersect(range(11, 20)), range(0, 10));
+    }
+
+    #[test]
+    fn add() {
+        assert_eq!(range(1, 2).add(range(3, 4)), range(4, 6));
+        assert_eq!(
+            range(0, 5).add(range(i128::MIN, i128::MAX - 5)),
+            range(i128::MIN, i128::MAX)
+        );
+        assert_eq!(
+            range(0, i128::MAX).add(range(0, 1)),
+            IntRange {
+                min: Some(0),
+                max: None
+            }
+        );
+        assert_eq!(
+            range(i128::MIN, 0).add(range(-1, 0)),
+            IntRange {
+                min: None,
+                max: Some(0)
+            }
+        );
+        assert_eq!(
+            range(i128::MIN, 0).neg(),
+            IntRange {
+                min: Some(0),
+                max: None
+            }
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Sub, range(0, 10), range(0, 5), 32),
+            Some(range(-5, 10))
+        );
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(range(2, 3).mul(range(4, 5)), range(8, 15));
+        assert_eq!(range(-2, 3).mul(range(4, 5)), range(-10, 15));
+        assert_eq!(range(-2, 3).mul(range(-5, -4)), range(-15, 10));
+        assert_eq!(range(0, i128::MAX).mul(range(0, 2)), UNKNOWN);
+        assert_eq!(range(i128::MIN, 0).mul(range(-1, 0)), UNKNOWN);
+        assert_eq!(
+            IntRange {
+                min: Some(0),
+                max: None
+            }
+            .mul(range(0, 0)),
+            UNKNOWN
+        );
+    }
+
+    #[test]
+    fn div() {
+        assert_eq!(
+            IntRange::binary(BinOpKind::Div, range(10, 20), range(2, 5), 32),
+            Some(range(2, 10))
+        );
+        assert_eq!(IntRange::binary(BinOpKind::Div, range(10, 20), range(-5, -2), 32), None);
+        assert_eq!(IntRange::binary(BinOpKind::Div, range(10, 20), range(0, 5), 32), None);
+        assert_eq!(IntRange::binary(BinOpKind::Div, range(-10, 20), range(2, 5), 32), None);
+    }
+
+    #[test]
+    fn rem() {
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(0, 100), range(1, 10), 32),
+            Some(range(0, 9))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(0, 5), range(10, 10), 32),
+            Some(range(0, 5))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(-100, 100), range(10, 10), 32),
+            Some(range(-9, 9))
+        );
+        assert_eq!(
+            IntRange::binary(
+                BinOpKind::Rem,
+                IntRange {
+                    min: Some(0),
+                    max: None
+                },
+                range(1, 10),
+                128
+            ),
+            Some(range(0, 9))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(i128::MIN, 0), range(1, i128::MAX), 128),
+            Some(range(-(i128::MAX - 1), i128::MAX - 1))
+        );
+        // Negative divisors and divisors which can be zero.
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(0, 100), range(-10, -1), 32),
+            None
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Rem, range(0, 100), range(-10, 10), 32),
+            None
+        );
+        assert_eq!(IntRange::binary(BinOpKind::Rem, range(0, 100), range(0, 10), 32), None);
+        assert_eq!(
+            IntRange::binary(
+                BinOpKind::Rem,
+                range(0, 100),
+                IntRange {
+                    min: Some(1),
+                    max: None
+                },
+                128
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn bit_and() {
+        assert_eq!(
+            IntRange::binary(BinOpKind::BitAnd, range(0, 15), range(0, 255), 32),
+            Some(range(0, 15))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::BitAnd, range(-100, 100), range(0, 15), 32),
+            Some(range(0, 15))
+        );
+        assert_eq!(
+            IntRange::binary(
+                BinOpKind::BitAnd,
+                IntRange {
+                    min: Some(0),
+                    max: None
+                },
+                range(0, 7),
+                128
+            ),
+            Some(range(0, 7))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::BitAnd, range(-1, 15), range(-1, 255), 32),
+            None
+        );
+        assert_eq!(
+            IntRange::binary(
+                BinOpKind::BitAnd,
+                IntRange {
+                    min: Some(0),
+                    max: None
+                },
+                range(-1, 7),
+                128
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn shr() {
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), range(2, 2), 8),
+            Some(range(0, 63))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), range(2, 7), 8),
+            Some(range(0, 63))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, i128::MAX), range(127, 127), 128),
+            Some(range(0, 0))
+        );
+        // Shifts by the bit width or more wrap around when they don't panic.
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), range(8, 8), 8),
+            Some(range(0, 255))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), range(2, 8), 8),
+            Some(range(0, 255))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, i128::MAX), range(128, 128), 128),
+            Some(range(0, i128::MAX))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), range(-1, 2), 8),
+            Some(range(0, 255))
+        );
+        assert_eq!(
+            IntRange::binary(BinOpKind::Shr, range(0, 255), UNKNOWN, 8),
+            Some(range(0, 255))
+        );
+        assert_eq!(IntRange::binary(BinOpKind::Shr, range(-1, 255), range(2, 2), 8), None);
+    }
+}
@@ -80,6 +80,29 @@ pub(crate) enum Commands {
         #[clap(short, long, default_value_t = 100)]
         number: usize,
     },
+    /// Create a lintcheck crates TOML file containing the N crates.io crates with the most
+    /// dependents in a `Cargo.lock` or a local registry index
+    Dependents {
+        /// Output TOML file name
+        output: PathBuf,
+        /// Number of crates to include
+        #[clap(short, long, default_value_t = 100)]
+        number: usize,
+        /// Rank the packages of this `Cargo.lock` by how many packages in it transitively depend on
+        /// them
+        #[clap(
+            long,
+            value_name = "PATH",
+            conflicts_with = "registry_index",
+            required_unless_present = "registry_index"
+        )]
+        lockfile: Option<PathBuf>,
+        /// Rank the crates of a local registry index, e.g.
+        /// `~/.cargo/registry/index/index.crates.io-<hash>`, by how many crates directly depend on
+        /// them
+        #[clap(long, value_name = "PATH")]
+        registry_index: Option<PathBuf>,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,280 @@
+//! Builds a crates TOML from the crates that are depended on the most in a local dependency graph,
+//! either a `Cargo.lock` or a local copy of a registry index
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Deserialize, Debug)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockPackage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LockPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// A single line of a registry index file
+#[derive(Deserialize, Debug)]
+struct IndexEntry {
+    name: String,
+    vers: String,
+    #[serde(default)]
+    deps: Vec<IndexDependency>,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct IndexDependency {
+    name: String,
+    /// Set when the dependency is renamed, `name` is then the name it is renamed to
+    package: Option<String>,
+    kind: Option<String>,
+}
+
+/// A crate from crates.io along with the number of crates that depend on it
+struct Ranked {
+    name: String,
+    version: String,
+    dependents: usize,
+}
+
+pub(crate) fn fetch(
+    output: PathBuf,
+    number: usize,
+    lockfile: Option<&Path>,
+    registry_index: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let mut crates = match (lockfile, registry_index) {
+        (Some(lockfile), None) => rank_lockfile(lockfile)?,
+        (None, Some(index)) => rank_registry_index(index)?,
+        _ => return Err("exactly one of `--lockfile` and `--registry-index` must be passed".into()),
+    };
+
+    crates.sort_by(|a, b| b.dependents.cmp(&a.dependents).then_with(|| a.name.cmp(&b.name)));
+    crates.truncate(number);
+
+    let mut out = "[crates]\n".to_string();
+    for Ranked {
+        name,
+        version,
+        dependents,
+    } in crates
+    {
+        writeln!(out, "# {dependents} dependents").unwrap();
+        writeln!(out, "{name} = {{ name = '{name}', version = '{version}' }}").unwrap();
+    }
+    fs::write(output, out)?;
+
+    Ok(())
+}
+
+/// Ranks the crates.io packages of a `Cargo.lock` by the number of packages in the lockfile that
+/// transitively depend on them
+fn rank_lockfile(path: &Path) -> Result<Vec<Ranked>, Box<dyn Error>> {
+    let lockfile: Lockfile = toml::from_str(&fs::read_to_string(path)?)?;
+    Ok(rank_lock_packages(&lockfile.package))
+}
+
+fn rank_lock_packages(packages: &[LockPackage]) -> Vec<Ranked> {
+    // Dependencies are written as `name` if there's only one version of the package in the lockfile,
+    // and `name version` or `name version (source)` otherwise
+    let resolve = |dependency: &str| {
+        let mut parts = dependency.split(' ');
+        let name = parts.next()?;
+        let version = parts.next();
+        packages
+            .iter()
+            .position(|package| package.name == name && version.is_none_or(|version| package.version == version))
+    };
+
+    let mut reverse_dependencies = vec![Vec::new(); packages.len()];
+    for (dependent, package) in packages.iter().enumerate() {
+        for dependency in package.dependencies.iter().filter_map(|dependency| resolve(dependency)) {
+            reverse_dependencies[dependency].push(dependent);
+        }
+    }
+
+    let mut ranked: Vec<Ranked> = Vec::new();
+    for (index, package) in packages.iter().enumerate() {
+        if !package.source.as_deref().is_some_and(is_crates_io) {
+            continue;
+        }
+
+        let mut seen = HashSet::new();
+        let mut stack = vec![index];
+        while let Some(current) = stack.pop() {
+            for &dependent in &reverse_dependencies[current] {
+                if seen.insert(dependent) {
+                    stack.push(dependent);
+                }
+            }
+        }
+
+        // Only keep the newest version of packages that appear multiple times, along with its own
+        // number of dependents
+        if let Some(existing) = ranked.iter_mut().find(|r| r.name == package.name) {
+            if version_key(&package.version) > version_key(&existing.version) {
+                existing.version.clone_from(&package.version);
+                existing.dependents = seen.len();
+            }
+        } else {
+            ranked.push(Ranked {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                dependents: seen.len(),
+            });
+        }
+    }
+
+    ranked
+}
+
+/// Ranks the crates in a registry index by the number of crates in the index whose latest version
+/// directly depends on them
+///
+/// Both plain index checkouts and the `.cache` directory cargo keeps for the sparse crates.io index
+/// in `~/.cargo/registry/index/` are supported.
+fn rank_registry_index(path: &Path) -> Result<Vec<Ranked>, Box<dyn Error>> {
+    let mut latest: HashMap<String, IndexEntry> = HashMap::new();
+
+    for entry in WalkDir::new(path).into_iter().filter_entry(|e| e.file_name() != ".git") {
+        let entry = entry?;
+        if !entry.file_type().is_file() || entry.file_name() == "config.json" {
+            continue;
+        }
+
+        // Index files contain one JSON object per line, the entries of cache files are separated by
+        // NUL bytes and interspersed with versions and a binary header
+        let contents = fs::read(entry.path())?;
+        for segment in contents.split(|&b| b == b'\n' || b == b'\0') {
+            if !segment.starts_with(b"{") {
+                continue;
+            }
+            let Ok(index_entry) = serde_json::from_slice::<IndexEntry>(segment) else {
+                continue;
+            };
+            if index_entry.yanked {
+                continue;
+            }
+            match latest.get(&index_entry.name) {
+                Some(existing) if version_key(&existing.vers) >= version_key(&index_entry.vers) => {},
+                _ => {
+                    latest.insert(index_entry.name.clone(), index_entry);
+                },
+            }
+        }
+    }
+
+    let mut dependents: HashMap<&str, usize> = HashMap::new();
+    for entry in latest.values() {
+        let dependencies: HashSet<&str> = entry
+            .deps
+            .iter()
+            .filter(|dependency| dependency.kind.as_deref() != Some("dev"))
+            .map(|dependency| dependency.package.as_deref().unwrap_or(&dependency.name))
+            .collect();
+        for dependency in dependencies {
+            *dependents.entry(dependency).or_default() += 1;
+        }
+    }
+
+    Ok(latest
+        .values()
+        .map(|entry| Ranked {
+            name: entry.name.clone(),
+            version: entry.vers.clone(),
+            dependents: dependents.get(entry.name.as_str()).copied().unwrap_or(0),
+        })
+        .collect())
+}
+
+fn is_crates_io(source: &str) -> bool {
+    source == "registry+https://github.com/rust-lang/crates.io-index" || source == "sparse+https://index.crates.io/"
+}
+
+/// Orders versions numerically, with pre-releases ordered before the release they precede
+fn version_key(version: &str) -> (Vec<u64>, bool) {
+    let version = version.split('+').next().unwrap_or(version);
+    let (release, pre) = version.split_once('-').map_or((version, None), |(r, p)| (r, Some(p)));
+    let numbers = release.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+    (numbers, pre.is_none())
+}
+
+#[test]
+fn test_version_key() {
+    assert!(version_key("1.10.0") > version_key("1.9.0"));
+    assert!(version_key("0.10.1") > version_key("0.9.12"));
+    assert!(version_key("2.0.0") > version_key("1.99.99"));
+    assert!(version_key("1.0.0") > version_key("1.0.0-rc.1"));
+    assert!(version_key("1.0.1-alpha") > version_key("1.0.0"));
+    assert_eq!(version_key("1.0.0+build.5"), version_key("1.0.0"));
+}
+
+#[test]
+fn test_rank_lockfile() {
+    let lockfile: Lockfile = toml::from_str(
+        r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["log", "rand 0.9.0", "uses-old-rand"]
+
+[[package]]
+name = "uses-old-rand"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["rand 0.8.5"]
+
+[[package]]
+name = "other"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["rand 0.8.5"]
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["log"]
+
+[[package]]
+name = "rand"
+version = "0.9.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "log"
+version = "0.4.22"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+    )
+    .unwrap();
+
+    let mut ranked = rank_lock_packages(&lockfile.package)
+        .into_iter()
+        .map(|r| (r.name, r.version, r.dependents))
+        .collect::<Vec<_>>();
+    ranked.sort();
+    assert_eq!(
+        ranked,
+        [
+            ("log".to_owned(), "0.4.22".to_owned(), 4),
+            ("other".to_owned(), "1.0.0".to_owned(), 0),
+            // `rand 0.8.5` has three dependents, but only the one of `0.9.0` is counted
+            ("rand".to_owned(), "0.9.0".to_owned(), 1),
+            ("uses-old-rand".to_owned(), "1.0.0".to_owned(), 1),
+        ]
+    );
+}
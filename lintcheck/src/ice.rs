@@ -0,0 +1,465 @@
+//! Reproduction bundles for crates that caused Clippy to ICE.
+//!
+//! When a crate ICEs a bundle is written to `target/lintcheck/ices/<crate>/` containing:
+//!
+//! - `source/`: a copy of the crate sources
+//! - `stderr.txt`: the full stderr of the failing `cargo check`
+//! - `command.sh`: the exact `clippy-driver` invocation that caused the ICE, runnable from the
+//!   bundle
+//! - `minimized.rs`: the triggering file with every top-level item that isn't needed to reproduce
+//!   the ICE removed
+
+use crate::{Crate, target_dir};
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{fmt, fs};
+
+use walkdir::WalkDir;
+
+/// The exit code `clippy-driver` uses for an ICE
+const ICE_EXIT_CODE: i32 = 101;
+
+/// Upper bound on the number of times `clippy-driver` is rerun while minimizing a single file
+const MAX_MINIMIZATION_RUNS: usize = 200;
+
+/// The `clippy-driver` invocation that caused the ICE, as reported by cargo
+struct DriverCommand {
+    program: String,
+    args: Vec<String>,
+}
+
+impl DriverCommand {
+    /// Extracts the failing command from cargo's
+    /// ``process didn't exit successfully: `...` (exit status: 101)`` message
+    fn from_stderr(stderr: &str) -> Option<Self> {
+        let line = stderr
+            .lines()
+            .find(|line| line.contains("process didn't exit successfully: `"))?;
+        let start = line.find('`')? + 1;
+        let end = line.rfind("` (")?;
+        let mut words = split_command_line(line.get(start..end)?).into_iter();
+
+        Some(Self {
+            program: words.next()?,
+            args: words.collect(),
+        })
+    }
+
+    /// The root source file of the crate being compiled, e.g. `src/lib.rs`
+    fn crate_root(&self) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|arg| !arg.starts_with('-') && Path::new(arg).extension().is_some_and(|ext| ext == "rs"))
+            .map(String::as_str)
+    }
+
+    /// Runs the command in `dir` writing any artifacts to `out_dir`, returns `true` if it causes an
+    /// ICE
+    fn ices(&self, dir: &Path, out_dir: &Path, clippy_args: &str) -> bool {
+        let out_dir = out_dir.display().to_string();
+        let mut args = self.args.clone();
+        let mut args_iter = args.iter_mut().peekable();
+        while let Some(arg) = args_iter.next() {
+            if arg == "--out-dir"
+                && let Some(value) = args_iter.peek_mut()
+            {
+                value.clone_from(&out_dir);
+            } else if arg.starts_with("incremental=") {
+                *arg = format!("incremental={out_dir}/incremental");
+            }
+        }
+
+        Command::new(&self.program)
+            .args(args)
+            .current_dir(dir)
+            .env("CLIPPY_ARGS", clippy_args)
+            .env("CLIPPY_DISABLE_DOCS_LINKS", "1")
+            .output()
+            .is_ok_and(|output| output.status.code() == Some(ICE_EXIT_CODE))
+    }
+}
+
+impl fmt::Display for DriverCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&shell_quote(&self.program))?;
+        for arg in &self.args {
+            write!(f, " {}", shell_quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a reproduction bundle for `krate` to `target/lintcheck/ices/<crate>/` and returns its
+/// path
+///
+/// `clippy_args` is the value of `CLIPPY_ARGS` that the crate was checked with.
+pub(crate) fn write_bundle(krate: &Crate, stderr: &str, clippy_args: &str) -> PathBuf {
+    let bundle_dir = PathBuf::from(format!("{}/lintcheck/ices/{}", target_dir(), krate.name));
+    let source_dir = bundle_dir.join("source");
+    let out_dir = bundle_dir.join("out");
+
+    let _ = fs::remove_dir_all(&bundle_dir);
+    fs::create_dir_all(&out_dir).unwrap();
+    copy_sources(&krate.path, &source_dir);
+    fs::write(bundle_dir.join("stderr.txt"), stderr).unwrap();
+
+    let Some(command) = DriverCommand::from_stderr(stderr) else {
+        eprintln!(
+            "Could not find the failing clippy-driver command for {}, the ICE bundle will be incomplete",
+            krate.name
+        );
+        return bundle_dir;
+    };
+
+    fs::write(
+        bundle_dir.join("command.sh"),
+        format!(
+            "#!/bin/sh\n\
+             # Reproduces the ICE in {} {}\n\
+             # Dependencies are read from the lintcheck target directory, so it must not be cleaned\n\
+             cd \"$(dirname \"$0\")/source\" || exit 1\n\
+             export CLIPPY_ARGS={}\n\
+             export CLIPPY_DISABLE_DOCS_LINKS=1\n\
+             {command}\n",
+            krate.name,
+            krate.version,
+            shell_quote(clippy_args),
+        ),
+    )
+    .unwrap();
+
+    if let Some(file) = triggering_file(stderr, &source_dir).or_else(|| command.crate_root().map(PathBuf::from)) {
+        println!("Minimizing {} for the ICE in {}", file.display(), krate.name);
+        if let Some(minimized) = minimize(&command, &source_dir, &out_dir, &file, clippy_args) {
+            fs::write(bundle_dir.join("minimized.rs"), minimized).unwrap();
+        }
+    }
+
+    bundle_dir
+}
+
+/// Copies the crate sources to `dest`, skipping its target directory
+fn copy_sources(src: &Path, dest: &Path) {
+    for entry in WalkDir::new(src)
+        .into_iter()
+        .filter_entry(|e| e.depth() != 1 || e.file_name() != "target")
+    {
+        let entry = entry.unwrap();
+        let dest_path = dest.join(entry.path().strip_prefix(src).unwrap());
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(dest_path).unwrap();
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), dest_path).unwrap();
+        }
+    }
+}
+
+/// Finds the first source file of the crate mentioned in a `path.rs:line` position in the ICE
+/// output, e.g. in the panic message or in a `--> src/lib.rs:1:1` line
+fn triggering_file(stderr: &str, source_dir: &Path) -> Option<PathBuf> {
+    stderr
+        .split(|c: char| c.is_whitespace() || matches!(c, '`' | '(' | ')' | ','))
+        .filter_map(|word| word.split_once(".rs:"))
+        .map(|(path, _)| PathBuf::from(format!("{path}.rs")))
+        .find(|path| path.is_relative() && source_dir.join(path).is_file())
+}
+
+/// Removes the top-level items of `file` one chunk at a time, keeping each removal only if the ICE
+/// still reproduces. Returns the minimized contents if the ICE could be reproduced at all.
+fn minimize(
+    command: &DriverCommand,
+    source_dir: &Path,
+    out_dir: &Path,
+    file: &Path,
+    clippy_args: &str,
+) -> Option<String> {
+    let path = source_dir.join(file);
+    let original = fs::read_to_string(&path).ok()?;
+    let check = |items: &[&str]| {
+        fs::write(&path, items.concat()).unwrap();
+        command.ices(source_dir, out_dir, clippy_args)
+    };
+
+    let mut items = split_items(&original);
+    if !check(&items) {
+        fs::write(&path, &original).unwrap();
+        eprintln!("Could not reproduce the ICE in the bundle, skipping minimization");
+        return None;
+    }
+
+    let mut runs = 1;
+    let mut chunk_size = items.len().div_ceil(2);
+    while chunk_size > 0 && runs < MAX_MINIMIZATION_RUNS {
+        let mut start = 0;
+        while start < items.len() && runs < MAX_MINIMIZATION_RUNS {
+            let end = (start + chunk_size).min(items.len());
+            let candidate: Vec<&str> = items[..start].iter().chain(&items[end..]).copied().collect();
+            runs += 1;
+            if check(&candidate) {
+                items = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk_size /= 2;
+    }
+
+    // Leave the bundle's copy of the file untouched so `command.sh` reproduces the original ICE
+    fs::write(&path, &original).unwrap();
+    Some(items.concat())
+}
+
+/// Splits Rust source code into its top-level items, each including its leading attributes,
+/// comments and whitespace. Concatenating the result gives back `src`.
+///
+/// This is a lexical approximation: an item ends at a `;` or `}` outside of any delimiters, and
+/// strings, chars and comments are skipped so their contents are not mistaken for delimiters.
+fn split_items(src: &str) -> Vec<&str> {
+    let bytes = src.as_bytes();
+    let mut items = Vec::new();
+    let mut item_start = 0;
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = src[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                continue;
+            },
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let mut nesting = 0;
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"/*") {
+                        nesting += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        nesting -= 1;
+                        i += 2;
+                        if nesting == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            },
+            b'"' => {
+                i = skip_string(bytes, i + 1);
+                continue;
+            },
+            b'r' | b'b' if i == 0 || !(bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_') => {
+                let prefix = if bytes[i..].starts_with(b"br") { 2 } else { 1 };
+                let hashes = bytes[i + prefix..].iter().take_while(|&&b| b == b'#').count();
+                if bytes[i..].starts_with(b"b\"") {
+                    i = skip_string(bytes, i + 2);
+                    continue;
+                } else if (bytes[i] == b'r' || prefix == 2) && bytes.get(i + prefix + hashes) == Some(&b'"') {
+                    let closing = [&b"\""[..], &vec![b'#'; hashes]].concat();
+                    let content_start = i + prefix + hashes + 1;
+                    i = bytes[content_start..]
+                        .windows(closing.len())
+                        .position(|window| window == closing)
+                        .map_or(bytes.len(), |pos| content_start + pos + closing.len());
+                    continue;
+                }
+            },
+            b'\'' => {
+                // Distinguish char literals from lifetimes and labels
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    // Skip the escaped character, it may be a quote itself as in `'\''`
+                    i = src
+                        .get(i + 3..)
+                        .and_then(|rest| rest.find('\''))
+                        .map_or(bytes.len(), |end| i + 3 + end);
+                } else if let Some(c) = src[i + 1..].chars().next()
+                    && bytes.get(i + 1 + c.len_utf8()) == Some(&b'\'')
+                {
+                    i += 1 + c.len_utf8();
+                }
+            },
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' => depth = depth.saturating_sub(1),
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    items.push(&src[item_start..=i]);
+                    item_start = i + 1;
+                }
+            },
+            b';' if depth == 0 => {
+                items.push(&src[item_start..=i]);
+                item_start = i + 1;
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+
+    if item_start < src.len() {
+        items.push(&src[item_start..]);
+    }
+    items
+}
+
+/// Returns the index of the byte after the closing quote of a string whose contents start at `i`
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Splits a command line quoted by cargo into words
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            },
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            c => {
+                in_word = true;
+                word.push(c);
+            },
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Quotes `word` for use in a POSIX shell script
+fn shell_quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '=' | '/' | '.' | ',' | ':' | '+' | '@'))
+    {
+        word.to_owned()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+#[test]
+fn test_split_items() {
+    let src = "use std::fmt;\n\n/// Docs\n#[derive(Debug)]\nstruct S {\n    a: [u8; 2],\n}\n\nfn f() {}\n";
+    assert_eq!(
+        split_items(src),
+        [
+            "use std::fmt;",
+            "\n\n/// Docs\n#[derive(Debug)]\nstruct S {\n    a: [u8; 2],\n}",
+            "\n\nfn f() {}",
+            "\n",
+        ]
+    );
+
+    // Delimiters in strings, chars and comments
+    let src = r##"const A: &str = "}{;"; const B: &str = r#"}";"#; // };
+const C: [char; 3] = ['\'', '{', '\u{7d}']; /* } /* ; */ { */ fn f<'a>(x: &'a u8) -> &'a u8 { x }"##;
+    assert_eq!(
+        split_items(src),
+        [
+            r#"const A: &str = "}{;";"#,
+            r##" const B: &str = r#"}";"#;"##,
+            " // };\nconst C: [char; 3] = ['\\'', '{', '\\u{7d}'];",
+            " /* } /* ; */ { */ fn f<'a>(x: &'a u8) -> &'a u8 { x }",
+        ]
+    );
+
+    assert_eq!(
+        split_items(r#"const B: &[u8] = b"\"}"; fn f() {}"#),
+        [r#"const B: &[u8] = b"\"}";"#, " fn f() {}"]
+    );
+    assert!(split_items("").is_empty());
+}
+
+#[test]
+fn test_split_command_line() {
+    assert_eq!(
+        split_command_line(r#"/bin/clippy-driver rustc --crate-name 'my crate' --cfg 'feature="std"' a\ b ''"#),
+        [
+            "/bin/clippy-driver",
+            "rustc",
+            "--crate-name",
+            "my crate",
+            "--cfg",
+            r#"feature="std""#,
+            "a b",
+            "",
+        ]
+    );
+    assert!(split_command_line("  ").is_empty());
+}
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("--crate-type=lib"), "--crate-type=lib");
+    assert_eq!(shell_quote("src/lib.rs"), "src/lib.rs");
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote("a b"), "'a b'");
+    assert_eq!(shell_quote(r#"feature="std""#), r#"'feature="std"'"#);
+    assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    assert_eq!(shell_quote("$HOME"), "'$HOME'");
+
+    for word in ["", "a b", "it's", "'\\'", "-Dwarnings"] {
+        assert_eq!(split_command_line(&shell_quote(word)), [word]);
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_minimize() {
+    let dir = std::env::temp_dir().join(format!("lintcheck-minimize-{}", std::process::id()));
+    let source_dir = dir.join("source");
+    let out_dir = dir.join("out");
+    fs::create_dir_all(&source_dir).unwrap();
+    fs::create_dir_all(&out_dir).unwrap();
+    let original = "fn a() {}\nfn boom() {}\nstruct S;\nfn c() {}\nfn bang() {}\n";
+    fs::write(source_dir.join("lib.rs"), original).unwrap();
+
+    // ICEs as long as both `boom` and `bang` are present
+    let command = DriverCommand {
+        program: "sh".into(),
+        args: vec![
+            "-c".into(),
+            "grep -q boom lib.rs && grep -q bang lib.rs && exit 101; exit 0".into(),
+        ],
+    };
+    let minimized = minimize(&command, &source_dir, &out_dir, Path::new("lib.rs"), "");
+    assert_eq!(minimized.as_deref(), Some("\nfn boom() {}\nfn bang() {}"));
+    assert_eq!(fs::read_to_string(source_dir.join("lib.rs")).unwrap(), original);
+
+    // The ICE doesn't reproduce
+    let command = DriverCommand {
+        program: "true".into(),
+        args: Vec::new(),
+    };
+    assert_eq!(minimize(&command, &source_dir, &out_dir, Path::new("lib.rs"), ""), None);
+    assert_eq!(fs::read_to_string(source_dir.join("lib.rs")).unwrap(), original);
+
+    fs::remove_dir_all(dir).unwrap();
+}
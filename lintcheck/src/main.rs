@@ -21,7 +21,9 @@
 )]
 
 mod config;
+mod dependents;
 mod driver;
+mod ice;
 mod input;
 mod json;
 mod output;
@@ -143,10 +145,11 @@ impl Crate {
             cmd = Command::new("cargo");
         }
 
+        let clippy_args = clippy_args.join("__CLIPPY_HACKERY__");
         cmd.arg(if config.fix { "fix" } else { "check" })
             .arg("--quiet")
             .current_dir(&self.path)
-            .env("CLIPPY_ARGS", clippy_args.join("__CLIPPY_HACKERY__"))
+            .env("CLIPPY_ARGS", &clippy_args)
             .env("CLIPPY_DISABLE_DOCS_LINKS", "1");
 
         if let Some(server) = server {
@@ -229,7 +232,14 @@ impl Crate {
             .map(ClippyCheckOutput::ClippyWarning)
             .collect();
 
-        if let Some(ice) = RustcIce::from_stderr_and_status(&self.name, *status, &stderr) {
+        if let Some(mut ice) = RustcIce::from_stderr_and_status(&self.name, *status, &stderr) {
+            let bundle = ice::write_bundle(self, &stderr, &clippy_args);
+            println!(
+                "Wrote ICE reproduction bundle for {} to {}",
+                self.name,
+                bundle.display()
+            );
+            ice.bundle = Some(bundle);
             entries.push(ClippyCheckOutput::RustcIce(ice));
         } else if !status.success() {
             println!("non-ICE bad exit status for {} {}: {}", self.name, self.version, stderr);
@@ -310,6 +320,12 @@ fn main() {
             write_summary,
         }) => json::diff(&old, &new, truncate, write_summary),
         Some(Commands::Popular { output, number }) => popular_crates::fetch(output, number).unwrap(),
+        Some(Commands::Dependents {
+            output,
+            number,
+            lockfile,
+            registry_index,
+        }) => dependents::fetch(output, number, lockfile.as_deref(), registry_index.as_deref()).unwrap(),
         None => lintcheck(config),
     }
 }
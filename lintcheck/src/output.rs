@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{self, Write as _};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 
 use crate::config::{LintcheckConfig, OutputFormat};
@@ -21,15 +21,17 @@ pub enum ClippyCheckOutput {
 pub struct RustcIce {
     pub crate_name: String,
     pub ice_content: String,
+    /// The directory containing the reproduction bundle, see [`crate::ice`]
+    pub bundle: Option<PathBuf>,
 }
 
 impl fmt::Display for RustcIce {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}:\n{}\n========================================\n",
-            self.crate_name, self.ice_content
-        )
+        write!(f, "{}:\n{}\n", self.crate_name, self.ice_content)?;
+        if let Some(bundle) = &self.bundle {
+            writeln!(f, "Reproduction bundle: {}", bundle.display())?;
+        }
+        writeln!(f, "========================================")
     }
 }
 
@@ -41,6 +43,7 @@ impl RustcIce {
             Some(Self {
                 crate_name: crate_name.to_owned(),
                 ice_content: stderr.to_owned(),
+                bundle: None,
             })
         } else {
             None
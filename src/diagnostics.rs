@@ -0,0 +1,324 @@
+//! Adjustments made by the driver to diagnostics before they're emitted.
+//!
+//! These are configured through `CLIPPY_ARGS` by `cargo clippy` and applied to every diagnostic,
+//! including the ones emitted by rustc itself.
+
+use clippy_utils::diagnostics::emitting_lint;
+use rustc_ast::visit::{FnKind, Visitor, walk_crate, walk_fn};
+use rustc_ast::{AttrVec, Crate, NodeId};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::{DiagInner, ErrorGuaranteed, Suggestions, TRACK_DIAGNOSTIC};
+use rustc_lint::{EarlyContext, EarlyLintPass, LintContext};
+use rustc_session::declare_lint_pass;
+use rustc_session::parse::ParseSess;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{BytePos, Span};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+type TrackDiagnostic = fn(DiagInner, &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>) -> Option<ErrorGuaranteed>;
+
+#[derive(Default)]
+pub struct DiagnosticFilter {
+    /// The lints passed to `--fix-only`. Suggestions for all other lints are removed.
+    pub fix_only: Option<Vec<String>>,
+    /// The revision passed to `--changed-since`. Lints are only reported on the lines changed
+    /// since then.
+    pub changed_since: Option<String>,
+    /// Whether lints in functions containing a changed line are reported as well.
+    pub changed_functions: bool,
+    changed_lines: Option<ChangedLines>,
+    /// The byte ranges of the changed lines, set once all source files have been loaded.
+    changed_spans: OnceLock<Vec<(BytePos, BytePos)>>,
+}
+
+impl DiagnosticFilter {
+    /// Parses and removes the arguments used to configure the filter from `CLIPPY_ARGS`.
+    pub fn from_args(args: &mut Vec<String>) -> Self {
+        let mut filter = Self::default();
+        args.retain(|arg| {
+            if let Some(lints) = arg.strip_prefix("--fix-only=") {
+                filter
+                    .fix_only
+                    .get_or_insert_default()
+                    .extend(lints.split(',').filter(|l| !l.is_empty()).map(normalize_lint_name));
+                false
+            } else if let Some(rev) = arg.strip_prefix("--changed-since=") {
+                filter.changed_since = Some(rev.to_owned());
+                false
+            } else if arg == "--changed-functions" {
+                filter.changed_functions = true;
+                false
+            } else {
+                true
+            }
+        });
+        filter
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fix_only.is_none() && self.changed_since.is_none()
+    }
+
+    /// Starts applying the filter to emitted diagnostics.
+    ///
+    /// This must be called after the compiler's interface has been set up as it replaces the
+    /// diagnostic tracking hook installed there.
+    pub fn install(mut self, psess: &ParseSess) {
+        static PREVIOUS: OnceLock<&'static TrackDiagnostic> = OnceLock::new();
+
+        fn track_diagnostic(
+            mut diag: DiagInner,
+            f: &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>,
+        ) -> Option<ErrorGuaranteed> {
+            if let Some(filter) = FILTER.get()
+                && !filter.apply(&mut diag)
+            {
+                return None;
+            }
+            match PREVIOUS.get() {
+                Some(previous) => previous(diag, f),
+                None => f(diag),
+            }
+        }
+
+        if self.is_empty() {
+            return;
+        }
+        if let Some(rev) = &self.changed_since {
+            match ChangedLines::since(rev) {
+                Ok(changed) => self.changed_lines = Some(changed),
+                Err(e) => {
+                    psess
+                        .dcx()
+                        .warn(format!("could not get the lines changed since `{rev}`: {e}"));
+                },
+            }
+        }
+        if FILTER.set(self).is_err() {
+            return;
+        }
+        let _ = PREVIOUS.set(TRACK_DIAGNOSTIC.swap(&(track_diagnostic as _)));
+    }
+
+    /// Modifies the diagnostic, returning `false` if it shouldn't be emitted at all.
+    fn apply(&self, diag: &mut DiagInner) -> bool {
+        // Lints emitted before the changed lines have been resolved are kept.
+        if diag.is_lint.is_some()
+            && let Some(changed) = self.changed_spans.get()
+        {
+            let overlaps = |span: Span| changed.iter().any(|&(lo, hi)| lo <= span.hi() && span.lo() <= hi);
+            let spans = diag.span.primary_spans();
+            if !spans.is_empty()
+                && !spans
+                    .iter()
+                    .any(|&span| overlaps(span) || overlaps(span.source_callsite()))
+            {
+                return false;
+            }
+        }
+        if let Some(fix_only) = &self.fix_only
+            && !matches!(diag.suggestions, Suggestions::Disabled)
+            && emitting_lint().is_none_or(|lint| !fix_only.contains(&lint.name_lower()))
+        {
+            diag.suggestions = Suggestions::Disabled;
+        }
+        true
+    }
+}
+
+static FILTER: OnceLock<DiagnosticFilter> = OnceLock::new();
+
+/// Converts `lint-name` and `clippy::lint-name` style names to the form used by rustc.
+fn normalize_lint_name(name: &str) -> String {
+    let name = name.trim().replace('-', "_");
+    if name.contains("::")
+        || !clippy_lints::declared_lints::LINTS
+            .iter()
+            .any(|l| l.name_lower() == name)
+    {
+        name
+    } else {
+        format!("clippy::{name}")
+    }
+}
+
+/// The lines of each file in the repository which were changed since a revision.
+struct ChangedLines {
+    files: FxHashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl ChangedLines {
+    /// Reads the changed lines from the git repository containing the current directory. This
+    /// includes uncommitted changes and untracked files.
+    fn since(rev: &str) -> Result<Self, String> {
+        let root = PathBuf::from(git(&["rev-parse", "--show-toplevel"], None)?.trim_end());
+        let diff = git(
+            &[
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                "--no-renames",
+                "--no-relative",
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                rev,
+                "--",
+            ],
+            Some(&root),
+        )?;
+
+        let mut files: FxHashMap<PathBuf, Vec<RangeInclusive<usize>>> = FxHashMap::default();
+        let mut lines = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                lines = path
+                    .trim_matches('"')
+                    .strip_prefix("b/")
+                    .map(|path| files.entry(canonicalize(root.join(path))).or_default());
+            } else if let Some(hunk) = line.strip_prefix("@@ ")
+                && let Some(lines) = &mut lines
+                && let Some(new) = hunk.split(' ').find_map(|range| range.strip_prefix('+'))
+            {
+                // The new range is either `start,len` or just `start` for a single line.
+                let (start, len) = new.split_once(',').unwrap_or((new, "1"));
+                let (Ok(start), Ok(len)) = (start.parse::<usize>(), len.parse::<usize>()) else {
+                    continue;
+                };
+                // Removed lines are attributed to the lines around them.
+                lines.push(if len == 0 {
+                    start..=start + 1
+                } else {
+                    start..=start + len - 1
+                });
+            }
+        }
+
+        let untracked = git(
+            &["ls-files", "--others", "--exclude-standard", "--full-name"],
+            Some(&root),
+        )?;
+        for path in untracked.lines() {
+            files.insert(canonicalize(root.join(path)), vec![1..=usize::MAX]);
+        }
+
+        Ok(Self { files })
+    }
+
+    fn contains(&self, source_map: &SourceMap, span: Span) -> bool {
+        span_lines(source_map, span).is_some_and(|(path, range)| {
+            self.files.get(&path).is_some_and(|lines| {
+                lines
+                    .iter()
+                    .any(|lines| lines.start() <= range.end() && range.start() <= lines.end())
+            })
+        })
+    }
+
+    /// Converts the changed lines into byte ranges of the loaded source files.
+    fn to_spans(&self, source_map: &SourceMap) -> Vec<(BytePos, BytePos)> {
+        let mut spans = Vec::new();
+        for file in source_map.files().iter() {
+            let Some(path) = file.name.clone().into_local_path() else {
+                continue;
+            };
+            let Some(lines) = self.files.get(&canonicalize(path)) else {
+                continue;
+            };
+            let count = file.count_lines();
+            for range in lines {
+                let (first, last) = (*range.start(), (*range.end()).min(count));
+                if first >= 1 && first <= last {
+                    spans.push((file.line_bounds(first - 1).start, file.line_bounds(last - 1).end));
+                }
+            }
+        }
+        spans
+    }
+}
+
+/// Gets the canonical path of the file containing the span and the lines it covers.
+fn span_lines(source_map: &SourceMap, span: Span) -> Option<(PathBuf, RangeInclusive<usize>)> {
+    if span.is_dummy() {
+        return None;
+    }
+    let lo = source_map.lookup_char_pos(span.lo());
+    let hi = source_map.lookup_char_pos(span.hi());
+    let path = lo.file.name.clone().into_local_path()?;
+    Some((canonicalize(path), lo.line..=hi.line))
+}
+
+/// Resolves symlinks so paths from git and the compiler can be compared.
+fn canonicalize(path: PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or(path)
+}
+
+fn git(args: &[&str], dir: Option<&Path>) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    if let Some(dir) = dir {
+        cmd.arg("-C").arg(dir);
+    }
+    let output = cmd
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("could not run git: {e}"))?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).map_err(|e| e.to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim_end().to_owned())
+    }
+}
+
+declare_lint_pass!(
+    /// Resolves the lines changed since the revision passed to `--changed-since` once all source
+    /// files have been loaded. With `--changed-functions` they're extended to the whole functions
+    /// containing them.
+    ///
+    /// This runs before all other early lint passes so the lines are resolved before any lint is
+    /// checked.
+    ChangedLinesPass => []
+);
+
+impl EarlyLintPass for ChangedLinesPass {
+    fn check_crate(&mut self, cx: &EarlyContext<'_>, krate: &Crate) {
+        struct FnVisitor<'a> {
+            changed: &'a ChangedLines,
+            source_map: &'a SourceMap,
+            fns: Vec<Span>,
+        }
+        impl<'ast> Visitor<'ast> for FnVisitor<'_> {
+            fn visit_fn(&mut self, kind: FnKind<'ast>, _: &AttrVec, span: Span, _: NodeId) {
+                if matches!(kind, FnKind::Fn(..))
+                    && !span.from_expansion()
+                    && self.changed.contains(self.source_map, span)
+                {
+                    self.fns.push(span);
+                }
+                walk_fn(self, kind);
+            }
+        }
+
+        let Some(filter) = FILTER.get() else {
+            return;
+        };
+        let Some(changed) = &filter.changed_lines else {
+            return;
+        };
+        let source_map = cx.sess().source_map();
+        let mut spans = changed.to_spans(source_map);
+        if filter.changed_functions {
+            let mut visitor = FnVisitor {
+                changed,
+                source_map,
+                fns: Vec::new(),
+            };
+            walk_crate(&mut visitor, krate);
+            spans.extend(visitor.fns.iter().map(|span| (span.lo(), span.hi())));
+        }
+        let _ = filter.changed_spans.set(spans);
+    }
+}
@@ -8,8 +8,13 @@
 
 // FIXME: switch to something more ergonomic here, once available.
 // (Currently there is no way to opt into sysroot crates without `extern crate`.)
+extern crate rustc_ast;
+extern crate rustc_data_structures;
 extern crate rustc_driver;
+extern crate rustc_errors;
 extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_metadata;
 extern crate rustc_session;
 extern crate rustc_span;
 
@@ -22,18 +27,25 @@ extern crate rustc_span;
 #[cfg(feature = "jemalloc")]
 extern crate tikv_jemalloc_sys as _;
 
+mod diagnostics;
+
+use clippy_config::Conf;
 use clippy_utils::sym;
 use declare_clippy_lint::LintListBuilder;
+use diagnostics::{ChangedLinesPass, DiagnosticFilter};
 use rustc_interface::interface;
-use rustc_session::EarlyDiagCtxt;
+use rustc_lint::LintStore;
+use rustc_metadata::{DylibError, load_symbol_from_dylib};
 use rustc_session::config::ErrorOutputType;
 use rustc_session::parse::ParseSess;
+use rustc_session::{EarlyDiagCtxt, Session};
 use rustc_span::symbol::Symbol;
 
-use std::env;
+use std::ffi::{CStr, c_char};
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::{env, mem};
 
 use anstream::println;
 
@@ -134,6 +146,12 @@ impl rustc_driver::Callbacks for RustcCallbacks {
 
 struct ClippyCallbacks {
     clippy_args_var: Option<String>,
+    diagnostic_filter: DiagnosticFilter,
+    /// Set by `--unused-allows`, whether unused `#[allow]` attributes are converted to `#[expect]`
+    /// instead of being removed.
+    unused_allows: Option<bool>,
+    /// The libraries passed to `--load-lints`.
+    load_lints: Vec<PathBuf>,
 }
 
 impl rustc_driver::Callbacks for ClippyCallbacks {
@@ -142,9 +160,14 @@ impl rustc_driver::Callbacks for ClippyCallbacks {
         let conf_path = clippy_config::lookup_conf_file();
         let previous = config.register_lints.take();
         let clippy_args_var = self.clippy_args_var.take();
+        let diagnostic_filter = mem::take(&mut self.diagnostic_filter);
+        let changed_since = diagnostic_filter.changed_since.is_some();
+        let unused_allows = self.unused_allows;
+        let load_lints = mem::take(&mut self.load_lints);
         config.psess_created = Some(Box::new(move |psess| {
             track_clippy_args(psess, clippy_args_var.as_deref());
             track_files(psess);
+            diagnostic_filter.install(psess);
 
             // Trigger a rebuild if CLIPPY_CONF_DIR changes. The value must be a valid string so
             // changes between dirs that are invalid UTF-8 will not trigger rebuilds
@@ -164,8 +187,24 @@ impl rustc_driver::Callbacks for ClippyCallbacks {
             list_builder.insert(clippy_lints::declared_lints::LINTS);
             list_builder.register(lint_store);
 
+            if changed_since {
+                lint_store.register_early_pass(|| Box::new(ChangedLinesPass));
+            }
             let conf = clippy_config::Conf::read(sess, &conf_path);
             clippy_lints::register_lint_passes(lint_store, conf);
+            if let Some(to_expect) = unused_allows {
+                clippy_lints::register_unused_allows_pass(lint_store, conf, to_expect);
+            }
+
+            // Paths in the configuration are relative to the configuration file.
+            let conf_dir = conf_path.as_ref().ok().and_then(|(path, _)| path.as_deref()?.parent());
+            let conf_lints = conf
+                .load_lints
+                .iter()
+                .map(|path| conf_dir.map_or_else(|| PathBuf::from(path), |dir| dir.join(path)));
+            for path in load_lints.iter().cloned().chain(conf_lints) {
+                load_lints_from(sess, lint_store, conf, &path);
+            }
 
             #[cfg(feature = "internal")]
             clippy_lints_internal::register_lints(lint_store);
@@ -191,6 +230,93 @@ fn display_help() {
 
 const BUG_REPORT_URL: &str = "https://github.com/rust-lang/rust-clippy/issues/new?template=ice.yml";
 
+/// Removes `--unused-allows[=expect]` from the arguments, returning whether unused `#[allow]`
+/// attributes should be converted to `#[expect]`.
+fn unused_allows_from_args(clippy_args: &mut Vec<String>) -> Option<bool> {
+    let mut unused_allows = None;
+    clippy_args.retain(|arg| match arg.as_str() {
+        "--unused-allows" => {
+            unused_allows = Some(false);
+            false
+        },
+        "--unused-allows=expect" => {
+            unused_allows = Some(true);
+            false
+        },
+        _ => true,
+    });
+    if unused_allows.is_some() {
+        // Added first so it can be overridden by the lint levels passed by the user.
+        clippy_args.splice(0..0, ["-W".into(), "clippy::unused_clippy_allow".into()]);
+    }
+    unused_allows
+}
+
+/// Removes `--load-lints <path>` and `--load-lints=<path>` from the arguments, returning the paths.
+fn load_lints_from_args(args: &mut Vec<String>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        if let Some(path) = args[i].strip_prefix("--load-lints=") {
+            paths.push(PathBuf::from(path));
+            args.remove(i);
+        } else if args[i] == "--load-lints" && i + 1 < args.len() {
+            paths.push(PathBuf::from(args.remove(i + 1)));
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    paths
+}
+
+/// The functions exported by libraries passed to `--load-lints`.
+type BuildIdFn = extern "C" fn() -> *const c_char;
+type RegisterLintsFn = fn(&mut LintStore, &'static Conf);
+
+/// Loads the dynamic library at `path` and calls its `register_lints` function.
+fn load_lints_from(sess: &Session, lint_store: &mut LintStore, conf: &'static Conf, path: &Path) {
+    if !path.exists() {
+        sess.dcx()
+            .fatal(format!("couldn't load lints from `{}`: no such file", path.display()));
+    }
+
+    // SAFETY: `clippy_build_id` is exported by `clippy_config::export_build_id!` with this signature,
+    // and returns a pointer to a static C string.
+    let build_id = match unsafe { load_symbol_from_dylib::<BuildIdFn>(path, "clippy_build_id") } {
+        Ok(build_id) => unsafe { CStr::from_ptr(build_id()) },
+        Err(DylibError::DlOpen(path, err) | DylibError::DlSym(path, err)) => {
+            sess.dcx()
+                .struct_fatal(format!("couldn't load lints from `{path}`: {err}"))
+                .with_help("the library has to export its build ID with `clippy_config::export_build_id!()`")
+                .emit();
+        },
+    };
+    if build_id != clippy_config::BUILD_ID {
+        sess.dcx().fatal(format!(
+            "couldn't load lints from `{}`: the library was built for {}, but this is {}",
+            path.display(),
+            build_id.to_string_lossy(),
+            clippy_config::BUILD_ID.to_string_lossy(),
+        ));
+    }
+
+    // SAFETY: the library was built with the same Clippy version and toolchain, checked with the
+    // build ID above.
+    match unsafe { load_symbol_from_dylib::<RegisterLintsFn>(path, "register_lints") } {
+        Ok(register_lints) => {
+            register_lints(lint_store, conf);
+            // Re-run Clippy when the library is rebuilt.
+            if let Some(path) = path.to_str() {
+                sess.psess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+            }
+        },
+        Err(DylibError::DlOpen(path, err) | DylibError::DlSym(path, err)) => {
+            sess.dcx().fatal(format!("couldn't load lints from `{path}`: {err}"));
+        },
+    }
+}
+
 pub fn main() {
     let early_dcx = EarlyDiagCtxt::new(ErrorOutputType::default());
 
@@ -276,7 +402,7 @@ pub fn main() {
 
         let mut no_deps = false;
         let clippy_args_var = env::var("CLIPPY_ARGS").ok();
-        let clippy_args = clippy_args_var
+        let mut clippy_args = clippy_args_var
             .as_deref()
             .unwrap_or_default()
             .split("__CLIPPY_HACKERY__")
@@ -290,6 +416,10 @@ pub fn main() {
             })
             .chain(vec!["--cfg".into(), "clippy".into()])
             .collect::<Vec<String>>();
+        let diagnostic_filter = DiagnosticFilter::from_args(&mut clippy_args);
+        let unused_allows = unused_allows_from_args(&mut clippy_args);
+        let mut load_lints = load_lints_from_args(&mut clippy_args);
+        load_lints.extend(load_lints_from_args(&mut args));
 
         // If no Clippy lints will be run we do not need to run Clippy
         let cap_lints_allow = arg_value(&orig_args, "--cap-lints", |val| val == "allow").is_some()
@@ -306,7 +436,15 @@ pub fn main() {
         let clippy_enabled = !cap_lints_allow && relevant_package && !info_query;
         if clippy_enabled {
             args.extend(clippy_args);
-            rustc_driver::run_compiler(&args, &mut ClippyCallbacks { clippy_args_var });
+            rustc_driver::run_compiler(
+                &args,
+                &mut ClippyCallbacks {
+                    clippy_args_var,
+                    diagnostic_filter,
+                    unused_allows,
+                    load_lints,
+                },
+            );
         } else {
             rustc_driver::run_compiler(&args, &mut RustcCallbacks { clippy_args_var });
         }
@@ -326,6 +464,7 @@ Run <cyan>clippy-driver</> with the same arguments you use for <cyan>rustc</>
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
     <cyan,bold>--rustc</>                  Pass all arguments to <cyan>rustc</>
+    <cyan,bold>--load-lints</> <cyan><<PATH>></>      Load additional lints from the dynamic library at <cyan><<PATH>></>
 
 <green,bold>Allowing / Denying lints</>
 You can use tool lints to allow or deny lints from your code, e.g.:
@@ -0,0 +1,570 @@
+//! Applying suggestions which aren't applied by `cargo fix`.
+
+use crate::ClippyCmd;
+use anstream::{eprint, eprintln, print};
+use rustfix::diagnostics::{Applicability, Diagnostic};
+use rustfix::{CodeFix, Filter, Suggestion};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<serde_json::Value>,
+}
+
+/// The diagnostics produced by a single `cargo check` run.
+struct CheckResult {
+    diagnostics: Vec<Diagnostic>,
+    /// The messages of the emitted errors, used to find errors introduced by a fix. These don't
+    /// include the location so that moved code isn't treated as a new error.
+    errors: HashSet<String>,
+}
+
+/// Runs `cargo check` with Clippy, collecting the emitted diagnostics.
+fn check(cmd: &ClippyCmd) -> Result<CheckResult, i32> {
+    let output = cmd
+        .to_std_cmd()
+        .arg("--message-format=json")
+        .stderr(Stdio::piped())
+        .output()
+        .expect("could not run cargo");
+
+    let mut result = CheckResult {
+        diagnostics: Vec::new(),
+        errors: HashSet::new(),
+    };
+    for line in output.stdout.split(|&b| b == b'\n') {
+        let Ok(CargoMessage {
+            reason,
+            message: Some(message),
+        }) = serde_json::from_slice(line)
+        else {
+            continue;
+        };
+        if reason != "compiler-message" {
+            continue;
+        }
+        if message["level"] == "error" {
+            result
+                .errors
+                .insert(message["message"].as_str().unwrap_or_default().to_owned());
+        }
+        if let Ok(diag) = serde_json::from_value(message) {
+            result.diagnostics.push(diag);
+        }
+    }
+
+    // Cargo itself failed, e.g. because of an invalid manifest.
+    if !output.status.success() && result.errors.is_empty() {
+        eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+        return Err(output.status.code().unwrap_or(-1));
+    }
+    Ok(result)
+}
+
+/// Gets the root directory of the workspace, which the file names in diagnostics are relative to.
+fn workspace_root(cmd: &ClippyCmd) -> PathBuf {
+    let mut locate = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+    locate.args(["locate-project", "--workspace", "--message-format", "plain"]);
+    if let Some(pos) = cmd.args.iter().position(|arg| arg == "--manifest-path") {
+        locate.args(&cmd.args[pos..(pos + 2).min(cmd.args.len())]);
+    } else if let Some(arg) = cmd.args.iter().find(|arg| arg.starts_with("--manifest-path=")) {
+        locate.arg(arg);
+    }
+    let output = locate.output().expect("could not run cargo");
+    let manifest = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    manifest.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Collects the suggestions of the given applicability along with the lint which emitted them and
+/// the file they apply to.
+fn suggestions(diagnostics: &[Diagnostic], applicability: Applicability) -> Vec<(Option<String>, String, Suggestion)> {
+    let mut suggestions = Vec::new();
+    for diag in diagnostics {
+        let mut diag = diag.clone();
+        for child in &mut diag.children {
+            child
+                .spans
+                .retain(|span| span.suggestion_applicability == Some(applicability));
+        }
+        if let Some(suggestion) = rustfix::collect_suggestions(&diag, &HashSet::new(), Filter::Everything)
+            && let Some(file) = suggestion
+                .solutions
+                .first()
+                .and_then(|solution| solution.replacements.first())
+                .map(|replacement| replacement.snippet.file_name.clone())
+        {
+            suggestions.push((diag.code.map(|code| code.code), file, suggestion));
+        }
+    }
+    suggestions
+}
+
+/// Collects the suggestions of the given applicability, grouped by lint and then by file.
+fn suggestions_by_lint(
+    diagnostics: &[Diagnostic],
+    applicability: Applicability,
+) -> BTreeMap<String, BTreeMap<String, Vec<Suggestion>>> {
+    let mut lints: BTreeMap<String, BTreeMap<String, Vec<Suggestion>>> = BTreeMap::new();
+    for (lint, file, suggestion) in suggestions(diagnostics, applicability) {
+        let Some(lint) = lint else {
+            continue;
+        };
+        let suggestions = lints.entry(lint).or_default().entry(file).or_default();
+        if !suggestions.contains(&suggestion) {
+            suggestions.push(suggestion);
+        }
+    }
+    lints
+}
+
+enum Outcome {
+    Accepted,
+    Rejected(String),
+}
+
+struct Report {
+    lint: String,
+    suggestions: usize,
+    files: usize,
+    outcome: Outcome,
+}
+
+/// Applies `MaybeIncorrect` suggestions one lint at a time, keeping only the ones which don't
+/// introduce new errors.
+pub(crate) fn fix_maybe_incorrect(cmd: &ClippyCmd) -> Result<(), i32> {
+    let root = workspace_root(cmd);
+    let mut result = check(cmd)?;
+    let mut reports: Vec<Report> = Vec::new();
+
+    loop {
+        let lints = suggestions_by_lint(&result.diagnostics, Applicability::MaybeIncorrect);
+        let Some((lint, files)) = lints
+            .into_iter()
+            .find(|(lint, _)| !reports.iter().any(|report| report.lint == *lint))
+        else {
+            break;
+        };
+
+        // Apply the suggestions of the lint, remembering the original contents to revert them.
+        let mut originals = HashMap::new();
+        let mut suggestions = 0;
+        let mut failed = None;
+        for (file, file_suggestions) in &files {
+            let path = root.join(file);
+            let Ok(original) = fs::read_to_string(&path) else {
+                failed = Some(format!("could not read `{file}`"));
+                break;
+            };
+            match rustfix::apply_suggestions(&original, file_suggestions) {
+                Ok(fixed) => {
+                    if fs::write(&path, fixed).is_err() {
+                        failed = Some(format!("could not write `{file}`"));
+                    }
+                    suggestions += file_suggestions.len();
+                    originals.insert(path, original);
+                },
+                Err(e) => failed = Some(format!("could not apply the suggestions to `{file}`: {e}")),
+            }
+            if failed.is_some() {
+                break;
+            }
+        }
+
+        let outcome = if let Some(reason) = failed {
+            Outcome::Rejected(reason)
+        } else {
+            let new_result = check(cmd)?;
+            if let Some(error) = new_result.errors.iter().find(|e| !result.errors.contains(*e)) {
+                Outcome::Rejected(format!("error: {error}"))
+            } else {
+                result = new_result;
+                Outcome::Accepted
+            }
+        };
+        if let Outcome::Rejected(_) = outcome {
+            for (path, original) in &originals {
+                fs::write(path, original).unwrap_or_else(|e| panic!("could not revert `{}`: {e}", path.display()));
+            }
+        }
+        reports.push(Report {
+            lint,
+            suggestions,
+            files: files.len(),
+            outcome,
+        });
+    }
+
+    print_report(&reports);
+    Ok(())
+}
+
+fn print_report(reports: &[Report]) {
+    if reports.is_empty() {
+        eprintln!("No `MaybeIncorrect` suggestions were found");
+        return;
+    }
+    for (title, accepted) in [("Accepted fixes", true), ("Rejected fixes", false)] {
+        let mut reports = reports
+            .iter()
+            .filter(|report| matches!(report.outcome, Outcome::Accepted) == accepted)
+            .peekable();
+        if reports.peek().is_none() {
+            continue;
+        }
+        eprintln!("{title}:");
+        for report in reports {
+            let plural = if report.suggestions == 1 { "" } else { "s" };
+            let files_plural = if report.files == 1 { "" } else { "s" };
+            eprint!(
+                "    {}: {} suggestion{plural} in {} file{files_plural}",
+                report.lint, report.suggestions, report.files
+            );
+            match &report.outcome {
+                Outcome::Accepted => eprintln!(),
+                Outcome::Rejected(reason) => eprintln!(", reverted: {reason}"),
+            }
+        }
+    }
+}
+
+/// Where `--fix --diff` writes the diff of the fixes.
+pub(crate) enum DiffOutput {
+    Stdout,
+    File(PathBuf),
+}
+
+/// The number of unchanged lines shown around the changes in the diff.
+const CONTEXT_LINES: usize = 3;
+
+/// A suggestion which was applied to a file.
+struct AppliedFix {
+    lint: Option<String>,
+    /// The replaced byte ranges of the original file and their replacements.
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+/// Prints the changes `--fix` would make as a unified diff without modifying any files.
+pub(crate) fn print_diff(cmd: &ClippyCmd, output: &DiffOutput) -> Result<(), i32> {
+    let root = workspace_root(cmd);
+    let result = check(cmd)?;
+    let mut files: BTreeMap<String, Vec<(Option<String>, Suggestion)>> = BTreeMap::new();
+    for (lint, file, suggestion) in suggestions(&result.diagnostics, Applicability::MachineApplicable) {
+        files.entry(file).or_default().push((lint, suggestion));
+    }
+
+    let mut diff = String::new();
+    let mut fix_count = 0;
+    let mut fixed_files = 0;
+    for (file, suggestions) in &files {
+        let Ok(original) = fs::read_to_string(root.join(file)) else {
+            eprintln!("warning: could not read `{file}`, skipping its fixes");
+            continue;
+        };
+        let applied = resolve_overlaps(&original, suggestions);
+        if !applied.is_empty() {
+            fix_count += applied.len();
+            fixed_files += 1;
+            write_file_diff(&mut diff, file, &original, applied);
+        }
+    }
+
+    match output {
+        DiffOutput::Stdout => print!("{diff}"),
+        DiffOutput::File(path) => {
+            if let Err(e) = fs::write(path, &diff) {
+                eprintln!("error: could not write `{}`: {e}", path.display());
+                return Err(1);
+            }
+        },
+    }
+    if fix_count == 0 {
+        eprintln!("No machine applicable suggestions were found");
+    } else {
+        let plural = if fix_count == 1 { "" } else { "es" };
+        let files_plural = if fixed_files == 1 { "" } else { "s" };
+        eprintln!("{fix_count} fix{plural} in {fixed_files} file{files_plural}");
+    }
+    Ok(())
+}
+
+/// Picks the suggestions to apply the same way as `cargo fix`. Duplicated suggestions, e.g. from
+/// checking both a library and its tests, are applied once and suggestions overlapping an
+/// already applied one are skipped.
+fn resolve_overlaps(original: &str, suggestions: &[(Option<String>, Suggestion)]) -> Vec<AppliedFix> {
+    let mut already_applied = HashSet::new();
+    let mut fix = CodeFix::new(original);
+    let mut applied = Vec::new();
+    for (lint, suggestion) in suggestions.iter().rev() {
+        if suggestion.solutions.iter().any(|sol| already_applied.contains(sol)) {
+            continue;
+        }
+        // A suggestion which fails to apply may have been partially applied.
+        let mut attempt = fix.clone();
+        if attempt.apply(suggestion).is_ok() {
+            fix = attempt;
+            already_applied.extend(&suggestion.solutions);
+            applied.push(AppliedFix {
+                lint: lint.clone(),
+                replacements: suggestion
+                    .solutions
+                    .iter()
+                    .flat_map(|sol| &sol.replacements)
+                    .map(|r| (r.snippet.range.clone(), r.replacement.clone()))
+                    .collect(),
+            });
+        }
+    }
+    applied.sort_by_key(|fix| fix.replacements.iter().map(|(range, _)| range.start).min());
+    applied
+}
+
+/// A group of fixes close enough together to share a hunk.
+struct Hunk {
+    /// The first and last line changed by the fixes.
+    lines: (usize, usize),
+    lints: Vec<String>,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+/// Writes the hunks of a single file to the diff. The lints causing the changes are added as the
+/// hunk's section heading.
+fn write_file_diff(diff: &mut String, file: &str, original: &str, applied: Vec<AppliedFix>) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(original.match_indices('\n').map(|(i, _)| i + 1))
+        .filter(|&i| i < original.len())
+        .collect();
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos).saturating_sub(1);
+    let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(original.len());
+    let last_line = line_starts.len().saturating_sub(1);
+
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for fix in applied {
+        let start = fix.replacements.iter().map(|(range, _)| range.start).min().unwrap_or(0);
+        let end = fix.replacements.iter().map(|(range, _)| range.end).max().unwrap_or(0);
+        let lines = (line_of(start), line_of(end.saturating_sub(1).max(start)));
+        match hunks.last_mut() {
+            Some(hunk) if lines.0 <= hunk.lines.1 + 2 * CONTEXT_LINES + 1 => {
+                hunk.lines.1 = hunk.lines.1.max(lines.1);
+                hunk.replacements.extend(fix.replacements);
+            },
+            _ => hunks.push(Hunk {
+                lines,
+                lints: Vec::new(),
+                replacements: fix.replacements,
+            }),
+        }
+        let hunk = hunks.last_mut().unwrap();
+        if let Some(lint) = fix.lint
+            && !hunk.lints.contains(&lint)
+        {
+            hunk.lints.push(lint);
+        }
+    }
+
+    let _ = writeln!(diff, "--- a/{file}\n+++ b/{file}");
+    let (mut added, mut removed) = (0, 0);
+    for mut hunk in hunks {
+        let first = hunk.lines.0.saturating_sub(CONTEXT_LINES);
+        let last = (hunk.lines.1 + CONTEXT_LINES).min(last_line);
+        let region = line_starts.get(first).copied().unwrap_or(0)..line_end(last);
+
+        hunk.replacements.sort_by_key(|(range, _)| range.start);
+        let mut new = String::new();
+        let mut pos = region.start;
+        for (range, replacement) in &hunk.replacements {
+            new.push_str(&original[pos..range.start]);
+            new.push_str(replacement);
+            pos = range.end;
+        }
+        new.push_str(&original[pos..region.end]);
+
+        let old_lines: Vec<_> = original[region].split_inclusive('\n').collect();
+        let new_lines: Vec<_> = new.split_inclusive('\n').collect();
+        // Unified diffs use the line before the hunk as the start of an empty range.
+        let start = |len: usize, line: usize| if len == 0 { line } else { line + 1 };
+        let _ = write!(
+            diff,
+            "@@ -{},{} +{},{} @@",
+            start(old_lines.len(), first),
+            old_lines.len(),
+            start(new_lines.len(), first + added - removed),
+            new_lines.len(),
+        );
+        if !hunk.lints.is_empty() {
+            let _ = write!(diff, " {}", hunk.lints.join(", "));
+        }
+        diff.push('\n');
+        for (prefix, line) in diff_lines(&old_lines, &new_lines) {
+            diff.push(prefix);
+            diff.push_str(line);
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        added += new_lines.len();
+        removed += old_lines.len();
+    }
+}
+
+/// Computes a line based diff from the longest common subsequence of the lines.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    // `lcs[i][j]` is the length of the longest common subsequence of `old[i..]` and `new[j..]`.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppliedFix, diff_lines, resolve_overlaps, write_file_diff};
+    use rustfix::{LinePosition, LineRange, Replacement, Snippet, Solution, Suggestion};
+    use std::ops::Range;
+
+    fn suggestion(range: Range<usize>, replacement: &str) -> Suggestion {
+        let position = LinePosition { line: 0, column: 0 };
+        let snippet = Snippet {
+            file_name: "src/lib.rs".into(),
+            line_range: LineRange {
+                start: position,
+                end: position,
+            },
+            range,
+        };
+        Suggestion {
+            message: String::new(),
+            snippets: vec![snippet.clone()],
+            solutions: vec![Solution {
+                message: String::new(),
+                replacements: vec![Replacement {
+                    snippet,
+                    replacement: replacement.into(),
+                }],
+            }],
+        }
+    }
+
+    fn fix(lint: &str, range: Range<usize>, replacement: &str) -> AppliedFix {
+        AppliedFix {
+            lint: Some(lint.into()),
+            replacements: vec![(range, replacement.into())],
+        }
+    }
+
+    fn file_diff(original: &str, applied: Vec<AppliedFix>) -> String {
+        let mut diff = String::new();
+        write_file_diff(&mut diff, "src/lib.rs", original, applied);
+        diff
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            diff_lines(&["a\n", "b\n", "c\n"], &["a\n", "x\n", "c\n", "d\n"]),
+            [(' ', "a\n"), ('-', "b\n"), ('+', "x\n"), (' ', "c\n"), ('+', "d\n")]
+        );
+        assert_eq!(diff_lines(&["a\n", "b\n"], &["b\n"]), [('-', "a\n"), (' ', "b\n")]);
+        assert_eq!(diff_lines(&[], &["a"]), [('+', "a")]);
+        assert!(diff_lines(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn overlaps() {
+        let original = "let x = a.len() == 0;\n";
+        let suggestions = [
+            (Some("len_zero".to_owned()), suggestion(8..20, "a.is_empty()")),
+            (Some("other".to_owned()), suggestion(8..15, "b")),
+            // The same suggestion emitted twice, e.g. for a library and its tests
+            (Some("other".to_owned()), suggestion(8..15, "b")),
+            (Some("later".to_owned()), suggestion(4..5, "y")),
+        ];
+        let applied = resolve_overlaps(original, &suggestions);
+        let applied: Vec<_> = applied
+            .iter()
+            .map(|fix| (fix.lint.as_deref(), fix.replacements.clone()))
+            .collect();
+        // Suggestions are applied last to first, so the overlapping `len_zero` one is skipped
+        assert_eq!(
+            applied,
+            [
+                (Some("later"), vec![(4..5, "y".to_owned())]),
+                (Some("other"), vec![(8..15, "b".to_owned())]),
+            ]
+        );
+    }
+
+    #[test]
+    fn hunks() {
+        let original = "fn a() {}\nfn b() {}\nfn c() {}\n";
+        assert_eq!(
+            file_diff(original, vec![fix("some_lint", 13..14, "x")]),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,3 +1,3 @@ some_lint\n fn a() {}\n-fn b() {}\n+fn x() {}\n fn c() {}\n"
+        );
+
+        // Fixes more than `2 * CONTEXT_LINES` lines apart get separate hunks. The second hunk
+        // accounts for the line added by the first.
+        let original = "0\n1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n";
+        assert_eq!(
+            file_diff(
+                original,
+                vec![fix("first", 0..1, "zero\nnew"), fix("second", 23..25, "eleven")]
+            ),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,4 +1,5 @@ first\n-0\n+zero\n+new\n 1\n 2\n 3\n\
+             @@ -9,4 +10,4 @@ second\n 8\n 9\n 10\n-11\n+eleven\n"
+        );
+    }
+
+    #[test]
+    fn insertion_at_end_of_file() {
+        let original = "a\nb\nc\nd\ne\n";
+        assert_eq!(
+            file_diff(original, vec![fix("some_lint", 10..10, "f\n")]),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -2,4 +2,5 @@ some_lint\n b\n c\n d\n e\n+f\n"
+        );
+
+        let original = "a\nb";
+        assert_eq!(
+            file_diff(original, vec![fix("some_lint", 3..3, "\n")]),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,2 +1,2 @@ some_lint\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+
+        assert_eq!(
+            file_diff("", vec![fix("some_lint", 0..0, "fn main() {}\n")]),
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -0,0 +1,1 @@ some_lint\n+fn main() {}\n"
+        );
+    }
+}
@@ -10,6 +10,9 @@ use std::process::{self, Command};
 
 use anstream::println;
 
+mod fix;
+mod summary;
+
 fn show_help() {
     println!("{}", help_message());
 }
@@ -52,6 +55,36 @@ struct ClippyCmd {
     cargo_subcommand: &'static str,
     args: Vec<String>,
     clippy_args: Vec<String>,
+    /// Whether `MaybeIncorrect` suggestions should be applied and checked one lint at a time.
+    fix_maybe_incorrect: bool,
+    /// Where the fixes are written as a diff instead of applying them.
+    diff: Option<fix::DiffOutput>,
+    /// Whether the number of warnings per lint and group is printed after the run.
+    summary: bool,
+    /// The maximum number of warnings of each lint or group passed to `--max-warnings`.
+    max_warnings: Vec<(String, usize)>,
+}
+
+/// Gets the value of an option passed as a separate argument, exiting with `error` if it's missing.
+fn option_value(args: &mut impl Iterator<Item = String>, error: &str) -> String {
+    args.next().unwrap_or_else(|| {
+        eprintln!("error: {error}");
+        process::exit(1);
+    })
+}
+
+fn max_warnings_arg(arg: &str) -> (String, usize) {
+    summary::parse_max_warnings(arg).unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        process::exit(1);
+    })
+}
+
+/// Makes the path of a `--load-lints` library absolute, as `clippy-driver` runs in the directory of
+/// each package.
+fn load_lints_arg(path: &str) -> String {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.into());
+    format!("--load-lints={}", path.display())
 }
 
 impl ClippyCmd {
@@ -62,13 +95,75 @@ impl ClippyCmd {
         let mut cargo_subcommand = "check";
         let mut args = vec![];
         let mut clippy_args: Vec<String> = vec![];
-
-        for arg in old_args.by_ref() {
+        let mut fix_maybe_incorrect = false;
+        let mut diff = None;
+        let mut summary = false;
+        let mut max_warnings = Vec::new();
+
+        while let Some(arg) = old_args.next() {
+            if let Some(lints) = arg.strip_prefix("--fix-only=") {
+                cargo_subcommand = "fix";
+                clippy_args.push(format!("--fix-only={lints}"));
+                continue;
+            }
+            if let Some(rev) = arg.strip_prefix("--changed-since=") {
+                clippy_args.push(format!("--changed-since={rev}"));
+                continue;
+            }
+            if let Some(path) = arg.strip_prefix("--load-lints=") {
+                clippy_args.push(load_lints_arg(path));
+                continue;
+            }
+            if let Some(max) = arg.strip_prefix("--max-warnings=") {
+                max_warnings.push(max_warnings_arg(max));
+                continue;
+            }
+            if let Some(path) = arg.strip_prefix("--diff=") {
+                diff = Some(fix::DiffOutput::File(path.into()));
+                continue;
+            }
             match arg.as_str() {
                 "--fix" => {
                     cargo_subcommand = "fix";
                     continue;
                 },
+                "--fix-only" => {
+                    let lints = option_value(&mut old_args, "`--fix-only` requires a comma separated list of lints");
+                    cargo_subcommand = "fix";
+                    clippy_args.push(format!("--fix-only={lints}"));
+                    continue;
+                },
+                "--fix-maybe-incorrect" => {
+                    fix_maybe_incorrect = true;
+                    continue;
+                },
+                "--diff" => {
+                    diff = Some(fix::DiffOutput::Stdout);
+                    continue;
+                },
+                "--changed-since" => {
+                    let rev = option_value(&mut old_args, "`--changed-since` requires a git revision");
+                    clippy_args.push(format!("--changed-since={rev}"));
+                    continue;
+                },
+                "--load-lints" => {
+                    let path = option_value(&mut old_args, "`--load-lints` requires the path of a dynamic library");
+                    clippy_args.push(load_lints_arg(&path));
+                    continue;
+                },
+                "--summary" => {
+                    summary = true;
+                    continue;
+                },
+                "--max-warnings" => {
+                    let max = option_value(&mut old_args, "`--max-warnings` requires `<lint-or-group>=<n>`");
+                    max_warnings.push(max_warnings_arg(&max));
+                    continue;
+                },
+                "--changed-functions" | "--unused-allows" | "--unused-allows=expect" => {
+                    clippy_args.push(arg);
+                    continue;
+                },
                 "--no-deps" => {
                     clippy_args.push("--no-deps".into());
                     continue;
@@ -81,14 +176,71 @@ impl ClippyCmd {
         }
 
         clippy_args.append(&mut (old_args.collect()));
-        if cargo_subcommand == "fix" && !clippy_args.iter().any(|arg| arg == "--no-deps") {
-            clippy_args.push("--no-deps".into());
-        }
+        let mut cmd = Self {
+            cargo_subcommand,
+            args,
+            clippy_args,
+            fix_maybe_incorrect,
+            diff,
+            summary,
+            max_warnings,
+        };
+        cmd.check_option_combinations();
+        cmd
+    }
 
-        Self {
+    /// Checks that the Clippy specific options can be used together, and adds the options they
+    /// imply.
+    fn check_option_combinations(&mut self) {
+        let Self {
             cargo_subcommand,
             args,
             clippy_args,
+            fix_maybe_incorrect,
+            diff,
+            summary,
+            max_warnings,
+        } = self;
+        let fix_maybe_incorrect = *fix_maybe_incorrect;
+        if clippy_args.iter().any(|arg| arg == "--changed-functions")
+            && !clippy_args.iter().any(|arg| arg.starts_with("--changed-since="))
+        {
+            eprintln!("error: `--changed-functions` can only be used with `--changed-since`");
+            process::exit(1);
+        }
+        if *summary || !max_warnings.is_empty() {
+            if fix_maybe_incorrect || diff.is_some() {
+                eprintln!(
+                    "error: `--summary` and `--max-warnings` can't be used with `--fix-maybe-incorrect` or `--diff`"
+                );
+                process::exit(1);
+            }
+            if summary::message_formats(args).any(|format| !format.starts_with("json")) {
+                eprintln!("error: `--summary` and `--max-warnings` can only be used with JSON message formats");
+                process::exit(1);
+            }
+        }
+        if diff.is_some() {
+            if *cargo_subcommand != "fix" {
+                eprintln!("error: `--diff` can only be used with `--fix` or `--fix-only`");
+                process::exit(1);
+            }
+            // The files aren't modified, so the suggestions are collected from `cargo check`.
+            *cargo_subcommand = "check";
+            args.retain(|arg| {
+                !matches!(
+                    arg.as_str(),
+                    "--allow-dirty" | "--allow-staged" | "--allow-no-vcs" | "--broken-code"
+                )
+            });
+        }
+        if (fix_maybe_incorrect || diff.is_some()) && !args.iter().any(|arg| is_target_selection(arg)) {
+            args.push("--all-targets".into());
+        }
+        if (*cargo_subcommand == "fix" || fix_maybe_incorrect || diff.is_some())
+            && !clippy_args.iter().any(|arg| arg == "--no-deps")
+        {
+            clippy_args.push("--no-deps".into());
         }
     }
 
@@ -104,7 +256,27 @@ impl ClippyCmd {
         path
     }
 
-    fn into_std_cmd(self) -> Command {
+    /// Resolves the revision passed to `--changed-since` to a commit, so Clippy is rerun when e.g.
+    /// the branch it names is updated.
+    fn resolve_changed_since(&mut self) -> Result<(), i32> {
+        for arg in &mut self.clippy_args {
+            if let Some(rev) = arg.strip_prefix("--changed-since=") {
+                let output = Command::new("git")
+                    .args(["rev-parse", "--verify", "--quiet", "--end-of-options"])
+                    .arg(format!("{rev}^{{commit}}"))
+                    .output()
+                    .expect("could not run git");
+                if !output.status.success() {
+                    eprintln!("error: `{rev}` is not a commit in the current git repository");
+                    return Err(1);
+                }
+                *arg = format!("--changed-since={}", String::from_utf8_lossy(&output.stdout).trim());
+            }
+        }
+        Ok(())
+    }
+
+    fn to_std_cmd(&self) -> Command {
         let mut cmd = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
         let clippy_args: String = self
             .clippy_args
@@ -128,9 +300,20 @@ fn process<I>(old_args: I) -> Result<(), i32>
 where
     I: Iterator<Item = String>,
 {
-    let cmd = ClippyCmd::new(old_args);
+    let mut cmd = ClippyCmd::new(old_args);
+    cmd.resolve_changed_since()?;
+
+    if cmd.fix_maybe_incorrect {
+        return fix::fix_maybe_incorrect(&cmd);
+    }
+    if let Some(output) = &cmd.diff {
+        return fix::print_diff(&cmd, output);
+    }
+    if cmd.summary || !cmd.max_warnings.is_empty() {
+        return summary::run(&cmd);
+    }
 
-    let mut cmd = cmd.into_std_cmd();
+    let mut cmd = cmd.to_std_cmd();
 
     let exit_status = cmd
         .spawn()
@@ -145,6 +328,24 @@ where
     }
 }
 
+/// Checks if the argument selects the targets to check, e.g. `--lib` or `--bin=foo`.
+fn is_target_selection(arg: &str) -> bool {
+    let name = arg.split_once('=').map_or(arg, |(name, _)| name);
+    matches!(
+        name,
+        "--lib"
+            | "--bins"
+            | "--bin"
+            | "--examples"
+            | "--example"
+            | "--tests"
+            | "--test"
+            | "--benches"
+            | "--bench"
+            | "--all-targets"
+    )
+}
+
 #[must_use]
 pub fn help_message() -> &'static str {
     color_print::cstr!(
@@ -156,6 +357,19 @@ pub fn help_message() -> &'static str {
 <green,bold>Common options:</>
     <cyan,bold>--no-deps</>                Run Clippy only on the given crate, without linting the dependencies
     <cyan,bold>--fix</>                    Automatically apply lint suggestions. This flag implies <cyan>--no-deps</> and <cyan>--all-targets</>
+    <cyan,bold>--fix-only</> <cyan><<LINTS>></>       Like <cyan>--fix</>, but only apply the suggestions of the given comma separated lints
+    <cyan,bold>--diff</>[=<cyan><<PATH>></>]          With <cyan>--fix</>, print the fixes as a unified diff, or write them to <cyan><<PATH>></>,
+                             instead of modifying the files
+    <cyan,bold>--fix-maybe-incorrect</>    Apply suggestions which may be incorrect one lint at a time, reverting the
+                             ones which introduce new errors. This flag implies <cyan>--no-deps</>
+    <cyan,bold>--changed-since</> <cyan><<REV>></>    Only report lints on the lines changed since the given git revision
+    <cyan,bold>--changed-functions</>      With <cyan>--changed-since</>, also report lints in functions containing a changed line
+    <cyan,bold>--unused-allows</>[=expect] Report <cyan>#[allow]</> attributes for Clippy lints which don't suppress any lint,
+                             or with <cyan>=expect</> suggest converting them to <cyan>#[expect]</>
+    <cyan,bold>--load-lints</> <cyan><<PATH>></>      Load additional lints from the dynamic library at <cyan><<PATH>></>
+    <cyan,bold>--summary</>                Print the number of warnings of each lint and lint group after the run
+    <cyan,bold>--max-warnings</> <cyan><<LINT>>=<<N>></> Fail if a lint, a lint group or <cyan>warnings</> emitted more than <cyan><<N>></> warnings.
+                             Can be passed multiple times
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
     <cyan,bold>--explain [LINT]</>         Print the documentation for a given lint
@@ -185,6 +399,7 @@ You can use tool lints to allow or deny lints from your code, e.g.:
 #[cfg(test)]
 mod tests {
     use super::ClippyCmd;
+    use super::fix::DiffOutput;
 
     #[test]
     fn fix() {
@@ -210,6 +425,127 @@ mod tests {
         assert_eq!(cmd.clippy_args.iter().filter(|arg| *arg == "--no-deps").count(), 1);
     }
 
+    #[test]
+    fn fix_only() {
+        let args = "cargo clippy --fix-only uninlined_format_args,clippy::needless_return --all-targets"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("fix", cmd.cargo_subcommand);
+        assert!(cmd.args.iter().any(|arg| arg == "--all-targets"));
+        assert!(!cmd.args.iter().any(|arg| arg.contains("fix")));
+        assert!(
+            cmd.clippy_args
+                .iter()
+                .any(|arg| arg == "--fix-only=uninlined_format_args,clippy::needless_return")
+        );
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--no-deps"));
+
+        let args = "cargo clippy --fix-only=needless_return"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("fix", cmd.cargo_subcommand);
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--fix-only=needless_return"));
+    }
+
+    #[test]
+    fn fix_maybe_incorrect() {
+        let args = "cargo clippy --fix-maybe-incorrect"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert!(cmd.fix_maybe_incorrect);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(cmd.args.iter().any(|arg| arg == "--all-targets"));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--no-deps"));
+
+        let args = "cargo clippy --fix-maybe-incorrect --lib"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert!(!cmd.args.iter().any(|arg| arg == "--all-targets"));
+    }
+
+    #[test]
+    fn fix_diff() {
+        let args = "cargo clippy --fix --diff=fixes.patch"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(matches!(&cmd.diff, Some(DiffOutput::File(path)) if path.as_os_str() == "fixes.patch"));
+        assert!(cmd.args.iter().any(|arg| arg == "--all-targets"));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--no-deps"));
+
+        let args = "cargo clippy --fix-only needless_return --diff"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(matches!(cmd.diff, Some(DiffOutput::Stdout)));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--fix-only=needless_return"));
+    }
+
+    #[test]
+    fn changed_since() {
+        let args = "cargo clippy --changed-since origin/main --changed-functions -- -W clippy::pedantic"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(!cmd.args.iter().any(|arg| arg.starts_with("--changed")));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--changed-since=origin/main"));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--changed-functions"));
+    }
+
+    #[test]
+    fn unused_allows() {
+        let args = "cargo clippy --unused-allows=expect"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(!cmd.args.iter().any(|arg| arg.starts_with("--unused-allows")));
+        assert!(cmd.clippy_args.iter().any(|arg| arg == "--unused-allows=expect"));
+    }
+
+    #[test]
+    fn load_lints() {
+        let args = "cargo clippy --load-lints /lints/libextra_lints.so"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(!cmd.args.iter().any(|arg| arg.starts_with("--load-lints")));
+        assert!(
+            cmd.clippy_args
+                .iter()
+                .any(|arg| arg == "--load-lints=/lints/libextra_lints.so")
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let args = "cargo clippy --summary --max-warnings clippy::pedantic=10 --max-warnings=warnings=20 --lib"
+            .split_whitespace()
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args);
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(cmd.summary);
+        assert_eq!(
+            cmd.max_warnings,
+            [("clippy::pedantic".to_owned(), 10), ("warnings".to_owned(), 20)]
+        );
+        assert!(cmd.args.iter().any(|arg| arg == "--lib"));
+        assert!(
+            !cmd.args
+                .iter()
+                .any(|arg| arg.contains("warnings") || arg == "--summary")
+        );
+        assert!(cmd.clippy_args.is_empty());
+    }
+
     #[test]
     fn check() {
         let args = "cargo clippy".split_whitespace().map(ToString::to_string);
@@ -0,0 +1,253 @@
+//! Counting the warnings emitted by a run for `--summary` and `--max-warnings`.
+
+use crate::ClippyCmd;
+use anstream::{eprint, eprintln, println};
+use clippy_lints::declared_lints::LINTS;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+
+/// The lint groups included in `clippy::all`.
+const ALL_GROUPS: &[&str] = &["complexity", "correctness", "perf", "style", "suspicious"];
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    message: String,
+    code: Option<Code>,
+    level: String,
+    spans: Vec<Span>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Code {
+    code: String,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Hash)]
+struct Span {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// Converts a lint or group name passed to `--max-warnings` to the name used to count warnings,
+/// e.g. `needless-return` to `clippy::needless_return` and `pedantic` to `clippy::pedantic`.
+pub(crate) fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_ascii_lowercase().replace('-', "_");
+    let clippy_name = name.strip_prefix("clippy::").unwrap_or(&name);
+    if clippy_name == "all"
+        || group_names().any(|group| group == clippy_name)
+        || LINTS.iter().any(|lint| lint.name_lower() == clippy_name)
+    {
+        Ok(format!("clippy::{clippy_name}"))
+    } else if name.starts_with("clippy::") {
+        Err(format!("unknown lint or group `{name}`"))
+    } else {
+        Ok(name)
+    }
+}
+
+fn group_names() -> impl Iterator<Item = &'static str> {
+    LINTS.iter().map(|lint| lint.category.name())
+}
+
+/// Parses the value of `--max-warnings`, i.e. `<lint-or-group>=<n>`.
+pub(crate) fn parse_max_warnings(arg: &str) -> Result<(String, usize), String> {
+    let Some((name, max)) = arg.rsplit_once('=') else {
+        return Err(format!("`--max-warnings` expects `<lint-or-group>=<n>`, found `{arg}`"));
+    };
+    let max = max
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{max}` is not a valid number of warnings"))?;
+    Ok((normalize_name(name)?, max))
+}
+
+/// Gets the values passed to `--message-format`.
+pub(crate) fn message_formats(args: &[String]) -> impl Iterator<Item = &str> {
+    args.iter().enumerate().filter_map(|(i, arg)| {
+        if arg == "--message-format" {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            arg.strip_prefix("--message-format=")
+        }
+    })
+}
+
+/// The warnings emitted by a run, counted by lint and by group.
+#[derive(Default)]
+struct Counts {
+    total: usize,
+    lints: BTreeMap<String, usize>,
+    groups: BTreeMap<String, usize>,
+}
+
+impl Counts {
+    fn add(&mut self, lint: Option<&str>) {
+        self.total += 1;
+        let Some(lint) = lint else {
+            return;
+        };
+        *self.lints.entry(lint.to_owned()).or_default() += 1;
+        if let Some(name) = lint.strip_prefix("clippy::")
+            && let Some(info) = LINTS.iter().find(|info| info.name_lower() == name)
+        {
+            let group = info.category.name();
+            *self.groups.entry(format!("clippy::{group}")).or_default() += 1;
+            if ALL_GROUPS.contains(&group) {
+                *self.groups.entry("clippy::all".to_owned()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Gets the number of warnings emitted by a lint or group, as returned by `normalize_name`.
+    fn get(&self, name: &str) -> usize {
+        if name == "warnings" {
+            self.total
+        } else {
+            self.lints
+                .get(name)
+                .or_else(|| self.groups.get(name))
+                .copied()
+                .unwrap_or(0)
+        }
+    }
+
+    fn print(&self) {
+        eprintln!("\nWarnings summary: {} in total", self.total);
+        let width = self
+            .lints
+            .values()
+            .chain(self.groups.values())
+            .max()
+            .map_or(1, |count| count.to_string().len());
+        if !self.lints.is_empty() {
+            eprintln!("  by lint:");
+            let mut lints: Vec<_> = self.lints.iter().collect();
+            lints.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+            for (lint, count) in lints {
+                eprintln!("    {count:>width$}  {lint}");
+            }
+        }
+        if !self.groups.is_empty() {
+            eprintln!("  by group:");
+            for (group, count) in &self.groups {
+                eprintln!("    {count:>width$}  {group}");
+            }
+        }
+    }
+}
+
+/// Runs `cargo check` with Clippy, printing the diagnostics as usual while counting the emitted
+/// warnings. Afterwards the summary is printed and the warnings are checked against the maximums
+/// passed to `--max-warnings`.
+pub(crate) fn run(cmd: &ClippyCmd) -> Result<(), i32> {
+    // Diagnostics are forwarded untouched if JSON output was requested, and rendered otherwise.
+    let forward_json = message_formats(&cmd.args).next().is_some();
+    let mut std_cmd = cmd.to_std_cmd();
+    if !forward_json {
+        std_cmd.arg("--message-format=json-diagnostic-rendered-ansi");
+    }
+    let mut child = std_cmd.stdout(Stdio::piped()).spawn().expect("could not run cargo");
+
+    let mut counts = Counts::default();
+    // The same diagnostic is emitted once per target when checking e.g. a library and its tests.
+    let mut seen = HashSet::new();
+    let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    for line in stdout.lines() {
+        let line = line.expect("failed to read the output of cargo");
+        let message = match serde_json::from_str(&line) {
+            Ok(CargoMessage {
+                reason,
+                message: Some(message),
+            }) if reason == "compiler-message" => message,
+            Ok(_) if !forward_json => continue,
+            _ => {
+                println!("{line}");
+                continue;
+            },
+        };
+        if forward_json {
+            println!("{line}");
+        } else if let Some(rendered) = &message.rendered {
+            eprint!("{rendered}");
+        }
+        if message.level == "warning" {
+            let lint_name = message.code.as_ref().map(|code| code.code.as_str());
+            let span = message.spans.into_iter().find(|span| span.is_primary);
+            if seen.insert((lint_name.map(ToOwned::to_owned), message.message, span)) {
+                counts.add(lint_name);
+            }
+        }
+    }
+
+    let exit_status = child.wait().expect("failed to wait for cargo?");
+    if cmd.summary {
+        counts.print();
+    }
+    if !exit_status.success() {
+        return Err(exit_status.code().unwrap_or(-1));
+    }
+
+    let mut exceeded = false;
+    for (name, max) in &cmd.max_warnings {
+        let count = counts.get(name);
+        if count > *max {
+            eprintln!("error: `{name}` emitted {count} warnings, more than the maximum of {max}");
+            exceeded = true;
+        }
+    }
+    if exceeded { Err(1) } else { Ok(()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counts, normalize_name, parse_max_warnings};
+
+    #[test]
+    fn max_warnings() {
+        assert_eq!(
+            parse_max_warnings("clippy::pedantic=10"),
+            Ok(("clippy::pedantic".into(), 10))
+        );
+        assert_eq!(parse_max_warnings("pedantic=0"), Ok(("clippy::pedantic".into(), 0)));
+        assert_eq!(
+            parse_max_warnings("needless-return=2"),
+            Ok(("clippy::needless_return".into(), 2))
+        );
+        assert_eq!(parse_max_warnings("warnings=5"), Ok(("warnings".into(), 5)));
+        assert_eq!(
+            parse_max_warnings("unused_variables=1"),
+            Ok(("unused_variables".into(), 1))
+        );
+        assert!(parse_max_warnings("clippy::pedantic").is_err());
+        assert!(parse_max_warnings("clippy::pedantic=many").is_err());
+        assert!(normalize_name("clippy::not_a_lint").is_err());
+    }
+
+    #[test]
+    fn counts() {
+        let mut counts = Counts::default();
+        counts.add(Some("clippy::needless_return"));
+        counts.add(Some("clippy::needless_return"));
+        counts.add(Some("clippy::must_use_candidate"));
+        counts.add(Some("unused_variables"));
+        counts.add(None);
+        assert_eq!(counts.get("warnings"), 5);
+        assert_eq!(counts.get("clippy::needless_return"), 2);
+        assert_eq!(counts.get("clippy::style"), 2);
+        assert_eq!(counts.get("clippy::all"), 2);
+        assert_eq!(counts.get("clippy::pedantic"), 1);
+        assert_eq!(counts.get("unused_variables"), 1);
+        assert_eq!(counts.get("clippy::perf"), 0);
+    }
+}
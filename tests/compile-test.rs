@@ -339,6 +339,7 @@ fn run_ui_cargo(cx: &TestContext) {
 fn main() {
     unsafe {
         set_var("CLIPPY_DISABLE_DOCS_LINKS", "true");
+        set_var("CLIPPY_DISABLE_EXPECT_SUGGESTIONS", "true");
     }
 
     let cx = TestContext::new();
@@ -508,6 +509,22 @@ impl DiagnosticCollector {
                 }
             }
 
+            // Applicabilities are cached so that a run filtered with `TESTNAME`, as done by
+            // `cargo dev serve`, only has to run the tests of the lints that changed. The tests of a
+            // lint aren't always named after it, so the cache is merged by the names of the lints
+            // emitted in this run rather than by the filters.
+            let cache_path = PathBuf::from(var_os("CARGO_TARGET_DIR").unwrap_or_else(|| "target".into()))
+                .join("lint_applicabilities.json");
+            if env::var_os("TESTNAME").is_some()
+                && let Ok(cache) = fs::read(&cache_path)
+                && let Ok(cache) = serde_json::from_slice::<HashMap<String, Applicability>>(&cache)
+            {
+                for (lint, applicability) in cache {
+                    applicabilities.entry(lint).or_insert(applicability);
+                }
+            }
+            fs::write(&cache_path, serde_json::to_vec(&applicabilities).unwrap()).unwrap();
+
             let configs = clippy_config::get_configuration_metadata();
             let mut metadata: Vec<LintMetadata> = LINTS
                 .iter()
@@ -10,7 +10,6 @@ fn metadata() -> impl Iterator<Item = ClippyConfiguration> {
     get_configuration_metadata()
         .into_iter()
         .filter(|config| config.deprecation_reason.is_none())
-        .filter(|config| !config.lints.is_empty())
 }
 
 #[test]
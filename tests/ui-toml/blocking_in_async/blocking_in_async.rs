@@ -0,0 +1,36 @@
+//@no-rustfix
+#![warn(clippy::blocking_in_async)]
+
+mod db {
+    pub fn connect() {}
+    pub fn query(_: &str) {}
+    pub fn fetch() {}
+    pub async fn fetch_async() {}
+}
+
+mod pool {
+    pub fn run<T>(f: impl FnOnce() -> T) -> T {
+        f()
+    }
+}
+
+async fn handler() {
+    db::connect();
+    //~^ blocking_in_async
+    db::query("SELECT 1");
+    //~^ blocking_in_async
+    db::fetch();
+    //~^ blocking_in_async
+
+    // The default list is still checked.
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    //~^ blocking_in_async
+}
+
+async fn wrapped() {
+    pool::run(|| db::query("SELECT 1"));
+    pool::run(|| std::fs::read("data.bin")).unwrap();
+    pool::run(db::connect);
+}
+
+fn main() {}
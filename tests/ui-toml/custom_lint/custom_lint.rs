@@ -0,0 +1,50 @@
+#![allow(clippy::mem_forget)]
+
+use std::sync::{Mutex, MutexGuard};
+
+pub struct SharedState(Mutex<u32>);
+
+impl SharedState {
+    pub fn lock(&self) -> std::sync::LockResult<MutexGuard<'_, u32>> {
+        self.0.lock()
+    }
+
+    pub fn lock_infallible(&self) -> MutexGuard<'_, u32> {
+        self.0.lock().unwrap()
+    }
+}
+
+fn states() -> [SharedState; 1] {
+    [SharedState(Mutex::new(0))]
+}
+
+fn main() {
+    let state = SharedState(Mutex::new(0));
+    let _guard = state.lock().unwrap();
+    //~^ shared_state_lock_unwrap
+    drop(_guard);
+    let state = &state;
+    let _guard = state.lock().unwrap();
+    //~^ shared_state_lock_unwrap
+    drop(_guard);
+    let _ = *states()[0].lock().unwrap();
+    //~^ shared_state_lock_unwrap
+
+    // Not a `SharedState`
+    let mutex = Mutex::new(0);
+    let _guard = mutex.lock().unwrap();
+    drop(_guard);
+
+    #[allow(clippy::shared_state_lock_unwrap)]
+    let _guard = state.lock().unwrap();
+
+    macro_rules! lock {
+        ($e:expr) => {
+            $e.lock().unwrap()
+        };
+    }
+    let _ = *lock!(state);
+
+    std::mem::forget(String::new());
+    //~^ std_mem_forget
+}
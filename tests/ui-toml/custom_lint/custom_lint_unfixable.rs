@@ -0,0 +1,26 @@
+//@no-rustfix
+#![allow(clippy::eq_op)]
+
+fn main() {
+    let x = 1;
+    let _ = x == x;
+    //~^ self_comparison
+    let _ = x + 1 == x + 1;
+    //~^ self_comparison
+    let _ = x == 1;
+    let _ = x + 1 == x + 2;
+
+    let a = 1u8;
+    let _ = a + a;
+    //~^ double_add_u8
+    let b = 1u16;
+    let _ = b + b;
+    let _ = a + 1;
+
+    let pairs = [(1u8, "a")];
+    let _ = pairs[..].first();
+    //~^ first_entry
+    let _ = pairs.first();
+    let _ = [(1u16, "a")][..].first();
+    let _ = [(1u8, 'a')][..].first();
+}
@@ -0,0 +1,6 @@
+//@error-in-other-file: custom lint `needless_return` has the same name as another lint
+//@error-in-other-file: custom lint `blacklisted_name` has the same name as another lint
+//@error-in-other-file: custom lint `lock_unwrap` has the same name as another lint
+//@error-in-other-file: `Vec<u8>` used by custom lint `vec_len` does not refer to a reachable type
+
+fn main() {}
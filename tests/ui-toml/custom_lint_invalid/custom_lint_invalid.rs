@@ -0,0 +1,3 @@
+//@error-in-other-file: custom lint `lock_unwrap` uses `$y` which isn't in its pattern
+
+fn main() {}
@@ -0,0 +1,26 @@
+//@no-rustfix
+#![warn(clippy::detached_join_handles)]
+
+mod tasks {
+    pub struct TaskHandle;
+
+    impl TaskHandle {
+        pub fn wait(self) {}
+    }
+
+    pub fn spawn() -> TaskHandle {
+        TaskHandle
+    }
+}
+
+fn main() {
+    tasks::spawn();
+    //~^ detached_join_handles
+
+    let handle = tasks::spawn();
+    handle.wait();
+
+    // The default list is still checked.
+    std::thread::spawn(|| {});
+    //~^ detached_join_handles
+}
@@ -0,0 +1,13 @@
+#![warn(clippy::hardcoded_secret)]
+
+const SIGNING_KEY: &str = "5d41402abc4b2a76";
+//~^ hardcoded_secret
+const API_KEY: &str = "a8f5f167f44f4964e6c998dee827110c";
+//~^ hardcoded_secret
+
+const PASSWORD: &str = "<replace me>";
+const TOKEN: &str = "dev-token-local";
+const SECRET: &str = "dev-token-remote";
+//~^ hardcoded_secret
+
+fn main() {}
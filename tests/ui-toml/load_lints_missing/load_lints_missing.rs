@@ -0,0 +1,3 @@
+//@error-in-other-file: couldn't load lints from
+
+fn main() {}
@@ -0,0 +1,136 @@
+//@no-rustfix
+#![warn(clippy::missing_panics_doc)]
+
+fn main() {}
+
+fn checked(x: Option<u32>) -> u32 {
+    x.expect("no value")
+}
+
+fn indirect(x: Option<u32>) -> u32 {
+    checked(x)
+}
+
+fn no_panic(x: Option<u32>) -> u32 {
+    x.unwrap_or_default()
+}
+
+/// Calls a function which panics
+pub fn calls_checked(x: Option<u32>) -> u32 {
+    //~^ missing_panics_doc
+    checked(x)
+}
+
+/// Calls a function which calls a function which panics
+pub fn calls_indirect(x: Option<u32>) -> u32 {
+    //~^ missing_panics_doc
+    indirect(x)
+}
+
+/// # Panics
+///
+/// Panics if `x` is `None`.
+pub fn documented(x: Option<u32>) -> u32 {
+    checked(x)
+}
+
+/// Doesn't need a `# Panics` section
+pub fn calls_no_panic(x: Option<u32>) -> u32 {
+    no_panic(x)
+}
+
+pub struct Counter(Option<u32>);
+
+impl Counter {
+    fn get(&self) -> u32 {
+        self.0.unwrap()
+    }
+
+    /// Calls a method which panics
+    pub fn value(&self) -> u32 {
+        //~^ missing_panics_doc
+        self.get()
+    }
+}
+
+pub trait Value {
+    fn value(&self) -> u32;
+}
+
+impl Value for Option<u32> {
+    fn value(&self) -> u32 {
+        self.unwrap()
+    }
+}
+
+/// Calls a trait method whose implementation panics
+pub fn calls_trait_method(x: Option<u32>) -> u32 {
+    //~^ missing_panics_doc
+    x.value()
+}
+
+/// The panic isn't reachable through a generic trait method
+pub fn generic<T: Value>(x: &T) -> u32 {
+    x.value()
+}
+
+fn even(n: u32) -> bool {
+    if n == 0 { true } else { odd(n - 1) }
+}
+
+fn odd(n: u32) -> bool {
+    if n == 0 { panic!("odd") } else { even(n - 1) }
+}
+
+/// Recursion through a function which panics
+pub fn is_even(n: u32) -> bool {
+    //~^ missing_panics_doc
+    even(n)
+}
+
+fn loud(n: u32) -> bool {
+    if n == 0 { panic!("loud") } else { quiet(n - 1) }
+}
+
+fn quiet(n: u32) -> bool {
+    n != 0 && loud(n)
+}
+
+/// Recursion through a function which panics
+pub fn calls_loud(n: u32) -> bool {
+    //~^ missing_panics_doc
+    loud(n)
+}
+
+/// Recursion into a function which panics, after the cycle has been analyzed
+pub fn calls_quiet(n: u32) -> bool {
+    //~^ missing_panics_doc
+    quiet(n)
+}
+
+fn ping(done: bool) -> bool {
+    done || pong(!done)
+}
+
+fn pong(done: bool) -> bool {
+    done || ping(!done) || pang(done)
+}
+
+fn pang(done: bool) -> bool {
+    !done && ping(done) && pong(done)
+}
+
+/// Recursion through functions which don't panic
+pub fn play(done: bool) -> bool {
+    ping(done) && pang(done)
+}
+
+#[expect(clippy::missing_panics_doc)]
+fn allowed(x: Option<u32>) -> u32 {
+    x.unwrap()
+}
+
+/// The panic in the callee is intentionally undocumented
+pub fn calls_allowed(x: Option<u32>) -> u32 {
+    allowed(x)
+}
@@ -0,0 +1,118 @@
+//@no-rustfix
+#![warn(clippy::sql_injection)]
+
+mod request {
+    pub fn param(_name: &str) -> String {
+        String::new()
+    }
+}
+
+struct Connection;
+
+impl Connection {
+    fn query(&self, _sql: &str) {}
+}
+
+fn execute(_sql: String) {}
+
+struct Client;
+
+impl Client {
+    fn connect(_url: &str) -> Result<Self, ()> {
+        Ok(Self)
+    }
+
+    fn execute(&self, _sql: &str, _params: &[&String]) {}
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
+fn formatted(conn: &Connection) {
+    let name = request::param("name");
+    let sql = format!("SELECT * FROM users WHERE name = '{name}'");
+    conn.query(&sql);
+    //~^ sql_injection
+}
+
+fn from_env(conn: &Connection) {
+    let id = std::env::var("USER_ID").unwrap();
+    conn.query(&format!("SELECT * FROM users WHERE id = {id}"));
+    //~^ sql_injection
+}
+
+fn pushed() {
+    let mut sql = String::from("DELETE FROM users WHERE name = '");
+    sql.push_str(&request::param("name"));
+    sql.push('\'');
+    execute(sql);
+    //~^ sql_injection
+}
+
+fn added() {
+    execute(String::from("SELECT * FROM users WHERE name = '") + &request::param("name") + "'");
+    //~^ sql_injection
+}
+
+fn read_line(conn: &Connection) {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).unwrap();
+    let sql = ["SELECT * FROM users WHERE name = '", line.trim(), "'"].concat();
+    conn.query(&sql);
+    //~^ sql_injection
+}
+
+fn through_struct(conn: &Connection) {
+    struct Filter {
+        name: String,
+    }
+    let filter = Filter {
+        name: request::param("name"),
+    };
+    conn.query(&format!("SELECT * FROM users WHERE name = '{}'", filter.name));
+    //~^ sql_injection
+}
+
+fn sanitized(conn: &Connection) {
+    let name = escape(&request::param("name"));
+    conn.query(&format!("SELECT * FROM users WHERE name = '{name}'"));
+}
+
+fn constant(conn: &Connection) {
+    let table = "users";
+    conn.query(&format!("SELECT * FROM {table}"));
+    execute(String::from("SELECT 1"));
+}
+
+fn parameterized(client: &Client) {
+    let name = request::param("name");
+    client.execute("SELECT * FROM users WHERE name = $1", &[&name]);
+}
+
+fn client_from_env() -> Result<(), std::env::VarError> {
+    let client = Client::connect(&std::env::var("DATABASE_URL")?).unwrap();
+    client.execute("SELECT * FROM users", &[]);
+    Ok(())
+}
+
+fn not_built(conn: &Connection) {
+    // The whole query comes from the source, there is nothing to parameterize.
+    let sql = request::param("sql");
+    conn.query(&sql);
+}
+
+fn unrelated(conn: &Connection) {
+    let name = request::param("name");
+    println!("{name}");
+    conn.query("SELECT * FROM users");
+}
+
+async fn in_async(conn: &Connection) {
+    let name = request::param("name");
+    async {}.await;
+    conn.query(&format!("SELECT * FROM users WHERE name = '{name}'"));
+    //~^ sql_injection
+}
+
+fn main() {}
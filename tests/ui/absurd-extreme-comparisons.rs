@@ -96,3 +96,21 @@ pub fn bar(len: u64) -> bool {
     // This is OK as we are casting from target sized to fixed size
     len >= usize::MAX as u64
 }
+
+const fn max_u8() -> u8 {
+    u8::MAX
+}
+
+pub fn const_fn_bound(x: u8) -> bool {
+    x > max_u8()
+    //~^ absurd_extreme_comparisons
+}
+
+const fn limit() -> u8 {
+    if cfg!(debug_assertions) { u8::MAX } else { 100 }
+}
+
+pub fn cfg_const_fn_bound(x: u8) -> bool {
+    // Ok, the bound depends on the configuration
+    x > limit()
+}
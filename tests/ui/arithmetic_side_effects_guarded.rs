@@ -0,0 +1,60 @@
+//@no-rustfix
+#![warn(clippy::arithmetic_side_effects)]
+
+fn add(x: u32, y: u32) {
+    if x < 1000 && y < 1000 {
+        let _ = x + y;
+        let _ = x * y;
+    }
+    if x > 10 {
+        let _ = x - 1;
+    }
+    assert!(y != 0 && y > 0);
+    let _ = x / y;
+    let _ = x % y;
+}
+
+fn signed(x: i32, y: i32) {
+    if (-100..100).contains(&x) {
+        let _ = x + 1;
+        //~^ arithmetic_side_effects
+    }
+    if x > -100 && x < 100 {
+        let _ = x + 1;
+        let _ = -x;
+        //~^ arithmetic_side_effects
+    }
+    if y > 0 {
+        let _ = x / y;
+    }
+    if y < 0 {
+        let _ = x / y;
+        //~^ arithmetic_side_effects
+    }
+}
+
+fn loops(v: &[u32]) {
+    for i in 1..v.len() {
+        let _ = i - 1;
+    }
+    // `i < v.len() <= usize::MAX`
+    for i in 0..v.len() {
+        let _ = i + 1;
+    }
+}
+
+fn not_guarded(x: u32, mut y: u32) {
+    let _ = x + 1;
+    //~^ arithmetic_side_effects
+    if x > 0 {
+        let _ = x + 1;
+        //~^ arithmetic_side_effects
+    }
+    if y < 10 {
+        y = x;
+        let _ = y + 1;
+        //~^ arithmetic_side_effects
+    }
+}
+
+fn main() {}
@@ -96,3 +96,18 @@ fn _f4() {
     assert!(C);
     //~^ assertions_on_constants
 }
+
+const fn enabled() -> bool {
+    true
+}
+
+const fn debug_enabled() -> bool {
+    cfg!(debug_assertions)
+}
+
+fn _f5() {
+    // Ok, these check the implementation of the function or depend on the target
+    assert!(enabled());
+    assert!(!debug_enabled());
+    assert!(std::mem::size_of::<usize>() == 8);
+}
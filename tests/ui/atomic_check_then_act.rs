@@ -0,0 +1,130 @@
+//@no-rustfix
+#![warn(clippy::atomic_check_then_act)]
+#![allow(clippy::collapsible_if)]
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+static PENDING: AtomicBool = AtomicBool::new(false);
+
+struct Pool {
+    available: AtomicUsize,
+    closed: Arc<AtomicBool>,
+}
+
+impl Pool {
+    fn take(&self) -> bool {
+        if self.available.load(Ordering::Acquire) > 0 {
+            self.available.fetch_sub(1, Ordering::AcqRel);
+            //~^ atomic_check_then_act
+            true
+        } else {
+            false
+        }
+    }
+
+    fn close(&self) {
+        if !self.closed.load(Ordering::Relaxed) {
+            self.closed.store(true, Ordering::Relaxed);
+            //~^ atomic_check_then_act
+        }
+    }
+
+    fn other_atomic(&self) {
+        if self.closed.load(Ordering::Relaxed) {
+            self.available.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn with_local(&self) {
+        let available = self.available.load(Ordering::Relaxed);
+        if available == 0 {
+            self.available.store(10, Ordering::Relaxed);
+            //~^ atomic_check_then_act
+        }
+    }
+
+    fn with_match(&self) {
+        match self.available.load(Ordering::Relaxed) {
+            0 => {},
+            n => {
+                self.available.store(n - 1, Ordering::Relaxed);
+                //~^ atomic_check_then_act
+            },
+        }
+    }
+
+    fn atomic_update(&self) {
+        let _ = self
+            .available
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+        if self.available.load(Ordering::Relaxed) == 0 {
+            let _ = self
+                .available
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_add(10));
+        }
+    }
+}
+
+fn run_pending_job() {}
+
+fn flag() {
+    if PENDING.load(Ordering::Acquire) {
+        PENDING.store(false, Ordering::Release);
+        //~^ atomic_check_then_act
+        run_pending_job();
+    }
+
+    if PENDING.swap(false, Ordering::AcqRel) {
+        run_pending_job();
+    }
+
+    // Only linted once.
+    if PENDING.load(Ordering::Acquire) {
+        if PENDING.load(Ordering::Acquire) {
+            PENDING.store(false, Ordering::Release);
+            //~^ atomic_check_then_act
+        }
+    }
+}
+
+fn different_locals(a: &AtomicBool, b: &AtomicBool) {
+    if a.load(Ordering::Relaxed) {
+        b.store(false, Ordering::Relaxed);
+    }
+    if a.load(Ordering::Relaxed) {
+        a.store(false, Ordering::Relaxed);
+        //~^ atomic_check_then_act
+    }
+}
+
+static INIT: AtomicBool = AtomicBool::new(false);
+static INIT_LOCK: Mutex<()> = Mutex::new(());
+
+fn double_checked_locking() {
+    if !INIT.load(Ordering::Acquire) {
+        let _guard = INIT_LOCK.lock().unwrap();
+        if !INIT.load(Ordering::Relaxed) {
+            INIT.store(true, Ordering::Release);
+        }
+    }
+}
+
+fn under_lock() {
+    let _guard = INIT_LOCK.lock().unwrap();
+    if !INIT.load(Ordering::Relaxed) {
+        INIT.store(true, Ordering::Relaxed);
+    }
+}
+
+fn lock_released() {
+    if !INIT.load(Ordering::Relaxed) {
+        {
+            let _guard = INIT_LOCK.lock().unwrap();
+        }
+        INIT.store(true, Ordering::Relaxed);
+        //~^ atomic_check_then_act
+    }
+}
+
+fn main() {}
@@ -0,0 +1,57 @@
+//@ check-pass
+
+#![allow(clippy::needless_return)]
+
+#[clippy::author]
+fn answer() -> u32 {
+    return 42;
+}
+
+#[clippy::author]
+fn empty() -> String {
+    String::new()
+}
+
+#[clippy::author]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[clippy::author]
+enum Shape {
+    Circle(u32),
+    Empty,
+}
+
+trait Area {
+    #[clippy::author]
+    fn area(&self) -> u32;
+}
+
+impl Area for Shape {
+    fn area(&self) -> u32 {
+        0
+    }
+}
+
+#[clippy::author]
+impl Clone for Point {
+    #[clippy::author]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for Point {}
+
+fn first((a, _): (u32, u32), #[clippy::author] [b, ..]: [u32; 2]) -> u32 {
+    a + b
+}
+
+fn main() {
+    let _ = answer();
+    let _ = empty();
+    let _ = first((1, 2), [3, 4]);
+    let _ = |#[clippy::author] x: &u8| *x;
+}
@@ -0,0 +1,57 @@
+//@no-rustfix
+#![warn(clippy::blocking_in_async)]
+#![allow(clippy::manual_async_fn)]
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+async fn read_file() -> std::io::Result<Vec<u8>> {
+    std::fs::read("data.bin")
+    //~^ blocking_in_async
+}
+
+async fn sleep_and_lock(m: &Mutex<u32>) {
+    std::thread::sleep(Duration::from_millis(10));
+    //~^ blocking_in_async
+    *m.lock().unwrap() += 1;
+    //~^ blocking_in_async
+}
+
+fn async_block() -> impl Future<Output = String> {
+    async {
+        std::fs::read_to_string("data.txt").unwrap()
+        //~^ blocking_in_async
+    }
+}
+
+async fn closure_in_async(paths: &[&str]) -> Vec<Vec<u8>> {
+    paths.iter().map(|p| std::fs::read(p).unwrap()).collect()
+    //~^ blocking_in_async
+}
+
+async fn async_closure() {
+    let f = async || std::thread::sleep(Duration::from_millis(10));
+    //~^ blocking_in_async
+    f().await;
+}
+
+async fn spawned() {
+    // Runs on a separate thread.
+    let handle = std::thread::spawn(|| std::fs::read("data.bin"));
+    let _ = handle.join();
+    //~^ blocking_in_async
+}
+
+async fn nested_fn() {
+    // Not called from the async body.
+    fn load() -> Vec<u8> {
+        std::fs::read("data.bin").unwrap()
+    }
+}
+
+fn not_async() {
+    std::thread::sleep(Duration::from_millis(10));
+    let _ = std::fs::read("data.bin");
+}
+
+fn main() {}
@@ -0,0 +1,97 @@
+//@no-rustfix
+#![warn(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+#![allow(clippy::manual_range_contains)]
+
+fn if_guard(x: u32, y: i32) {
+    if x < 256 {
+        let _ = x as u8;
+    }
+    if x <= 255 && y >= 0 {
+        let _ = x as u8;
+        let _ = y as u32;
+    }
+    if x > 255 {
+        return;
+    }
+    let _ = x as u8;
+}
+
+fn if_else_guard(x: u32) -> u8 {
+    if x >= 256 { 0 } else { x as u8 }
+}
+
+fn assert_guard(x: u64, y: i64) {
+    assert!(x < 1000);
+    assert!(y > -1);
+    let _ = x as u16;
+    let _ = y as u64;
+    let _ = (x / 4) as u8;
+    let _ = (x + 1) as u16;
+}
+
+fn match_guard(x: i32) {
+    match x {
+        0..=255 => {
+            let _ = x as u8;
+        },
+        256 | 257 => {
+            let _ = x as u16;
+        },
+        n @ 1000..2000 => {
+            let _ = n as u16;
+        },
+        n if n > 0 => {
+            let _ = n as u32;
+        },
+        _ => {},
+    }
+    if let 0..=9 = x {
+        let _ = x as u8;
+    }
+}
+
+fn loop_guard(n: usize) {
+    for i in 0..100usize {
+        let _ = i as u8;
+    }
+    for i in 0..n.min(255) {
+        let _ = i as u8;
+    }
+}
+
+fn expr_range(x: u32, y: i64) {
+    if x < 1000 && y > -10 && y < 1000 {
+        let _ = (x / 4) as u8;
+        let _ = (x % 200) as u8;
+        let _ = x.min(100) as u8;
+        let _ = (y + 10) as u64;
+        let _ = y.clamp(0, 100) as u8;
+    }
+}
+
+fn not_guarded(x: u32, mut y: u32, z: i32) {
+    let _ = x as u8;
+    //~^ cast_possible_truncation
+    if x < 257 {
+        let _ = x as u8;
+        //~^ cast_possible_truncation
+    }
+    if x < 256 || y < 256 {
+        let _ = x as u8;
+        //~^ cast_possible_truncation
+    }
+    if y < 256 {
+        y += 1;
+        let _ = y as u8;
+        //~^ cast_possible_truncation
+    }
+    if z > -1 {
+        let _ = (z - 1) as u32;
+        //~^ cast_sign_loss
+    }
+    debug_assert!(x < 256);
+    let _ = x as u8;
+    //~^ cast_possible_truncation
+}
+
+fn main() {}
@@ -0,0 +1,67 @@
+//@no-rustfix
+#![warn(clippy::detached_join_handles)]
+
+use std::thread;
+
+fn work() -> u32 {
+    42
+}
+
+fn main() {
+    thread::spawn(work);
+    //~^ detached_join_handles
+
+    let _ = thread::spawn(work);
+    //~^ detached_join_handles
+
+    thread::Builder::new().spawn(work).unwrap();
+    //~^ detached_join_handles
+
+    {
+        let handle = thread::spawn(work);
+        //~^ detached_join_handles
+        println!("{}", handle.is_finished());
+        handle.thread().unpark();
+    }
+
+    {
+        let handle = thread::spawn(work);
+        //~^ detached_join_handles
+        if work() > 1 {
+            handle.join().unwrap();
+        }
+    }
+
+    {
+        let handle = thread::spawn(work);
+        handle.join().unwrap();
+    }
+
+    {
+        let mut handles = Vec::new();
+        handles.push(thread::spawn(work));
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    {
+        // Scoped threads are joined at the end of the scope.
+        thread::scope(|s| {
+            s.spawn(work);
+        });
+    }
+}
+
+fn returned() -> thread::JoinHandle<u32> {
+    thread::spawn(work)
+}
+
+fn early_return() {
+    let handle = thread::spawn(work);
+    //~^ detached_join_handles
+    if work() > 1 {
+        return;
+    }
+    handle.join().unwrap();
+}
@@ -0,0 +1,37 @@
+//@rustc-env:CLIPPY_DISABLE_EXPECT_SUGGESTIONS=0
+#![warn(clippy::eq_op, clippy::too_many_arguments, clippy::precedence)]
+
+fn statement() {
+    let _ = 1 == 1;
+    //~^ eq_op
+
+    if true {
+        let _ = 2 == 2;
+        //~^ eq_op
+    }
+}
+
+fn tail() -> bool {
+    1 == 1
+    //~^ eq_op
+}
+
+fn too_many(_: u8, _: u8, _: u8, _: u8, _: u8, _: u8, _: u8, _: u8) {}
+//~^ too_many_arguments
+
+struct S;
+
+impl S {
+    fn method(&self) -> bool {
+        1 == 1
+        //~^ eq_op
+    }
+}
+
+// Early lints don't get the suggestion
+fn early() -> i32 {
+    1 << 2 + 3
+    //~^ precedence
+}
+
+fn main() {}
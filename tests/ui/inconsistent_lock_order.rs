@@ -0,0 +1,130 @@
+//@no-rustfix
+#![warn(clippy::inconsistent_lock_order)]
+#![allow(clippy::await_holding_lock)]
+
+use std::sync::{Arc, Mutex, RwLock};
+
+struct Bank {
+    accounts: Mutex<Vec<u64>>,
+    log: Mutex<Vec<String>>,
+}
+
+impl Bank {
+    fn deposit(&self, amount: u64) {
+        let mut accounts = self.accounts.lock().unwrap();
+        let mut log = self.log.lock().unwrap();
+        //~^ inconsistent_lock_order
+        accounts[0] += amount;
+        log.push(String::new());
+    }
+
+    fn audit(&self) {
+        let log = self.log.lock().unwrap();
+        let accounts = self.accounts.lock().unwrap();
+        println!("{} {}", log.len(), accounts.len());
+    }
+
+    fn same_order(&self) {
+        let accounts = self.accounts.lock().unwrap();
+        let log = self.log.lock().unwrap();
+        println!("{} {}", log.len(), accounts.len());
+    }
+
+    fn dropped_before(&self) {
+        let log = self.log.lock().unwrap();
+        drop(log);
+        let accounts = self.accounts.lock().unwrap();
+        println!("{}", accounts.len());
+    }
+
+    fn temporary(&self) {
+        let len = self.log.lock().unwrap().len();
+        let accounts = self.accounts.lock().unwrap();
+        println!("{len} {}", accounts.len());
+    }
+
+    fn scoped(&self) {
+        {
+            let log = self.log.lock().unwrap();
+            println!("{}", log.len());
+        }
+        let accounts = self.accounts.lock().unwrap();
+        println!("{}", accounts.len());
+    }
+
+    fn if_condition(&self) {
+        if self.log.lock().unwrap().is_empty() {
+            let accounts = self.accounts.lock().unwrap();
+            println!("{}", accounts.len());
+        }
+    }
+}
+
+struct Inner {
+    a: RwLock<u32>,
+    b: RwLock<u32>,
+    c: RwLock<u32>,
+}
+
+struct Shared {
+    inner: Arc<Inner>,
+}
+
+impl Shared {
+    fn a_then_b(&self) {
+        let a = self.inner.a.read().unwrap();
+        let mut b = self.inner.b.write().unwrap();
+        //~^ inconsistent_lock_order
+        *b += *a;
+    }
+
+    fn b_then_c(&self) {
+        let _b = self.inner.b.read().unwrap();
+        let _c = self.inner.c.read().unwrap();
+    }
+
+    fn c_then_a(&self) {
+        let _c = self.inner.c.read().unwrap();
+        let _a = self.inner.a.read().unwrap();
+    }
+}
+
+static FIRST: Mutex<u8> = Mutex::new(0);
+static SECOND: Mutex<u8> = Mutex::new(0);
+
+fn statics() {
+    let _first = FIRST.lock().unwrap();
+    let _second = SECOND.lock().unwrap();
+    //~^ inconsistent_lock_order
+}
+
+fn statics_reversed() {
+    let _second = SECOND.lock().unwrap();
+    let _first = FIRST.lock().unwrap();
+}
+
+fn temporaries_in_one_statement() {
+    // Both guards are alive until the end of the statement.
+    let _ = *SECOND.lock().unwrap() == *FIRST.lock().unwrap();
+}
+
+struct Local {
+    x: Mutex<u8>,
+    y: Mutex<u8>,
+}
+
+fn local_x_then_y(l: &Local) {
+    let x = l.x.lock().unwrap();
+    let y = l.y.lock().unwrap();
+    println!("{} {}", *x, *y);
+}
+
+fn local_y_then_x_in_closure(l: &Local) {
+    let y = l.y.lock().unwrap();
+    // The closure isn't called while `y` is held.
+    let f = || *l.x.lock().unwrap();
+    drop(y);
+    f();
+}
+
+fn main() {}
@@ -0,0 +1,90 @@
+//@no-rustfix
+#![warn(clippy::indexing_slicing)]
+#![allow(
+    clippy::needless_range_loop,
+    clippy::no_effect,
+    clippy::single_match,
+    clippy::unnecessary_operation
+)]
+
+fn index_guard(v: &[u8], i: usize, j: usize) {
+    if i < v.len() {
+        let _ = v[i];
+    }
+    if v.len() > i && j < 4 {
+        let _ = v[i];
+    }
+    assert!(j < v.len());
+    let _ = v[j];
+}
+
+fn early_exit(v: Vec<u8>, i: usize) -> u8 {
+    if i >= v.len() {
+        return 0;
+    }
+    v[i]
+}
+
+fn loops(v: &[u8], arr: [u8; 8]) {
+    for i in 0..v.len() {
+        let _ = v[i];
+    }
+    for i in 0..8 {
+        let _ = arr[i];
+    }
+    let mut i = 0;
+    while i < v.len() {
+        let _ = v[i];
+        //~^ indexing_slicing
+        i += 1;
+    }
+}
+
+fn array_bounds(arr: [u8; 16], i: usize) {
+    if i < 16 {
+        let _ = arr[i];
+    }
+    match i {
+        0..16 => {
+            let _ = arr[i];
+        },
+        _ => {},
+    }
+}
+
+fn slicing(v: &[u8], n: usize, m: usize) {
+    if n <= v.len() {
+        let _ = &v[..n];
+        let _ = &v[n..];
+    }
+    if n < v.len() {
+        let _ = &v[..=n];
+        let _ = &v[0..n];
+    }
+    let _ = &v[..n];
+    //~^ indexing_slicing
+    if n <= v.len() && m <= n {
+        let _ = &v[m..n];
+        //~^ indexing_slicing
+    }
+}
+
+fn not_guarded(v: &[u8], mut w: Vec<u8>, i: usize) {
+    let _ = v[i];
+    //~^ indexing_slicing
+    if i <= v.len() {
+        let _ = v[i];
+        //~^ indexing_slicing
+    }
+    if i < w.len() {
+        w.clear();
+        let _ = w[i];
+        //~^ indexing_slicing
+    }
+    if i < v.len() || i < 4 {
+        let _ = v[i];
+        //~^ indexing_slicing
+    }
+}
+
+fn main() {}
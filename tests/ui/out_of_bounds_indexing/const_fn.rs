@@ -0,0 +1,61 @@
+#![warn(clippy::out_of_bounds_indexing)]
+#![allow(clippy::no_effect, clippy::unnecessary_operation)]
+
+struct Bounds {
+    start: usize,
+    end: usize,
+}
+
+const fn double(x: usize) -> usize {
+    x * 2
+}
+
+const fn last(len: usize) -> usize {
+    double(len) - len - 1
+}
+
+const fn end(start: usize, len: usize) -> usize {
+    Bounds {
+        start,
+        end: start + len,
+    }
+    .end
+}
+
+const fn start(start: usize, len: usize) -> usize {
+    Bounds {
+        start,
+        end: start + len,
+    }
+    .start
+}
+
+const fn word_size() -> usize {
+    std::mem::size_of::<usize>()
+}
+
+const fn debug_len() -> usize {
+    if cfg!(debug_assertions) { 8 } else { 2 }
+}
+
+fn main() {
+    let x = [1, 2, 3, 4];
+
+    x[double(2)];
+    //~^ out_of_bounds_indexing
+    x[double(1)]; // Ok, should not produce stderr.
+    x[last(5)];
+    //~^ out_of_bounds_indexing
+    x[last(4)]; // Ok, should not produce stderr.
+    x[end(1, 4)];
+    //~^ out_of_bounds_indexing
+    x[start(1, 4)]; // Ok, should not produce stderr.
+    x["abcd".len()];
+    //~^ out_of_bounds_indexing
+    &x[double(1)..double(3)];
+    //~^ out_of_bounds_indexing
+
+    // Ok, the results depend on the target or the configuration.
+    x[word_size()];
+    x[debug_len()];
+}
@@ -0,0 +1,48 @@
+#![warn(clippy::shell_command_injection)]
+#![allow(clippy::needless_borrows_for_generic_args, clippy::useless_format)]
+
+use std::process::Command;
+
+fn main() {
+    let file = "notes.txt";
+
+    Command::new("sh").arg("-c").arg(format!("wc -l {file}"));
+    //~^ shell_command_injection
+    Command::new("/bin/bash").arg("-c").arg(&format!("cat {}", file));
+    //~^ shell_command_injection
+    Command::new("cmd").arg("/C").arg(format!("type {file}"));
+    //~^ shell_command_injection
+    Command::new("powershell.exe")
+        .arg("-Command")
+        .arg(format!("Get-Content {file}"));
+    //~^ shell_command_injection
+    Command::new("sh").args(["-c", &format!("wc -l {file}")]);
+    //~^ shell_command_injection
+    Command::new("sh").arg("-c").args([format!("wc -l {file}")]);
+    //~^ shell_command_injection
+    Command::new("sh")
+        .env("LC_ALL", "C")
+        .arg("-c")
+        .arg(String::from("wc -l ") + file);
+    //~^ shell_command_injection
+
+    let script = format!("wc -l {file}");
+    Command::new("bash").arg("-c").arg(&script);
+    //~^ shell_command_injection
+    Command::new("bash").arg("-c").arg(script.as_str());
+    //~^ shell_command_injection
+
+    // Not a shell
+    Command::new("wc").arg("-c").arg(format!("{file}"));
+    // The formatted string is not the script
+    Command::new("sh")
+        .arg("-c")
+        .arg("wc -l \"$0\"")
+        .arg(format!("./{file}"));
+    Command::new("sh").arg(format!("./{file}.sh"));
+    // Nothing is formatted into the script
+    Command::new("sh").arg("-c").arg("wc -l notes.txt");
+    Command::new("sh").arg("-c").arg(format!("wc -l notes.txt"));
+    // Separate arguments
+    Command::new("wc").arg("-l").arg(file);
+}
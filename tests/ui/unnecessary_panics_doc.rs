@@ -0,0 +1,163 @@
+//@no-rustfix
+#![warn(clippy::unnecessary_panics_doc)]
+
+fn main() {}
+
+/// # Panics
+///
+/// Never
+pub fn cannot_panic(x: Option<u32>) -> u32 {
+    //~^ unnecessary_panics_doc
+    if let Some(x) = x
+        && x > 10
+    {
+        x
+    } else {
+        0
+    }
+}
+
+/// # Panics
+///
+/// Calls into another crate which may panic.
+pub fn external_unwrap_or(x: Option<u32>) -> u32 {
+    x.unwrap_or_default()
+}
+
+fn helper(flag: bool) -> bool {
+    !flag
+}
+
+/// # Panics
+///
+/// Never
+pub fn calls_local(flag: bool) -> bool {
+    //~^ unnecessary_panics_doc
+    helper(flag)
+}
+
+/// # Panics
+///
+/// Panics if `x` is `None`.
+pub fn unwraps(x: Option<u32>) -> u32 {
+    x.unwrap()
+}
+
+fn panics(x: Option<u32>) -> u32 {
+    x.expect("no value")
+}
+
+/// # Panics
+///
+/// Panics if `x` is `None`.
+pub fn calls_panicking(x: Option<u32>) -> u32 {
+    panics(x)
+}
+
+/// # Panics
+///
+/// Panics if the values overflow.
+pub fn arithmetic(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+/// # Panics
+///
+/// Panics if `i` is out of bounds.
+pub fn index(v: &[u32], i: usize) -> u32 {
+    v[i]
+}
+
+/// # Panics
+///
+/// Calls into another crate which may panic.
+pub fn external(v: &mut Vec<u32>) {
+    v.push(1);
+}
+
+fn ping(n: u32) -> bool {
+    if n == 0 { true } else { pong(n) }
+}
+
+fn pong(n: u32) -> bool {
+    n != 1 && ping(0)
+}
+
+/// # Panics
+///
+/// Never
+pub fn recursive(n: u32) -> bool {
+    //~^ unnecessary_panics_doc
+    ping(n)
+}
+
+pub struct Checked(pub u32);
+
+impl std::ops::Add for Checked {
+    type Output = Checked;
+
+    fn add(self, other: Checked) -> Checked {
+        Checked(self.0.checked_add(other.0).expect("overflow"))
+    }
+}
+
+impl std::ops::AddAssign for Checked {
+    fn add_assign(&mut self, other: Checked) {
+        self.0 = self.0.checked_add(other.0).expect("overflow");
+    }
+}
+
+/// # Panics
+///
+/// Panics if the values overflow.
+pub fn overloaded_add(a: Checked, b: Checked) -> Checked {
+    a + b
+}
+
+/// # Panics
+///
+/// Panics if the values overflow.
+pub fn overloaded_add_assign(a: &mut Checked, b: Checked) {
+    *a += b;
+}
+
+pub struct Bits(pub u32);
+
+impl std::ops::BitOr for Bits {
+    type Output = Bits;
+
+    fn bitor(self, other: Bits) -> Bits {
+        Bits(self.0 | other.0)
+    }
+}
+
+/// # Panics
+///
+/// Never
+pub fn overloaded_bitor(a: Bits, b: Bits) -> Bits {
+    //~^ unnecessary_panics_doc
+    a | b
+}
+
+pub struct PanicOnDrop;
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("dropped");
+    }
+}
+
+/// # Panics
+///
+/// Panics when `d` is dropped.
+pub fn drops_local(d: PanicOnDrop) {
+    let _x = d;
+}
+
+/// # Panics
+///
+/// Never
+pub fn drops_string(s: String) {
+    //~^ unnecessary_panics_doc
+    let _x = s;
+}
@@ -0,0 +1,60 @@
+//@revisions: remove expect
+//@[remove] rustc-env:CLIPPY_ARGS=--unused-allows
+//@[expect] rustc-env:CLIPPY_ARGS=--unused-allows=expect
+#![warn(clippy::unused_clippy_allow, clippy::manual_let_else)]
+// The attributes converted to `#[expect]` are unfulfilled
+#![allow(unfulfilled_lint_expectations)]
+#![allow(clippy::needless_return)]
+//~^ unused_clippy_allow
+
+#[allow(clippy::needless_return)]
+fn used() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::redundant_clone)]
+//~^ unused_clippy_allow
+fn unused() {}
+
+#[allow(clippy::redundant_clone, reason = "no longer needed")]
+//~^ unused_clippy_allow
+fn unused_with_reason() {}
+
+#[allow(dead_code, clippy::redundant_clone, clippy::needless_return)]
+//~^ unused_clippy_allow
+fn partially_used() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::unused_clippy_allow, clippy::redundant_clone)]
+fn self_allowed() {}
+
+#[rustfmt::skip]
+fn not_on_own_line() {
+    #[allow(clippy::redundant_clone)] let _x = 1;
+    //~^ unused_clippy_allow
+}
+
+// `manual_let_else` checks if `question_mark` is allowed, it must see the same level as without
+// `--unused-allows`
+#[allow(clippy::question_mark)]
+fn allowed_for_other_lint(x: Option<u32>) -> Option<u32> {
+    let v = if let Some(v) = x { v } else { return None };
+    //~^ manual_let_else
+    Some(v)
+}
+
+// Lint groups are not checked
+#[allow(clippy::pedantic)]
+fn group_allowed() {}
+
+fn main() {
+    used();
+    unused();
+    unused_with_reason();
+    partially_used();
+    self_allowed();
+    not_on_own_line();
+    allowed_for_other_lint(None);
+    group_allowed();
+}